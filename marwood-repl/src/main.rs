@@ -2,6 +2,7 @@ use marwood::cell::Cell;
 use marwood::lex::scan;
 use marwood::parse::parse;
 use marwood::syntax::ReplHighlighter;
+use marwood::vm::filesystem::{Filesystem, RealFilesystem};
 use marwood::vm::trace::StackTrace;
 use marwood::vm::{SystemInterface, Vm};
 use marwood::{lex, parse};
@@ -49,6 +50,7 @@ impl Highlighter for InputValidator {
 #[derive(Debug)]
 struct ReplSystemInterface {
     term_dimensions: (usize, usize),
+    filesystem: RealFilesystem,
 }
 impl SystemInterface for ReplSystemInterface {
     fn display(&self, cell: &Cell) {
@@ -69,6 +71,10 @@ impl SystemInterface for ReplSystemInterface {
             Err(_) => 0,
         }
     }
+
+    fn filesystem(&self) -> &dyn Filesystem {
+        &self.filesystem
+    }
 }
 
 fn main() {
@@ -85,7 +91,10 @@ fn main() {
         Some((cols, rows)) => (cols, rows),
         None => (0, 0),
     };
-    vm.set_system_interface(Box::new(ReplSystemInterface { term_dimensions }));
+    vm.set_system_interface(Box::new(ReplSystemInterface {
+        term_dimensions,
+        filesystem: RealFilesystem::new(),
+    }));
     loop {
         let readline = rl.readline_with_initial("> ", (&remaining, ""));
         match readline {
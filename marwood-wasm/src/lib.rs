@@ -5,6 +5,7 @@ use marwood::cell::Cell;
 use marwood::lex;
 use marwood::parse;
 use marwood::syntax::ReplHighlighter;
+use marwood::vm::filesystem::{Filesystem, MemoryFilesystem};
 use marwood::vm::{SystemInterface, Vm};
 use std::borrow::Cow;
 use wasm_bindgen::prelude::*;
@@ -23,8 +24,10 @@ pub struct Marwood {
     hl: ReplHighlighter,
 }
 
-#[derive(Debug)]
-struct WasmSystemInterface {}
+#[derive(Debug, Default)]
+struct WasmSystemInterface {
+    filesystem: MemoryFilesystem,
+}
 impl SystemInterface for WasmSystemInterface {
     fn display(&self, cell: &Cell) {
         display(&format!("{}", cell))
@@ -43,6 +46,10 @@ impl SystemInterface for WasmSystemInterface {
     fn time_utc(&self) -> u64 {
         Date::now() as u64
     }
+
+    fn filesystem(&self) -> &dyn Filesystem {
+        &self.filesystem
+    }
 }
 
 #[wasm_bindgen]
@@ -51,7 +58,7 @@ impl Marwood {
         #[cfg(feature = "console_error_panic_hook")]
         console_error_panic_hook::set_once();
         let mut vm = Vm::new();
-        vm.set_system_interface(Box::new(WasmSystemInterface {}));
+        vm.set_system_interface(Box::new(WasmSystemInterface::default()));
         Marwood {
             vm,
             hl: ReplHighlighter::new(),
@@ -26,10 +26,8 @@
        ((lambda (name ...) body1 body2 ...)
         val ...))
     ((let tag ((name val) ...) body1 body2 ...)
-       ((letrec ((tag (lambda (name ...)
-               body1 body2 ...)))
-         tag)
-       val ...))))
+       (((lambda (tag) (set! tag (lambda (name ...) body1 body2 ...)) tag) #f)
+        val ...))))
 
 (define-syntax or
   (syntax-rules ()
@@ -74,6 +72,30 @@
             (let* ((name2 val2) ...)
               body1 body2 ...)))))
 
+(define-syntax receive
+  (syntax-rules ()
+    ((receive formals expression body1 body2 ...)
+     (call-with-values (lambda () expression)
+       (lambda formals body1 body2 ...)))))
+
+(define-syntax let*-values
+  (syntax-rules ()
+    ((let*-values () body1 body2 ...)
+     (let () body1 body2 ...))
+    ((let*-values ((formals init) binding ...) body1 body2 ...)
+     (call-with-values (lambda () init)
+       (lambda formals
+         (let*-values (binding ...) body1 body2 ...))))))
+
+(define-syntax let-values
+  (syntax-rules ()
+    ((let-values ((formals init) ...) body1 body2 ...)
+     (let ((let-values-list (list (call-with-values (lambda () init) list) ...)))
+       (let*-values ((formals (let ((vals (car let-values-list)))
+                                (set! let-values-list (cdr let-values-list))
+                                (apply values vals))) ...)
+         body1 body2 ...)))))
+
 (define-syntax cond
       (syntax-rules (else =>)
         ((cond (else result1 result2 ...))
@@ -132,6 +154,80 @@
          (begin result1 result2 ...)
          (case key clause clauses ...)))))
 
+;; do-aux normalizes each (var init) binding into (var init var), so that
+;; every binding has a step
+(define-syntax do
+  (syntax-rules ()
+    ((do bindings (test expr ...) command ...)
+     (do-aux bindings () (test expr ...) command ...))))
+
+(define-syntax do-aux
+  (syntax-rules ()
+    ((do-aux () ((var init step) ...) (test expr ...) command ...)
+     (let loop ((var init) ...)
+       (if test
+           (begin (if #f #f) expr ...)
+           (begin command ... (loop step ...)))))
+    ((do-aux ((var init) binding ...) (normalized ...) clause command ...)
+     (do-aux (binding ...) (normalized ... (var init var)) clause command ...))
+    ((do-aux ((var init step) binding ...) (normalized ...) clause command ...)
+     (do-aux (binding ...) (normalized ... (var init step)) clause command ...))))
+
+(define-syntax guard
+  (syntax-rules ()
+    ((guard (var clause ...) e1 e2 ...)
+     ((call/cc
+       (lambda (guard-k)
+         (with-exception-handler
+          (lambda (condition)
+            ((call/cc
+              (lambda (handler-k)
+                (guard-k
+                 (lambda ()
+                   (let ((var condition))
+                     (guard-aux
+                      (handler-k
+                       (lambda ()
+                         (raise-continuable condition)))
+                      clause ...))))))))
+          (lambda ()
+            (call-with-values
+             (lambda () e1 e2 ...)
+             (lambda args
+               (guard-k
+                (lambda ()
+                  (apply values args)))))))))))))
+
+(define-syntax guard-aux
+  (syntax-rules (else =>)
+    ((guard-aux reraise (else result1 result2 ...))
+     (begin result1 result2 ...))
+    ((guard-aux reraise (test => result))
+     (let ((temp test))
+       (if temp
+           (result temp)
+           reraise)))
+    ((guard-aux reraise (test => result) clause1 clause2 ...)
+     (let ((temp test))
+       (if temp
+           (result temp)
+           (guard-aux reraise clause1 clause2 ...))))
+    ((guard-aux reraise (test))
+     (or test reraise))
+    ((guard-aux reraise (test) clause1 clause2 ...)
+     (let ((temp test))
+       (if temp
+           temp
+           (guard-aux reraise clause1 clause2 ...))))
+    ((guard-aux reraise (test result1 result2 ...))
+     (if test
+         (begin result1 result2 ...)
+         reraise))
+    ((guard-aux reraise (test result1 result2 ...) clause1 clause2 ...)
+     (if test
+         (begin result1 result2 ...)
+         (guard-aux reraise clause1 clause2 ...)))))
+
 (define (atom? obj)
     (and (not (pair? obj)) (not (vector? obj))))
 
@@ -139,6 +235,30 @@
 (define (cadr obj) (car (cdr obj)))
 (define (cdar obj) (cdr (car obj)))
 (define (cddr obj) (cdr (cdr obj)))
+(define (caaar obj) (car (caar obj)))
+(define (caadr obj) (car (cadr obj)))
+(define (cadar obj) (car (cdar obj)))
+(define (caddr obj) (car (cddr obj)))
+(define (cdaar obj) (cdr (caar obj)))
+(define (cdadr obj) (cdr (cadr obj)))
+(define (cddar obj) (cdr (cdar obj)))
+(define (cdddr obj) (cdr (cddr obj)))
+(define (caaaar obj) (car (caaar obj)))
+(define (caaadr obj) (car (caadr obj)))
+(define (caadar obj) (car (cadar obj)))
+(define (caaddr obj) (car (caddr obj)))
+(define (cadaar obj) (car (cdaar obj)))
+(define (cadadr obj) (car (cdadr obj)))
+(define (caddar obj) (car (cddar obj)))
+(define (cadddr obj) (car (cdddr obj)))
+(define (cdaaar obj) (cdr (caaar obj)))
+(define (cdaadr obj) (cdr (caadr obj)))
+(define (cdadar obj) (cdr (cadar obj)))
+(define (cdaddr obj) (cdr (caddr obj)))
+(define (cddaar obj) (cdr (cdaar obj)))
+(define (cddadr obj) (cdr (cdadr obj)))
+(define (cdddar obj) (cdr (cddar obj)))
+(define (cddddr obj) (cdr (cdddr obj)))
 
 (define (list . l) l)
 
@@ -159,12 +279,6 @@
       ((eqv? (car list) obj) list)
       (else (memv obj (cdr list)))))
 
-(define (member obj list)
-    (cond
-      ((null? list) #f)
-      ((equal? (car list) obj) list)
-      (else (member obj (cdr list)))))
-
 (define (assq obj alist)
     (cond
     ((null? alist) #f)
@@ -179,51 +293,54 @@
           (eqv? (caar alist) obj)) (car alist))
     (else (assv obj (cdr alist)))))
 
-(define (assoc obj alist)
-    (cond
-    ((null? alist) #f)
-    ((and (pair? (car alist))
-          (equal? (caar alist) obj)) (car alist))
-    (else (assoc obj (cdr alist)))))
-
 (define (substring string start end)
     (string-copy string start end))
 
+;; Promises are forced iteratively, following the reference implementation
+;; in R7RS 7.3. Forcing a delay-force promise results in another promise,
+;; whose state the forced promise adopts with promise-update! before forcing
+;; continues in a tail call, so that a chain of delay-force promises is
+;; forced in constant space.
 (define-syntax delay-force
   (syntax-rules ()
     ((delay-force expression)
-     (make-promise #f (lambda () expression)))))
+     (promise-new #f (lambda () expression)))))
 
 (define-syntax delay
-   (syntax-rules ()
-     ((delay expression)
-      (delay-force (make-promise #t expression)))))
-
-(define make-promise
-    (lambda (done? proc)
-      (list (cons done? proc))))
+  (syntax-rules ()
+    ((delay expression)
+     (delay-force (promise-new #t expression)))))
 
 (define (force promise)
-    (if (promise-done? promise)
-        (promise-value promise)
-        (let ((promise* ((promise-value promise))))
-          (unless (promise-done? promise)
-            (promise-update! promise* promise))
-          (force promise))))
-
-(define promise-done?
-    (lambda (x) (car (car x))))
-  (define promise-value
-    (lambda (x) (cdr (car x))))
-  (define promise-update!
-    (lambda (new old)
-      (set-car! (car old) (promise-done? new))
-      (set-cdr! (car old) (promise-value new))
-      (set-car! new (car old))))
+  (cond
+    ((not (promise? promise)) promise)
+    ((promise-done? promise) (promise-value promise))
+    (else
+     (let ((promise* ((promise-value promise))))
+       (unless (promise-done? promise)
+         (promise-update! promise* promise))
+       (force promise)))))
 
 (define (add1 x) (+ x 1))
 (define (sub1 x) (- x 1))
-(define (newline) (display #\newline))
+
+(define (call-with-output-string proc)
+  (let ((port (open-output-string)))
+    (proc port)
+    (get-output-string port)))
+
+(define (call-with-port port proc)
+  (call-with-values
+    (lambda () (proc port))
+    (lambda results
+      (close-port port)
+      (apply values results))))
+
+(define (call-with-input-file file proc)
+  (call-with-port (open-input-file file) proc))
+
+(define (call-with-output-file file proc)
+  (call-with-port (open-output-file file) proc))
 
 (define (any? proc list)
     (and (pair? list)
@@ -256,3 +373,65 @@
                   (for-each-all (map1 cdr xss)) void)))))
 
     (for-each-all xss)))
+
+;; A parameter is a procedure that returns its value when applied to no
+;; arguments. Applied to parameter-key, it returns its converter, and applied
+;; to parameter-key and a value it sets its value without conversion, which
+;; is used by parameterize.
+(define parameter-key (list 'parameter))
+
+(define (make-parameter-procedure get update converter)
+  (lambda args
+    (cond
+      ((null? args) (get))
+      ((not (eq? (car args) parameter-key))
+       (error "parameter applied to arguments" args))
+      ((null? (cdr args)) converter)
+      (else (update (cadr args))))))
+
+(define (make-parameter value . converter)
+  (let* ((converter (if (null? converter) (lambda (x) x) (car converter)))
+         (value (converter value)))
+    (make-parameter-procedure
+      (lambda () value)
+      (lambda (new) (set! value new))
+      converter)))
+
+(define (parameterize-aux params vals thunk)
+  (let ((vals (map (lambda (p v) ((p parameter-key) v)) params vals)))
+    (define (swap!)
+      (let ((previous (map (lambda (p) (p)) params)))
+        (for-each (lambda (p v) (p parameter-key v)) params vals)
+        (set! vals previous)))
+    (dynamic-wind swap! thunk swap!)))
+
+(define-syntax parameterize
+  (syntax-rules ()
+    ((parameterize ((param value) ...) body1 body2 ...)
+     (parameterize-aux (list param ...) (list value ...) (lambda () body1 body2 ...)))))
+
+(define current-input-port
+  (make-parameter-procedure
+    current-input-port
+    set-current-input-port!
+    (lambda (port)
+      (if (input-port? port) port (error "not an input port" port)))))
+
+(define current-output-port
+  (make-parameter-procedure
+    current-output-port
+    set-current-output-port!
+    (lambda (port)
+      (if (output-port? port) port (error "not an output port" port)))))
+
+(define (with-input-from-file file thunk)
+  (call-with-input-file file
+    (lambda (port)
+      (parameterize ((current-input-port port))
+        (thunk)))))
+
+(define (with-output-to-file file thunk)
+  (call-with-output-file file
+    (lambda (port)
+      (parameterize ((current-output-port port))
+        (thunk)))))
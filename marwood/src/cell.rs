@@ -9,6 +9,7 @@ use std::ops::DerefMut;
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub enum Cell {
     Bool(bool),
+    Bytevector(Vec<u8>),
     Char(char),
     Nil,
     Number(Number),
@@ -19,10 +20,18 @@ pub enum Cell {
 
     // Types that exist in VCell, but need Cell representation for
     // printing purposes. These are never created by the lexer/parser.
+    Condition(String),
     Continuation,
+    Environment,
+    Eof,
     Macro,
+    Port,
+    Promise,
     Procedure(Option<String>),
+    Record(String),
+    RecordType(String),
     Undefined,
+    Values(Vec<Cell>),
     Void,
 }
 
@@ -124,6 +133,10 @@ impl Cell {
         matches!(self, Cell::Vector(_))
     }
 
+    pub fn is_bytevector(&self) -> bool {
+        matches!(self, Cell::Bytevector(_))
+    }
+
     pub fn is_list(&self) -> bool {
         if self.is_pair() {
             let mut rest = self.cdr().unwrap();
@@ -174,21 +187,84 @@ impl Cell {
         self.is_symbol_str("unquote")
     }
 
+    pub fn is_unquote_splicing(&self) -> bool {
+        self.is_symbol_str("unquote-splicing")
+    }
+
     pub fn is_define(&self) -> bool {
         self.is_symbol_str("define")
     }
 
+    pub fn is_define_values(&self) -> bool {
+        self.is_symbol_str("define-values")
+    }
+
+    pub fn is_define_record_type(&self) -> bool {
+        self.is_symbol_str("define-record-type")
+    }
+
+    pub fn is_define_syntax(&self) -> bool {
+        self.is_symbol_str("define-syntax")
+    }
+
     pub fn is_lambda(&self) -> bool {
         self.is_symbol_str("lambda")
     }
 
     pub fn is_symbol_str(&self, s: &'static str) -> bool {
         match self.as_symbol() {
-            Some(sym) => sym == s,
+            Some(sym) => unalias(sym) == s,
             _ => false,
         }
     }
 
+    /// New Alias
+    ///
+    /// Return an alias of sym introduced by the macro expansion numbered
+    /// expansion. An alias is a symbol the reader is unable to produce,
+    /// which refers to sym unless it's bound by the expansion itself.
+    ///
+    /// # Arguments
+    /// `sym` - The symbol being aliased
+    /// `expansion` - The number of the macro expansion introducing the alias
+    pub fn new_alias(sym: &str, expansion: usize) -> Cell {
+        Cell::Symbol(format!("{}#{}", sym, expansion))
+    }
+
+    pub fn is_alias(&self) -> bool {
+        match self {
+            Cell::Symbol(sym) => unalias(sym) != sym,
+            _ => false,
+        }
+    }
+
+    /// Unaliased
+    ///
+    /// If this cell is an alias, return the symbol it refers to. Any other
+    /// cell is returned as is.
+    pub fn unaliased(&self) -> Cell {
+        match self {
+            Cell::Symbol(sym) => Cell::Symbol(unalias(sym).into()),
+            cell => cell.clone(),
+        }
+    }
+
+    /// Strip Aliases
+    ///
+    /// Return a copy of this cell with every alias within it replaced by the
+    /// symbol it refers to. This is used for quoted data, where an alias is
+    /// never bound.
+    pub fn strip_aliases(&self) -> Cell {
+        match self {
+            Cell::Symbol(_) => self.unaliased(),
+            Cell::Pair(car, cdr) => Cell::new_pair(car.strip_aliases(), cdr.strip_aliases()),
+            Cell::Vector(vector) => {
+                Cell::Vector(vector.iter().map(|it| it.strip_aliases()).collect())
+            }
+            cell => cell.clone(),
+        }
+    }
+
     /// Is Primitive Symbol
     ///
     /// Return true if the given cell is a primitive symbol (e.g. a built-in
@@ -199,17 +275,22 @@ impl Cell {
     pub fn is_primitive_symbol(&self) -> bool {
         lazy_static! {
             static ref PRIMITIVE_SYMBOLS: HashSet<&'static str> = HashSet::from([
+                "case-lambda",
                 "define",
+                "define-record-type",
+                "define-values",
                 "lambda",
                 "if",
+                "#named-let",
                 "quasiquote",
                 "quote",
                 "set!",
-                "unquote"
+                "unquote",
+                "unquote-splicing"
             ]);
         }
         match self {
-            Cell::Symbol(sym) => PRIMITIVE_SYMBOLS.contains(sym.as_str()),
+            Cell::Symbol(sym) => PRIMITIVE_SYMBOLS.contains(unalias(sym)),
             _ => false,
         }
     }
@@ -382,6 +463,36 @@ impl IntoIterator for Cell {
     }
 }
 
+/// Split Alias
+///
+/// If sym is an alias created by new_alias, return the symbol it aliases
+/// and the number of the expansion that introduced it, removing a single
+/// layer of aliasing.
+///
+/// # Arguments
+/// `sym` - The symbol to split
+pub fn split_alias(sym: &str) -> Option<(&str, usize)> {
+    let (name, expansion) = sym.rsplit_once('#')?;
+    if name.is_empty() || !expansion.bytes().all(|it| it.is_ascii_digit()) {
+        return None;
+    }
+    Some((name, expansion.parse().ok()?))
+}
+
+/// Unalias
+///
+/// Return the symbol an alias created by new_alias refers to, removing
+/// every layer of aliasing. Any other symbol is returned as is.
+///
+/// # Arguments
+/// `sym` - The symbol to unalias
+pub fn unalias(mut sym: &str) -> &str {
+    while let Some((name, _)) = split_alias(sym) {
+        sym = name;
+    }
+    sym
+}
+
 impl Display for Cell {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -427,6 +538,16 @@ impl Display for Cell {
             Cell::Bool(val) => {
                 write!(f, "{}", if *val { "#t" } else { "#f" })
             }
+            Cell::Bytevector(bytevector) => {
+                write!(f, "#u8(")?;
+                for (idx, byte) in bytevector.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", byte)?;
+                }
+                write!(f, ")")
+            }
             Cell::Char(c) => match f.alternate() {
                 false => write!(f, "{}", c),
                 true => write_escaped_char(*c, f),
@@ -479,12 +600,27 @@ impl Display for Cell {
                 }
                 write!(f, ")")
             }
+            Cell::Condition(message) => {
+                write!(f, "#<condition: {}>", message)
+            }
             Cell::Continuation => {
                 write!(f, "#<continuation>")
             }
+            Cell::Environment => {
+                write!(f, "#<environment>")
+            }
             Cell::Macro => {
                 write!(f, "#<macro>")
             }
+            Cell::Eof => {
+                write!(f, "#<eof>")
+            }
+            Cell::Port => {
+                write!(f, "#<port>")
+            }
+            Cell::Promise => {
+                write!(f, "#<promise>")
+            }
             Cell::Procedure(desc) => match desc {
                 Some(desc) => {
                     write!(f, "#<procedure:{}>", desc)
@@ -493,9 +629,28 @@ impl Display for Cell {
                     write!(f, "#<procedure>")
                 }
             },
+            Cell::Record(name) => {
+                write!(f, "#<record {}>", name)
+            }
+            Cell::RecordType(name) => {
+                write!(f, "#<record-type {}>", name)
+            }
             Cell::Undefined => {
                 write!(f, "#<undefined>")
             }
+            Cell::Values(values) => {
+                for (idx, cell) in values.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, " ")?;
+                    }
+                    if f.alternate() {
+                        write!(f, "{:#}", cell)?;
+                    } else {
+                        write!(f, "{}", cell)?;
+                    }
+                }
+                Ok(())
+            }
             Cell::Void => {
                 write!(f, "#<void>")
             }
@@ -693,6 +848,8 @@ mod tests {
         assert_eq!(format!("{}", cons!("foo", "bar")), "(foo . bar)");
         assert_eq!(format!("{}", cons!(1, cons!(2, 3))), "(1 2 . 3)");
         assert_eq!(format!("{}", cons!(cell!(), 42)), "(() . 42)");
+        assert_eq!(format!("{}", Cell::Bytevector(vec![])), "#u8()");
+        assert_eq!(format!("{}", Cell::Bytevector(vec![1, 255])), "#u8(1 255)");
         assert_eq!(format!("{}", list!["quote", list![1, 2]]), "'(1 2)");
         assert_eq!(
             format!("{}", list!["quote", cons!["quote", 1]]),
@@ -720,4 +877,21 @@ mod tests {
         assert_eq!(list![1, 2, 3].car(), Some(&cell![1]));
         assert_eq!(list![1, 2, 3].cdr(), Some(&list![2, 3]));
     }
+
+    #[test]
+    fn aliases() {
+        let alias = Cell::new_alias("temp", 3);
+        assert!(alias.is_alias());
+        assert_eq!(alias.unaliased(), cell!["temp"]);
+        assert_eq!(Cell::new_alias("temp#3", 12).unaliased(), cell!["temp"]);
+        assert!(Cell::new_alias("if", 1).is_symbol_str("if"));
+        assert!(Cell::new_alias("if", 1).is_primitive_symbol());
+        assert!(!cell!["#values0"].is_alias());
+        assert!(!cell!["a#"].is_alias());
+        assert!(!cell!["a#b"].is_alias());
+        assert_eq!(
+            list!["quote", list![alias.clone(), 1]].strip_aliases(),
+            list!["quote", list!["temp", 1]]
+        );
+    }
 }
@@ -1,7 +1,7 @@
 use crate::cell::Cell;
 use crate::{lex, parse};
 
-#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
 pub enum Error {
     #[error("{}", .0.iter().map(|it| it.to_string()).collect::<Vec<_>>().join(" "))]
     ErrorSignal(Vec<Cell>),
@@ -39,18 +39,33 @@ pub enum Error {
     #[error("misplaced macro keyword {0}")]
     MisplacedMacroKeyword(String),
 
+    #[error("uncaught exception: {0:#}")]
+    UncaughtException(Cell),
+
     #[error("{0} is not bound")]
     VariableNotBound(String),
 
     #[error("invalid syntax: () must be quoted")]
     UnquotedNil,
 
-    #[error("vector index {0} out of range of 0..{}", .1 - 1)]
+    #[error("vector index {0} out of range of 0..{}", .1.saturating_sub(1))]
     InvalidVectorIndex(usize, usize),
 
-    #[error("string index {0} out of range of 0..{}", .1 - 1)]
+    #[error("string index {0} out of range of 0..{}", .1.saturating_sub(1))]
     InvalidStringIndex(usize, usize),
 
+    #[error("bytevector index {0} out of range of 0..{}", .1.saturating_sub(1))]
+    InvalidBytevectorIndex(usize, usize),
+
+    #[error("{1}: {0}")]
+    FileError(String, String),
+
+    #[error("library {0} not found")]
+    LibraryNotFound(String),
+
+    #[error("{0}: {1}")]
+    LoadError(String, Box<Error>),
+
     #[error(transparent)]
     ParseError(#[from] parse::Error),
 
@@ -1,3 +1,4 @@
+use crate::number::Number;
 use std::iter::Peekable;
 use std::str::CharIndices;
 
@@ -20,8 +21,10 @@ pub enum TokenType {
     Symbol,
     True,
     Unquote,
+    UnquoteSplicing,
     WhiteSpace,
     HashParen,
+    HashU8Paren,
 }
 
 /// Token
@@ -75,7 +78,7 @@ impl Token {
 /// Error Type
 ///
 /// The type of error encountered by the scanner.
-#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
 pub enum Error {
     #[error("incomplete")]
     Incomplete,
@@ -111,16 +114,65 @@ pub fn scan(text: &str) -> Result<Vec<Token>, Error> {
     let mut tokens = vec![];
     let mut cur = text.char_indices().peekable();
 
+    while let Some(token) = scan_token(&mut cur, text)? {
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Scan Datum
+///
+/// [`scan_datum`] is like [`scan`], but stops scanning once the tokens
+/// of the first datum in `text` have been scanned. Any text following
+/// the datum is left unscanned, so it may not produce an error until it
+/// is scanned itself.
+///
+/// # Arguments
+/// `text` - the text to return the first datum's tokens for
+pub fn scan_datum(text: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = vec![];
+    let mut cur = text.char_indices().peekable();
+    let mut depth = 0_usize;
+
+    while let Some(token) = scan_token(&mut cur, text)? {
+        match token.token_type {
+            TokenType::LeftParen | TokenType::HashParen | TokenType::HashU8Paren => depth += 1,
+            TokenType::RightParen => depth = depth.saturating_sub(1),
+            TokenType::SingleQuote
+            | TokenType::Quasiquote
+            | TokenType::Unquote
+            | TokenType::UnquoteSplicing
+            | TokenType::NumberPrefix => {
+                tokens.push(token);
+                continue;
+            }
+            _ => {}
+        }
+        tokens.push(token);
+        if depth == 0 {
+            break;
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Scan Token
+///
+/// Scan the next token from `cur`, skipping any whitespace and comments
+/// preceding it. None is returned if the end of the text is reached.
+fn scan_token(cur: &mut Peekable<CharIndices>, text: &str) -> Result<Option<Token>, Error> {
     while let Some(&(_, c)) = cur.peek() {
-        tokens.push(match c {
-            '(' | ')' | '[' | ']' | '{' | '}' | '\'' | '`' | ',' => scan_simple_token(&mut cur)?,
-            '#' => scan_hash_token(&mut cur)?,
-            '.' => scan_dot(&mut cur)?,
-            '"' => scan_string(&mut cur)?,
-            _ if is_initial_identifier(c) => scan_symbol(&mut cur)?,
-            _ if is_initial_number(c) => scan_number(&mut cur)?,
+        return Ok(Some(match c {
+            '(' | ')' | '[' | ']' | '{' | '}' | '\'' | '`' | ',' => scan_simple_token(cur)?,
+            '#' => scan_hash_token(cur)?,
+            '.' => scan_dot(cur)?,
+            '"' => scan_string(cur)?,
+            _ if is_initial_identifier(c) => scan_symbol(cur)?,
+            _ if is_initial_number(c) => scan_number(cur, text)?,
             ';' => {
-                scan_comment(&mut cur)?;
+                scan_comment(cur)?;
                 continue;
             }
             _ if c.is_whitespace() => {
@@ -128,10 +180,10 @@ pub fn scan(text: &str) -> Result<Vec<Token>, Error> {
                 continue;
             }
             _ => return Err(Error::UnexpectedToken(c)),
-        });
+        }));
     }
 
-    Ok(tokens)
+    Ok(None)
 }
 
 /// Scan Comment
@@ -188,6 +240,12 @@ fn scan_dot(cur: &mut Peekable<CharIndices>) -> Result<Token, Error> {
 
 fn scan_simple_token(cur: &mut Peekable<CharIndices>) -> Result<Token, Error> {
     let (start, c) = cur.next().unwrap();
+    if c == ',' {
+        if let Some(&(_, '@')) = cur.peek() {
+            cur.next();
+            return Ok(Token::new((start, start + 2), TokenType::UnquoteSplicing));
+        }
+    }
     Ok(Token::new(
         (start, start + c.len_utf8()),
         match c {
@@ -213,7 +271,8 @@ fn scan_hash_token(cur: &mut Peekable<CharIndices>) -> Result<Token, Error> {
         't' => Ok(Token::new((start, start + 2), TokenType::True)),
         'f' => Ok(Token::new((start, start + 2), TokenType::False)),
         '(' => Ok(Token::new((start, start + 2), TokenType::HashParen)),
-        'e' | 'i' | 'b' | 'o' | 'd' | 'x' => {
+        'u' => scan_bytevector_prefix(cur, start),
+        'e' | 'i' | 'b' | 'o' | 'd' | 'x' | 'E' | 'I' | 'B' | 'O' | 'D' | 'X' => {
             Ok(Token::new((start, start + 2), TokenType::NumberPrefix))
         }
         '\\' => scan_char(cur, start),
@@ -221,6 +280,27 @@ fn scan_hash_token(cur: &mut Peekable<CharIndices>) -> Result<Token, Error> {
     }
 }
 
+/// Scan Bytevector Prefix
+///
+/// Scan the remainder of the bytevector prefix #u8(, having already
+/// advanced the cursor past #u.
+///
+/// # Arguments
+/// `cur` - The cursor, pointing at the character following #u
+/// `start` - The start of the token
+fn scan_bytevector_prefix(cur: &mut Peekable<CharIndices>, start: usize) -> Result<Token, Error> {
+    for expected in ['8', '('] {
+        match cur.next() {
+            Some((_, c)) if c == expected => {}
+            Some((_, c)) => {
+                return Err(Error::UnexpectedCharacterFollowing("#u".into(), c.into()));
+            }
+            None => return Err(Error::Incomplete),
+        }
+    }
+    Ok(Token::new((start, start + 4), TokenType::HashU8Paren))
+}
+
 fn scan_symbol(cur: &mut Peekable<CharIndices>) -> Result<Token, Error> {
     let start = cur.peek().unwrap().0;
     let mut end = start;
@@ -297,7 +377,16 @@ fn scan_char(cur: &mut Peekable<CharIndices>, start: usize) -> Result<Token, Err
     Ok(Token::new((start, end), TokenType::Char))
 }
 
-fn scan_number(cur: &mut Peekable<CharIndices>) -> Result<Token, Error> {
+/// Scan Number
+///
+/// Scan a token beginning with a digit or sign. The token is downgraded
+/// to a symbol if it contains characters that can't appear in a number,
+/// unless the whole token is a number such as +inf.0 or -nan.0.
+///
+/// # Arguments
+/// `cur` - The cursor, pointing at the first character of the token
+/// `text` - The text backed by the cursor
+fn scan_number(cur: &mut Peekable<CharIndices>, text: &str) -> Result<Token, Error> {
     let start = cur.peek().unwrap().0;
     let mut end = start;
     let mut token_type = TokenType::Number;
@@ -312,6 +401,9 @@ fn scan_number(cur: &mut Peekable<CharIndices>) -> Result<Token, Error> {
         end = offset + c.len_utf8();
         cur.next();
     }
+    if token_type == TokenType::Symbol && Number::parse(&text[start..end], 10).is_some() {
+        token_type = TokenType::Number;
+    }
     Ok(Token::new((start, end), token_type))
 }
 
@@ -320,7 +412,14 @@ pub fn is_initial_number(c: char) -> bool {
 }
 
 pub fn is_subsequent_number(c: char) -> bool {
-    c.is_ascii_digit() || c.is_ascii_hexdigit() || c == '.' || c == '/'
+    c.is_ascii_digit()
+        || c.is_ascii_hexdigit()
+        || c == '.'
+        || c == '/'
+        || c == '+'
+        || c == '-'
+        || c == '@'
+        || c == 'i'
 }
 
 pub fn is_initial_identifier(c: char) -> bool {
@@ -389,10 +488,39 @@ mod tests {
         lexes! {
             "(" => TokenType::LeftParen,
             ")" => TokenType::RightParen,
-            "#(" => TokenType::HashParen
+            "#(" => TokenType::HashParen,
+            "#u8(" => TokenType::HashU8Paren
         };
     }
 
+    #[test]
+    fn datum() {
+        let text = "'(a #(b)) #z";
+        assert_eq!(
+            expand(scan_datum(text).unwrap(), text),
+            [
+                ("'", TokenType::SingleQuote),
+                ("(", TokenType::LeftParen),
+                ("a", TokenType::Symbol),
+                ("#(", TokenType::HashParen),
+                ("b", TokenType::Symbol),
+                (")", TokenType::RightParen),
+                (")", TokenType::RightParen)
+            ]
+        );
+        assert_eq!(
+            expand(scan_datum("#x10 \"").unwrap(), "#x10 \""),
+            [("#x", TokenType::NumberPrefix), ("10", TokenType::Number)]
+        );
+        assert_eq!(
+            scan_datum("#z 1"),
+            Err(Error::UnexpectedCharacterFollowing(
+                "#".to_string(),
+                "z".to_string()
+            ))
+        );
+    }
+
     #[test]
     fn symbols() {
         lexes! {
@@ -453,7 +581,17 @@ mod tests {
             "10..5" => TokenType::Number,
             "-42" => TokenType::Number,
             "+42" => TokenType::Number,
-            "-10.5" => TokenType::Number
+            "-10.5" => TokenType::Number,
+            "1+2i" => TokenType::Number,
+            "-2.5-i" => TokenType::Number,
+            "+i" => TokenType::Number,
+            "1@2" => TokenType::Number,
+            "+inf.0" => TokenType::Number,
+            "-inf.0" => TokenType::Number,
+            "+nan.0" => TokenType::Number,
+            "1-inf.0i" => TokenType::Number,
+            "+inf.0x" => TokenType::Symbol,
+            "-inf" => TokenType::Symbol
         };
     }
 
@@ -504,7 +642,9 @@ mod tests {
 
         fails! {
             "#" => Error::UnexpectedCharacterFollowing('#'.into(), "\\n".into()),
-            "#p" => Error::UnexpectedCharacterFollowing('#'.into(), "p".into())
+            "#p" => Error::UnexpectedCharacterFollowing('#'.into(), "p".into()),
+            "#u" => Error::Incomplete,
+            "#u16(" => Error::UnexpectedCharacterFollowing("#u".into(), "1".into())
         };
     }
 
@@ -519,5 +659,18 @@ mod tests {
             ("2", TokenType::Number),
             (")", TokenType::RightParen)
         };
+        lexes! {
+            "`(1 ,@(2) ,@x)" =>
+            ("`", TokenType::Quasiquote),
+            ("(", TokenType::LeftParen),
+            ("1", TokenType::Number),
+            (",@", TokenType::UnquoteSplicing),
+            ("(", TokenType::LeftParen),
+            ("2", TokenType::Number),
+            (")", TokenType::RightParen),
+            (",@", TokenType::UnquoteSplicing),
+            ("x", TokenType::Symbol),
+            (")", TokenType::RightParen)
+        };
     }
 }
@@ -1,9 +1,10 @@
 use num::bigint::BigInt;
+use num::complex::Complex64;
 use num::traits::FloatConst;
 use num::{
     BigRational, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, FromPrimitive, Rational64, Signed,
 };
-use num::{Num, Rational32, ToPrimitive};
+use num::{Num, One, Rational32, ToPrimitive, Zero};
 use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::{Binary, Formatter, LowerHex, Octal};
@@ -11,6 +12,11 @@ use std::hash::{Hash, Hasher};
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, Sub};
 use std::rc::Rc;
 
+/// The largest exponent magnitude of an exact decimal such as #e1e400. The
+/// value is computed in full, so an unbounded exponent would let a short
+/// literal exhaust time and memory.
+const MAX_EXACT_EXPONENT: i32 = 4096;
+
 /// Exactness
 ///
 /// Exactness represents preferred exactness when parsing
@@ -46,13 +52,35 @@ pub enum Exactness {
 /// Rationals are provided by the Rational32 type, allowing
 /// for a rational composed of a 32 bit numerator and denominator.
 /// If a rational exceeds these limits, the resulting operation
-/// falls back to "inexact" (float).
+/// is promoted to a BigRational, which is demoted back to a
+/// Rational32 once it fits.
+///
+/// Complex numbers are composed of a real and imaginary part, each of
+/// which is one of the real number types above.
 #[derive(Clone, Debug)]
 pub enum Number {
     Fixnum(i64),
     Float(f64),
     BigInt(Rc<BigInt>),
     Rational(Rational32),
+    BigRational(Rc<BigRational>),
+    Complex(Rc<Complex>),
+}
+
+/// Complex
+///
+/// A complex number with a non-zero imaginary part. Complex numbers are
+/// constructed with Number::new_complex(), which keeps the following
+/// invariants:
+///
+/// * A complex number with an exact zero imaginary part is real, and is
+///   represented by its real part alone.
+/// * A complex number is either exact or inexact as a whole. If either
+///   part is inexact, then both parts are inexact.
+#[derive(Clone, Debug)]
+pub struct Complex {
+    pub real: Number,
+    pub imag: Number,
 }
 
 impl Number {
@@ -60,42 +88,243 @@ impl Number {
         Number::BigInt(Rc::new(num.into()))
     }
 
+    /// New Complex
+    ///
+    /// Create a complex number from its real and imaginary parts. If the
+    /// imaginary part is an exact zero, then the real part is returned.
+    ///
+    /// # Arguments
+    /// `real` - The real part
+    /// `imag` - The imaginary part
+    pub fn new_complex(real: Number, imag: Number) -> Number {
+        if imag.is_exact() && imag.is_zero() {
+            real
+        } else if real.is_exact() && imag.is_exact() {
+            Number::Complex(Rc::new(Complex { real, imag }))
+        } else {
+            Number::Complex(Rc::new(Complex {
+                real: real.to_inexact().unwrap(),
+                imag: imag.to_inexact().unwrap(),
+            }))
+        }
+    }
+
+    /// New Polar
+    ///
+    /// Create a complex number from its magnitude and angle. The result
+    /// is inexact unless the angle is an exact zero.
+    ///
+    /// # Arguments
+    /// `magnitude` - The magnitude
+    /// `angle` - The angle, in radians
+    pub fn new_polar(magnitude: &Number, angle: &Number) -> Option<Number> {
+        if angle.is_exact() && angle.is_zero() {
+            return Some(magnitude.clone());
+        }
+        let (magnitude, angle) = (magnitude.to_f64()?, angle.to_f64()?);
+        Some(Number::new_complex(
+            (magnitude * angle.cos()).into(),
+            (magnitude * angle.sin()).into(),
+        ))
+    }
+
+    /// Parse Prefixed
+    ///
+    /// Parse the text, which may begin with at most one exactness prefix
+    /// (#e or #i) and at most one radix prefix (#b, #o, #d or #x) in either
+    /// order. A radix prefix overrides the default radix.
+    ///
+    /// # Arguments
+    /// `text` - The text to parse
+    /// `radix` - The radix to use if the text has no radix prefix
+    pub fn parse_prefixed(mut text: &str, mut radix: u32) -> Option<Number> {
+        let mut exactness = None;
+        let mut radix_prefix = false;
+        while let Some(rest) = text.strip_prefix('#') {
+            match rest.chars().next()?.to_ascii_lowercase() {
+                'e' if exactness.is_none() => exactness = Some(Exactness::Exact),
+                'i' if exactness.is_none() => exactness = Some(Exactness::Inexact),
+                c @ ('b' | 'o' | 'd' | 'x') if !radix_prefix => {
+                    radix = match c {
+                        'b' => 2,
+                        'o' => 8,
+                        'd' => 10,
+                        _ => 16,
+                    };
+                    radix_prefix = true;
+                }
+                _ => return None,
+            }
+            text = &rest[1..];
+        }
+        Self::parse_with_exactness(text, exactness.unwrap_or(Exactness::Unspecified), radix)
+    }
+
     /// Parse
     ///
-    /// Parse the text given the desired exactness and radix.
+    /// Parse the text given the desired exactness and radix. Decimals are
+    /// parsed directly into an exact number when exactness is preferred,
+    /// such that #e1.2 is 6/5.
     pub fn parse_with_exactness(text: &str, exactness: Exactness, radix: u32) -> Option<Number> {
         match exactness {
             Exactness::Unspecified => Self::parse(text, radix),
-            Exactness::Exact => Self::parse(text, radix).map(|num| match num.to_exact() {
-                Some(num) => num,
-                None => num,
-            }),
-            Exactness::Inexact => Self::parse(text, radix)
-                .map(|num| num.to_inexact())
-                .map(|it| it.unwrap()),
+            Exactness::Exact => {
+                let num = Self::parse_number(text, radix, true)?;
+                match num.is_exact() {
+                    true => Some(num),
+                    false => num.to_exact(),
+                }
+            }
+            Exactness::Inexact => Self::parse(text, radix)?.to_inexact(),
         }
     }
 
     pub fn parse(text: &str, radix: u32) -> Option<Number> {
-        if let Ok(num) = i64::from_str_radix(text, radix) {
-            Some(Number::from(num))
-        } else if let Ok(num) = BigInt::from_str_radix(text, radix) {
-            Some(Number::from(num))
-        } else if let Some(num) = Self::parse_rational(text, radix) {
-            Some(num)
-        } else if let Ok(num) = f64::from_str_radix(text, radix) {
-            Some(Number::from(num))
+        Self::parse_number(text, radix, false)
+    }
+
+    fn parse_number(text: &str, radix: u32, exact: bool) -> Option<Number> {
+        if !(2..=36).contains(&radix) {
+            return None;
+        }
+        match Self::parse_real(text, radix, exact) {
+            Some(num) => Some(num),
+            None => Self::parse_complex(text, radix, exact),
+        }
+    }
+
+    /// Parse Real
+    ///
+    /// Parse the text as a real number according to the R7RS grammar: an
+    /// optionally signed integer, rational or decimal, or one of +inf.0,
+    /// -inf.0, +nan.0 or -nan.0. Decimals may have an exponent only in
+    /// radix 10.
+    ///
+    /// # Arguments
+    /// `text` - The text to parse
+    /// `radix` - The radix of the digits
+    /// `exact` - If true, parse decimals into an exact number
+    fn parse_real(text: &str, radix: u32, exact: bool) -> Option<Number> {
+        match text.to_ascii_lowercase().as_str() {
+            "+inf.0" => return Some(f64::INFINITY.into()),
+            "-inf.0" => return Some(f64::NEG_INFINITY.into()),
+            "+nan.0" | "-nan.0" => return Some(f64::NAN.into()),
+            _ => {}
+        }
+
+        let unsigned = text.strip_prefix(['+', '-']).unwrap_or(text);
+        let is_digits = |text: &str| !text.is_empty() && text.chars().all(|c| c.is_digit(radix));
+        if is_digits(unsigned) {
+            match i64::from_str_radix(text, radix) {
+                Ok(num) => Some(Number::from(num)),
+                Err(_) => BigInt::from_str_radix(text, radix).ok().map(Number::from),
+            }
+        } else if let Some((numer, denom)) = unsigned.split_once('/') {
+            match is_digits(numer) && is_digits(denom) {
+                true => Self::parse_rational(text, radix),
+                false => None,
+            }
         } else {
-            None
+            Self::parse_decimal(text, radix, exact)
         }
     }
 
+    /// Parse Decimal
+    ///
+    /// Parse a decimal such as 1.5, .5, 1. or 1.5e-3. The digits are first
+    /// read into an exact rational, which is converted to a float unless
+    /// exact is true. Radix 10 decimals are parsed as floats directly.
+    ///
+    /// An exact decimal whose exponent exceeds MAX_EXACT_EXPONENT in
+    /// magnitude isn't parsed.
+    fn parse_decimal(text: &str, radix: u32, exact: bool) -> Option<Number> {
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) if radix == 10 => (mantissa, Some(exponent)),
+            _ => (unsigned, None),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if integer.is_empty() && fraction.is_empty()
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_digit(radix))
+        {
+            return None;
+        }
+        let exponent = match exponent {
+            Some(exponent) => {
+                let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+                if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+                exponent.parse::<i32>().ok()?
+            }
+            None => 0,
+        };
+
+        if radix == 10 && !exact {
+            return text.parse::<f64>().ok().map(Number::from);
+        }
+
+        if exponent.unsigned_abs() > MAX_EXACT_EXPONENT as u32 {
+            return None;
+        }
+        let digits = BigInt::from_str_radix(&format!("{}{}", integer, fraction), radix).ok()?;
+        let scale = BigRational::from_integer(BigInt::from(radix)).pow(-(fraction.len() as i32));
+        let scale = scale * BigRational::from_integer(BigInt::from(10)).pow(exponent);
+        let num = BigRational::from_integer(digits) * scale;
+        let num = Number::from(if negative { -num } else { num });
+        match exact {
+            true => Some(num),
+            false => num.to_inexact(),
+        }
+    }
+
+    /// Parse Complex
+    ///
+    /// Parse the text as a complex number in either rectangular form,
+    /// such as 1+2i, -i or +2.5i, or polar form, such as 1@2.
+    pub fn parse_complex(text: &str, radix: u32, exact: bool) -> Option<Number> {
+        if let Some((magnitude, angle)) = text.split_once('@') {
+            return Self::new_polar(
+                &Self::parse_real(magnitude, radix, exact)?,
+                &Self::parse_real(angle, radix, exact)?,
+            );
+        }
+
+        // The imaginary part begins at the last sign that isn't part of
+        // an exponent.
+        let text = text.strip_suffix('i')?;
+        let split = text
+            .char_indices()
+            .rev()
+            .find(|&(i, c)| {
+                (c == '+' || c == '-') && !(radix == 10 && text[..i].ends_with(['e', 'E']))
+            })?
+            .0;
+
+        let real = match &text[..split] {
+            "" => Number::from(0),
+            real => Self::parse_real(real, radix, exact)?,
+        };
+        let imag = match &text[split..] {
+            "+" => Number::from(1),
+            "-" => Number::from(-1),
+            imag => Self::parse_real(imag, radix, exact)?,
+        };
+        Some(Number::new_complex(real, imag))
+    }
+
     /// Parse Rational
     ///
     /// Parse the text according to the given radix. If a 32 bit rational
     /// cannot be constructed, then attempt to construct a BigRational,
     /// converting the resulting BigRational into the most appropriate
-    /// Number type.
+    /// exact Number type.
     pub fn parse_rational(text: &str, radix: u32) -> Option<Number> {
         match Rational32::from_str_radix(text, radix) {
             Ok(num) => {
@@ -106,16 +335,7 @@ impl Number {
                 }
             }
             Err(_) => match BigRational::from_str_radix(text, radix) {
-                Ok(num) => {
-                    if num.is_integer() {
-                        match num.to_i64() {
-                            Some(num) => Some(num.into()),
-                            None => Some(num.to_integer().into()),
-                        }
-                    } else {
-                        Some(num.to_f64().unwrap_or(f64::NAN).into())
-                    }
-                }
+                Ok(num) => Some(num.into()),
                 Err(_) => None,
             },
         }
@@ -173,16 +393,20 @@ impl Number {
             Number::Fixnum(num) => num.to_f64(),
             Number::BigInt(num) => num.to_f64(),
             Number::Rational(num) => num.to_f64(),
+            Number::BigRational(num) => num.to_f64(),
             Number::Float(num) => Some(*num),
+            Number::Complex(_) => None,
         }
     }
 
     pub fn is_integer(&self) -> bool {
         match self {
             Number::Fixnum(_) => true,
-            Number::Float(num) => num.floor() == *num,
+            Number::Float(num) => num.is_finite() && num.floor() == *num,
             Number::BigInt(_) => true,
             Number::Rational(num) => num.is_integer(),
+            Number::BigRational(num) => num.is_integer(),
+            Number::Complex(_) => false,
         }
     }
 
@@ -191,7 +415,7 @@ impl Number {
     }
 
     pub fn is_real(&self) -> bool {
-        true
+        !matches!(self, Number::Complex(_))
     }
 
     pub fn is_rational(&self) -> bool {
@@ -200,9 +424,40 @@ impl Number {
             Number::Float(_) => false,
             Number::BigInt(_) => true,
             Number::Rational(_) => true,
+            Number::BigRational(_) => true,
+            Number::Complex(_) => false,
         }
     }
 
+    pub fn is_exact(&self) -> bool {
+        match self {
+            Number::Fixnum(_) | Number::BigInt(_) | Number::Rational(_) => true,
+            Number::BigRational(_) => true,
+            Number::Float(_) => false,
+            Number::Complex(num) => num.real.is_exact(),
+        }
+    }
+
+    pub fn is_nan(&self) -> bool {
+        match self {
+            Number::Float(num) => num.is_nan(),
+            Number::Complex(num) => num.real.is_nan() || num.imag.is_nan(),
+            _ => false,
+        }
+    }
+
+    pub fn is_infinite(&self) -> bool {
+        match self {
+            Number::Float(num) => num.is_infinite(),
+            Number::Complex(num) => num.real.is_infinite() || num.imag.is_infinite(),
+            _ => false,
+        }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        !self.is_nan() && !self.is_infinite()
+    }
+
     pub fn is_zero(&self) -> bool {
         self == &Number::from(0)
     }
@@ -213,6 +468,11 @@ impl Number {
             Number::Float(num) => Some((*num).into()),
             Number::BigInt(num) => Some(num.to_f64().unwrap().into()),
             Number::Rational(num) => Some(num.to_f64().unwrap().into()),
+            Number::BigRational(num) => Some(num.to_f64().unwrap_or(f64::NAN).into()),
+            Number::Complex(num) => Some(Number::new_complex(
+                num.real.to_inexact()?,
+                num.imag.to_inexact()?,
+            )),
         }
     }
 
@@ -222,16 +482,21 @@ impl Number {
                 if self.is_integer() {
                     match num.to_i64() {
                         Some(integer) => Some(Number::Fixnum(integer)),
-                        None => num.to_i128().map(|num| BigInt::from(num).into()),
+                        None => BigInt::from_f64(*num).map(|num| num.into()),
                     }
                 } else {
                     match Rational32::from_f64(*num) {
                         Some(num) => Some(num.into()),
-                        None => Some((*num).into()),
+                        None => BigRational::from_float(*num).map(|num| num.into()),
                     }
                 }
             }
             Number::BigInt(_) | Number::Rational(_) | Number::Fixnum(_) => Some(self.clone()),
+            Number::BigRational(_) => Some(self.clone()),
+            Number::Complex(num) => Some(Number::new_complex(
+                num.real.to_exact()?,
+                num.imag.to_exact()?,
+            )),
         }
     }
 
@@ -244,6 +509,8 @@ impl Number {
             },
             Number::BigInt(_) => self.clone(),
             Number::Rational(num) => (*num.numer() as i64).into(),
+            Number::BigRational(num) => BigRational::from_integer(num.numer().clone()).into(),
+            Number::Complex(_) => self.clone(),
         }
     }
 
@@ -256,6 +523,8 @@ impl Number {
             },
             Number::BigInt(_) => 1.into(),
             Number::Rational(num) => (*num.denom() as i64).into(),
+            Number::BigRational(num) => BigRational::from_integer(num.denom().clone()).into(),
+            Number::Complex(_) => self.clone(),
         }
     }
 
@@ -265,6 +534,8 @@ impl Number {
             Number::Float(num) => num.abs().into(),
             Number::BigInt(num) => num.abs().into(),
             Number::Rational(num) => num.abs().into(),
+            Number::BigRational(num) => num.abs().into(),
+            Number::Complex(_) => self.magnitude(),
         }
     }
 
@@ -281,6 +552,8 @@ impl Number {
             Number::Float(num) => num.round().into(),
             Number::BigInt(_) => self.clone(),
             Number::Rational(num) => num.round().into(),
+            Number::BigRational(num) => num.round().into(),
+            Number::Complex(num) => Number::new_complex(num.real.round(), num.imag.round()),
         }
     }
 
@@ -290,6 +563,8 @@ impl Number {
             Number::Float(num) => num.floor().into(),
             Number::BigInt(_) => self.clone(),
             Number::Rational(num) => num.floor().into(),
+            Number::BigRational(num) => num.floor().into(),
+            Number::Complex(num) => Number::new_complex(num.real.floor(), num.imag.floor()),
         }
     }
 
@@ -299,6 +574,8 @@ impl Number {
             Number::Float(num) => num.ceil().into(),
             Number::BigInt(_) => self.clone(),
             Number::Rational(num) => num.ceil().into(),
+            Number::BigRational(num) => num.ceil().into(),
+            Number::Complex(num) => Number::new_complex(num.real.ceil(), num.imag.ceil()),
         }
     }
 
@@ -308,6 +585,8 @@ impl Number {
             Number::Float(num) => num.trunc().into(),
             Number::BigInt(_) => self.clone(),
             Number::Rational(num) => num.trunc().into(),
+            Number::BigRational(num) => num.trunc().into(),
+            Number::Complex(num) => Number::new_complex(num.real.truncate(), num.imag.truncate()),
         }
     }
 
@@ -320,12 +599,148 @@ impl Number {
             Number::Float(num) => num.powf(exp as f64).into(),
             Number::BigInt(lhs) => lhs.pow(exp).into(),
             Number::Rational(num) => {
+                match (num.numer().checked_pow(exp), num.denom().checked_pow(exp)) {
+                    (Some(numer), Some(denom)) => Rational32::new_raw(numer, denom).into(),
+                    _ => match self.to_big_rational() {
+                        Some(num) if exp.to_i32().is_some() => num.pow(exp as i32).into(),
+                        _ => num.to_f64().unwrap_or(f64::NAN).powf(exp as f64).into(),
+                    },
+                }
+            }
+            Number::BigRational(num) => {
                 if exp.to_i32().is_some() {
                     num.pow(exp as i32).into()
                 } else {
                     num.to_f64().unwrap_or(f64::NAN).powf(exp as f64).into()
                 }
             }
+            Number::Complex(_) => {
+                let (mut result, mut base, mut exp) = (Number::from(1), self.clone(), exp);
+                while exp > 0 {
+                    if exp & 1 == 1 {
+                        result = &result * &base;
+                    }
+                    base = &base * &base;
+                    exp >>= 1;
+                }
+                result
+            }
+        }
+    }
+
+    /// Expt
+    ///
+    /// Raise this number to the power of exp. Integer powers are computed
+    /// with pow(), real powers of non-negative real numbers with powf(), and
+    /// all other powers as e^(exp * log(self)), which may be complex.
+    ///
+    /// # Arguments
+    /// `exp` - The exponent
+    pub fn expt(&self, exp: &Number) -> Option<Number> {
+        if exp.is_integer() {
+            let result = self.pow(exp.abs().to_u32()?);
+            let result = match *exp < Number::from(0) {
+                true if result.is_zero() => return None,
+                true => &Number::from(1) / &result,
+                false => result,
+            };
+            match exp.is_exact() {
+                true => Some(result),
+                false => result.to_inexact(),
+            }
+        } else if self.is_real() && exp.is_real() && *self >= Number::from(0) {
+            Some(self.to_f64()?.powf(exp.to_f64()?).into())
+        } else {
+            (exp * &self.log()?).exp()
+        }
+    }
+
+    pub fn real_part(&self) -> Number {
+        match self {
+            Number::Complex(num) => num.real.clone(),
+            _ => self.clone(),
+        }
+    }
+
+    pub fn imag_part(&self) -> Number {
+        match self {
+            Number::Complex(num) => num.imag.clone(),
+            _ => Number::from(0),
+        }
+    }
+
+    /// Magnitude
+    ///
+    /// Return the magnitude of this number, which is exact if this number
+    /// is exact and the magnitude is rational.
+    pub fn magnitude(&self) -> Number {
+        match self {
+            Number::Complex(num) => {
+                let square = &(&num.real * &num.real) + &(&num.imag * &num.imag);
+                match square.exact_sqrt() {
+                    Some(magnitude) => magnitude,
+                    None => num
+                        .real
+                        .to_f64()
+                        .unwrap_or(f64::NAN)
+                        .hypot(num.imag.to_f64().unwrap_or(f64::NAN))
+                        .into(),
+                }
+            }
+            _ => self.abs(),
+        }
+    }
+
+    /// Angle
+    ///
+    /// Return the angle of this number in radians, which is an exact zero
+    /// for exact non-negative real numbers.
+    pub fn angle(&self) -> Number {
+        match self {
+            Number::Complex(num) => num
+                .imag
+                .to_f64()
+                .unwrap_or(f64::NAN)
+                .atan2(num.real.to_f64().unwrap_or(f64::NAN))
+                .into(),
+            _ if *self < Number::from(0) => f64::PI().into(),
+            Number::Float(_) => 0.0.into(),
+            _ => Number::from(0),
+        }
+    }
+
+    /// To Big Rational
+    ///
+    /// Convert an exact real number to a BigRational, or return None if
+    /// the number is inexact or complex.
+    fn to_big_rational(&self) -> Option<BigRational> {
+        match self {
+            Number::Fixnum(num) => Some(BigRational::from_integer((*num).into())),
+            Number::BigInt(num) => Some(BigRational::from_integer((**num).clone())),
+            Number::Rational(num) => Some(BigRational::new_raw(
+                (*num.numer()).into(),
+                (*num.denom()).into(),
+            )),
+            Number::BigRational(num) => Some((**num).clone()),
+            Number::Float(_) | Number::Complex(_) => None,
+        }
+    }
+
+    /// Exact Sqrt
+    ///
+    /// Return the exact square root of an exact non-negative rational
+    /// number, or None if the square root is irrational.
+    fn exact_sqrt(&self) -> Option<Number> {
+        let root = |num: Number| -> Option<Number> {
+            let root = Number::from(num.to_f64()?.sqrt().round()).to_exact()?;
+            (&root * &root == num).then_some(root)
+        };
+        match self {
+            Number::Fixnum(_) | Number::BigInt(_) if *self >= Number::from(0) => root(self.clone()),
+            Number::Rational(_) | Number::BigRational(_) if *self >= Number::from(0) => {
+                Some(&root(self.numerator())? / &root(self.denominator())?)
+            }
+            _ => None,
         }
     }
 }
@@ -345,6 +760,8 @@ impl PartialEq for Number {
                         false
                     }
                 }
+                Number::BigRational(_) => big_rational_cmp(self, rhs) == Some(Ordering::Equal),
+                Number::Complex(_) => complex_eq(self, rhs),
             },
             Number::BigInt(lhs) => match rhs {
                 Number::Fixnum(rhs) => **lhs == BigInt::from(*rhs),
@@ -354,6 +771,8 @@ impl PartialEq for Number {
                     Some(lhs) => Rational32::from_integer(lhs) == *rhs,
                     None => false,
                 },
+                Number::BigRational(_) => big_rational_cmp(self, rhs) == Some(Ordering::Equal),
+                Number::Complex(_) => complex_eq(self, rhs),
             },
             Number::Float(lhs) => match rhs {
                 Number::Fixnum(rhs) => *lhs == *rhs as f64,
@@ -363,6 +782,8 @@ impl PartialEq for Number {
                     Some(rhs) => *lhs == rhs,
                     None => false,
                 },
+                Number::BigRational(_) => big_rational_cmp(self, rhs) == Some(Ordering::Equal),
+                Number::Complex(_) => complex_eq(self, rhs),
             },
             Number::Rational(lhs) => match rhs {
                 Number::Fixnum(rhs) => {
@@ -381,7 +802,14 @@ impl PartialEq for Number {
                     None => false,
                 },
                 Number::Rational(rhs) => lhs == rhs,
+                Number::BigRational(_) => big_rational_cmp(self, rhs) == Some(Ordering::Equal),
+                Number::Complex(_) => complex_eq(self, rhs),
             },
+            Number::BigRational(_) => match rhs {
+                Number::Complex(_) => complex_eq(self, rhs),
+                _ => big_rational_cmp(self, rhs) == Some(Ordering::Equal),
+            },
+            Number::Complex(_) => complex_eq(self, rhs),
         }
     }
 }
@@ -400,6 +828,8 @@ impl PartialOrd for Number {
                         Some(Ordering::Greater)
                     }
                 }
+                Number::BigRational(_) => big_rational_cmp(self, rhs),
+                Number::Complex(_) => None,
             },
             Number::BigInt(lhs) => match rhs {
                 Number::Fixnum(rhs) => (**lhs).partial_cmp(&BigInt::from(*rhs)),
@@ -409,12 +839,16 @@ impl PartialOrd for Number {
                     Some(lhs) => Rational32::from_integer(lhs).partial_cmp(rhs),
                     None => Some(Ordering::Greater),
                 },
+                Number::BigRational(_) => big_rational_cmp(self, rhs),
+                Number::Complex(_) => None,
             },
             Number::Float(lhs) => match rhs {
                 Number::Fixnum(rhs) => lhs.partial_cmp(&(*rhs as f64)),
                 Number::Float(rhs) => lhs.partial_cmp(rhs),
                 Number::BigInt(rhs) => lhs.partial_cmp(&(**rhs).to_f64().unwrap()),
                 Number::Rational(rhs) => lhs.partial_cmp(&rhs.to_f64().unwrap()),
+                Number::BigRational(_) => big_rational_cmp(self, rhs),
+                Number::Complex(_) => None,
             },
             Number::Rational(lhs) => match rhs {
                 Number::Fixnum(rhs) => {
@@ -430,11 +864,87 @@ impl PartialOrd for Number {
                     None => Some(Ordering::Less),
                 },
                 Number::Rational(rhs) => lhs.partial_cmp(rhs),
+                Number::BigRational(_) => big_rational_cmp(self, rhs),
+                Number::Complex(_) => None,
+            },
+            Number::BigRational(_) => match rhs {
+                Number::Complex(_) => None,
+                _ => big_rational_cmp(self, rhs),
             },
+            Number::Complex(_) => None,
         }
     }
 }
 
+/// Big Rational Cmp
+///
+/// Compare lhs and rhs as big rationals if both are exact, or as floats
+/// otherwise.
+fn big_rational_cmp(lhs: &Number, rhs: &Number) -> Option<Ordering> {
+    match (lhs.to_big_rational(), rhs.to_big_rational()) {
+        (Some(lhs), Some(rhs)) => lhs.partial_cmp(&rhs),
+        _ => lhs.to_f64()?.partial_cmp(&rhs.to_f64()?),
+    }
+}
+
+/// Big Rational Op
+///
+/// Apply op to lhs and rhs as big rationals if both are exact, or apply
+/// float_op to them as floats otherwise. This is the slow path for
+/// operations involving a BigRational, or rationals that overflow a
+/// Rational32.
+fn big_rational_op(
+    lhs: &Number,
+    rhs: &Number,
+    op: fn(BigRational, BigRational) -> BigRational,
+    float_op: fn(f64, f64) -> f64,
+) -> Number {
+    match (lhs.to_big_rational(), rhs.to_big_rational()) {
+        (Some(lhs), Some(rhs)) => op(lhs, rhs).into(),
+        _ => float_op(
+            lhs.to_f64().unwrap_or(f64::NAN),
+            rhs.to_f64().unwrap_or(f64::NAN),
+        )
+        .into(),
+    }
+}
+
+fn complex_eq(lhs: &Number, rhs: &Number) -> bool {
+    lhs.real_part() == rhs.real_part() && lhs.imag_part() == rhs.imag_part()
+}
+
+fn complex_add(lhs: &Number, rhs: &Number) -> Number {
+    Number::new_complex(
+        &lhs.real_part() + &rhs.real_part(),
+        &lhs.imag_part() + &rhs.imag_part(),
+    )
+}
+
+fn complex_sub(lhs: &Number, rhs: &Number) -> Number {
+    Number::new_complex(
+        &lhs.real_part() - &rhs.real_part(),
+        &lhs.imag_part() - &rhs.imag_part(),
+    )
+}
+
+/// (a + bi)(c + di) = (ac - bd) + (ad + bc)i
+fn complex_mul(lhs: &Number, rhs: &Number) -> Number {
+    let (a, b) = (lhs.real_part(), lhs.imag_part());
+    let (c, d) = (rhs.real_part(), rhs.imag_part());
+    Number::new_complex(&(&a * &c) - &(&b * &d), &(&a * &d) + &(&b * &c))
+}
+
+/// (a + bi)/(c + di) = ((ac + bd) + (bc - ad)i) / (c^2 + d^2)
+fn complex_div(lhs: &Number, rhs: &Number) -> Number {
+    let (a, b) = (lhs.real_part(), lhs.imag_part());
+    let (c, d) = (rhs.real_part(), rhs.imag_part());
+    let divisor = &(&c * &c) + &(&d * &d);
+    Number::new_complex(
+        &(&(&a * &c) + &(&b * &d)) / &divisor,
+        &(&(&b * &c) - &(&a * &d)) / &divisor,
+    )
+}
+
 impl AddAssign for Number {
     fn add_assign(&mut self, rhs: Self) {
         let result = &*self + &rhs;
@@ -466,9 +976,9 @@ impl Add for Number {
 impl Add for &Number {
     type Output = Number;
 
-    fn add(self, rhs: Self) -> Self::Output {
+    fn add(self, other: Self) -> Self::Output {
         match self {
-            Number::Fixnum(lhs) => match rhs {
+            Number::Fixnum(lhs) => match other {
                 Number::Fixnum(rhs) => match lhs.checked_add(rhs) {
                     Some(num) => Number::Fixnum(num),
                     None => (BigInt::from(*lhs) + rhs).into(),
@@ -480,14 +990,16 @@ impl Add for &Number {
                         let lhs_rational = Rational32::from_integer(*lhs as i32);
                         match lhs_rational.checked_add(rhs) {
                             Some(num) => num.into(),
-                            None => (*lhs as f64 + rhs.to_f64().unwrap_or(f64::NAN)).into(),
+                            None => big_rational_op(self, other, Add::add, Add::add),
                         }
                     } else {
-                        (*lhs as f64 + rhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Add::add, Add::add)
                     }
                 }
+                Number::BigRational(_) => big_rational_op(self, other, Add::add, Add::add),
+                Number::Complex(_) => complex_add(self, other),
             },
-            Number::BigInt(lhs) => match rhs {
+            Number::BigInt(lhs) => match other {
                 Number::Fixnum(rhs) => (&**lhs + rhs).into(),
                 Number::BigInt(rhs) => (&**lhs + &**rhs).into(),
                 Number::Float(rhs) => (lhs.to_f64().unwrap() + *rhs).into(),
@@ -495,26 +1007,30 @@ impl Add for &Number {
                     if rhs.is_integer() {
                         (&**lhs + rhs.to_integer()).into()
                     } else {
-                        (lhs.to_f64().unwrap() + rhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Add::add, Add::add)
                     }
                 }
+                Number::BigRational(_) => big_rational_op(self, other, Add::add, Add::add),
+                Number::Complex(_) => complex_add(self, other),
             },
-            Number::Float(lhs) => match rhs {
+            Number::Float(lhs) => match other {
                 Number::Fixnum(rhs) => (*lhs + *rhs as f64).into(),
                 Number::Float(rhs) => (*lhs + *rhs).into(),
                 Number::BigInt(rhs) => (*lhs + rhs.to_f64().unwrap()).into(),
                 Number::Rational(rhs) => (*lhs + rhs.to_f64().unwrap_or(f64::NAN)).into(),
+                Number::BigRational(_) => big_rational_op(self, other, Add::add, Add::add),
+                Number::Complex(_) => complex_add(self, other),
             },
-            Number::Rational(lhs) => match rhs {
+            Number::Rational(lhs) => match other {
                 Number::Fixnum(rhs) => {
                     if rhs.to_i32().is_some() {
                         let rhs_rational = Rational32::from_integer(*rhs as i32);
                         match rhs_rational.checked_add(lhs) {
                             Some(num) => num.into(),
-                            None => (lhs.to_f64().unwrap_or(f64::NAN) + *rhs as f64).into(),
+                            None => big_rational_op(self, other, Add::add, Add::add),
                         }
                     } else {
-                        (lhs.to_f64().unwrap_or(f64::NAN) + *rhs as f64).into()
+                        big_rational_op(self, other, Add::add, Add::add)
                     }
                 }
                 Number::Float(rhs) => (lhs.to_f64().unwrap_or(f64::NAN) + *rhs).into(),
@@ -522,16 +1038,21 @@ impl Add for &Number {
                     if lhs.is_integer() {
                         (&**rhs + lhs.to_integer()).into()
                     } else {
-                        (rhs.to_f64().unwrap() + lhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Add::add, Add::add)
                     }
                 }
                 Number::Rational(rhs) => match lhs.checked_add(rhs) {
                     Some(num) => num.into(),
-                    None => {
-                        (lhs.to_f64().unwrap_or(f64::NAN) + rhs.to_f64().unwrap_or(f64::NAN)).into()
-                    }
+                    None => big_rational_op(self, other, Add::add, Add::add),
                 },
+                Number::BigRational(_) => big_rational_op(self, other, Add::add, Add::add),
+                Number::Complex(_) => complex_add(self, other),
+            },
+            Number::BigRational(_) => match other {
+                Number::Complex(_) => complex_add(self, other),
+                _ => big_rational_op(self, other, Add::add, Add::add),
             },
+            Number::Complex(_) => complex_add(self, other),
         }
     }
 }
@@ -546,9 +1067,9 @@ impl Mul for Number {
 impl Mul for &Number {
     type Output = Number;
 
-    fn mul(self, rhs: Self) -> Self::Output {
+    fn mul(self, other: Self) -> Self::Output {
         match self {
-            Number::Fixnum(lhs) => match rhs {
+            Number::Fixnum(lhs) => match other {
                 Number::Fixnum(rhs) => match lhs.checked_mul(rhs) {
                     Some(num) => Number::Fixnum(num),
                     None => (BigInt::from(*lhs) * rhs).into(),
@@ -560,14 +1081,16 @@ impl Mul for &Number {
                         let lhs_rational = Rational32::from_integer(*lhs as i32);
                         match lhs_rational.checked_mul(rhs) {
                             Some(num) => num.into(),
-                            None => (*lhs as f64 * rhs.to_f64().unwrap_or(f64::NAN)).into(),
+                            None => big_rational_op(self, other, Mul::mul, Mul::mul),
                         }
                     } else {
-                        (*lhs as f64 * rhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Mul::mul, Mul::mul)
                     }
                 }
+                Number::BigRational(_) => big_rational_op(self, other, Mul::mul, Mul::mul),
+                Number::Complex(_) => complex_mul(self, other),
             },
-            Number::BigInt(lhs) => match rhs {
+            Number::BigInt(lhs) => match other {
                 Number::Fixnum(rhs) => (&**lhs * rhs).into(),
                 Number::BigInt(rhs) => (&**lhs * &**rhs).into(),
                 Number::Float(rhs) => (lhs.to_f64().unwrap() * *rhs).into(),
@@ -575,26 +1098,30 @@ impl Mul for &Number {
                     if rhs.is_integer() {
                         (&**lhs * rhs.to_integer()).into()
                     } else {
-                        (lhs.to_f64().unwrap() * rhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Mul::mul, Mul::mul)
                     }
                 }
+                Number::BigRational(_) => big_rational_op(self, other, Mul::mul, Mul::mul),
+                Number::Complex(_) => complex_mul(self, other),
             },
-            Number::Float(lhs) => match rhs {
+            Number::Float(lhs) => match other {
                 Number::Fixnum(rhs) => (*lhs * *rhs as f64).into(),
                 Number::Float(rhs) => (*lhs * *rhs).into(),
                 Number::BigInt(rhs) => (*lhs * rhs.to_f64().unwrap()).into(),
                 Number::Rational(rhs) => (*lhs * rhs.to_f64().unwrap_or(f64::NAN)).into(),
+                Number::BigRational(_) => big_rational_op(self, other, Mul::mul, Mul::mul),
+                Number::Complex(_) => complex_mul(self, other),
             },
-            Number::Rational(lhs) => match rhs {
+            Number::Rational(lhs) => match other {
                 Number::Fixnum(rhs) => {
                     if rhs.to_i32().is_some() {
                         let rhs_rational = Rational32::from_integer(*rhs as i32);
                         match rhs_rational.checked_mul(lhs) {
                             Some(num) => num.into(),
-                            None => (lhs.to_f64().unwrap_or(f64::NAN) * *rhs as f64).into(),
+                            None => big_rational_op(self, other, Mul::mul, Mul::mul),
                         }
                     } else {
-                        (lhs.to_f64().unwrap_or(f64::NAN) * *rhs as f64).into()
+                        big_rational_op(self, other, Mul::mul, Mul::mul)
                     }
                 }
                 Number::Float(rhs) => (lhs.to_f64().unwrap_or(f64::NAN) * *rhs).into(),
@@ -602,16 +1129,21 @@ impl Mul for &Number {
                     if lhs.is_integer() {
                         (&**rhs * lhs.to_integer()).into()
                     } else {
-                        (rhs.to_f64().unwrap() * lhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Mul::mul, Mul::mul)
                     }
                 }
                 Number::Rational(rhs) => match lhs.checked_mul(rhs) {
                     Some(num) => num.into(),
-                    None => {
-                        (lhs.to_f64().unwrap_or(f64::NAN) * rhs.to_f64().unwrap_or(f64::NAN)).into()
-                    }
+                    None => big_rational_op(self, other, Mul::mul, Mul::mul),
                 },
+                Number::BigRational(_) => big_rational_op(self, other, Mul::mul, Mul::mul),
+                Number::Complex(_) => complex_mul(self, other),
+            },
+            Number::BigRational(_) => match other {
+                Number::Complex(_) => complex_mul(self, other),
+                _ => big_rational_op(self, other, Mul::mul, Mul::mul),
             },
+            Number::Complex(_) => complex_mul(self, other),
         }
     }
 }
@@ -626,9 +1158,9 @@ impl Sub for Number {
 impl Sub for &Number {
     type Output = Number;
 
-    fn sub(self, rhs: Self) -> Self::Output {
+    fn sub(self, other: Self) -> Self::Output {
         match self {
-            Number::Fixnum(lhs) => match rhs {
+            Number::Fixnum(lhs) => match other {
                 Number::Fixnum(rhs) => match lhs.checked_sub(rhs) {
                     Some(num) => Number::Fixnum(num),
                     None => (BigInt::from(*lhs) - rhs).into(),
@@ -640,14 +1172,16 @@ impl Sub for &Number {
                         let lhs_rational = Rational32::from_integer(*lhs as i32);
                         match lhs_rational.checked_sub(rhs) {
                             Some(num) => num.into(),
-                            None => (*lhs as f64 - rhs.to_f64().unwrap_or(f64::NAN)).into(),
+                            None => big_rational_op(self, other, Sub::sub, Sub::sub),
                         }
                     } else {
-                        (*lhs as f64 - rhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Sub::sub, Sub::sub)
                     }
                 }
+                Number::BigRational(_) => big_rational_op(self, other, Sub::sub, Sub::sub),
+                Number::Complex(_) => complex_sub(self, other),
             },
-            Number::BigInt(lhs) => match rhs {
+            Number::BigInt(lhs) => match other {
                 Number::Fixnum(rhs) => (&**lhs - rhs).into(),
                 Number::BigInt(rhs) => (&**lhs - &**rhs).into(),
                 Number::Float(rhs) => (lhs.to_f64().unwrap() - *rhs).into(),
@@ -655,26 +1189,30 @@ impl Sub for &Number {
                     if rhs.is_integer() {
                         (&**lhs - rhs.to_integer()).into()
                     } else {
-                        (lhs.to_f64().unwrap() - rhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Sub::sub, Sub::sub)
                     }
                 }
+                Number::BigRational(_) => big_rational_op(self, other, Sub::sub, Sub::sub),
+                Number::Complex(_) => complex_sub(self, other),
             },
-            Number::Float(lhs) => match rhs {
+            Number::Float(lhs) => match other {
                 Number::Fixnum(rhs) => (*lhs - *rhs as f64).into(),
                 Number::Float(rhs) => (*lhs - *rhs).into(),
                 Number::BigInt(rhs) => (*lhs - rhs.to_f64().unwrap()).into(),
                 Number::Rational(rhs) => (*lhs - rhs.to_f64().unwrap_or(f64::NAN)).into(),
+                Number::BigRational(_) => big_rational_op(self, other, Sub::sub, Sub::sub),
+                Number::Complex(_) => complex_sub(self, other),
             },
-            Number::Rational(lhs) => match rhs {
+            Number::Rational(lhs) => match other {
                 Number::Fixnum(rhs) => {
                     if rhs.to_i32().is_some() {
                         let rhs_rational = Rational32::from_integer(*rhs as i32);
                         match lhs.checked_sub(&rhs_rational) {
                             Some(num) => num.into(),
-                            None => (lhs.to_f64().unwrap_or(f64::NAN) - *rhs as f64).into(),
+                            None => big_rational_op(self, other, Sub::sub, Sub::sub),
                         }
                     } else {
-                        (lhs.to_f64().unwrap_or(f64::NAN) - *rhs as f64).into()
+                        big_rational_op(self, other, Sub::sub, Sub::sub)
                     }
                 }
                 Number::Float(rhs) => (lhs.to_f64().unwrap_or(f64::NAN) - *rhs).into(),
@@ -682,16 +1220,21 @@ impl Sub for &Number {
                     if lhs.is_integer() {
                         (BigInt::from(lhs.to_integer()) - &**rhs).into()
                     } else {
-                        (lhs.to_f64().unwrap_or(f64::NAN) - rhs.to_f64().unwrap()).into()
+                        big_rational_op(self, other, Sub::sub, Sub::sub)
                     }
                 }
                 Number::Rational(rhs) => match lhs.checked_sub(rhs) {
                     Some(num) => num.into(),
-                    None => {
-                        (lhs.to_f64().unwrap_or(f64::NAN) - rhs.to_f64().unwrap_or(f64::NAN)).into()
-                    }
+                    None => big_rational_op(self, other, Sub::sub, Sub::sub),
                 },
+                Number::BigRational(_) => big_rational_op(self, other, Sub::sub, Sub::sub),
+                Number::Complex(_) => complex_sub(self, other),
+            },
+            Number::BigRational(_) => match other {
+                Number::Complex(_) => complex_sub(self, other),
+                _ => big_rational_op(self, other, Sub::sub, Sub::sub),
             },
+            Number::Complex(_) => complex_sub(self, other),
         }
     }
 }
@@ -706,21 +1249,21 @@ impl Div for Number {
 impl Div for &Number {
     type Output = Number;
 
-    fn div(self, rhs: Self) -> Self::Output {
+    fn div(self, other: Self) -> Self::Output {
         match self {
-            Number::Fixnum(lhs) => match rhs {
+            Number::Fixnum(lhs) => match other {
                 Number::Fixnum(rhs) => {
                     if lhs.to_i32().is_some() && rhs.to_i32().is_some() {
                         Rational32::new(*lhs as i32, *rhs as i32).into()
                     } else {
-                        (*lhs as f64 / *rhs as f64).into()
+                        big_rational_op(self, other, Div::div, Div::div)
                     }
                 }
                 Number::BigInt(rhs) => {
                     if lhs.to_i32().is_some() && rhs.to_i32().is_some() {
                         Rational32::new(*lhs as i32, rhs.to_i32().unwrap()).into()
                     } else {
-                        (*lhs as f64 / rhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Div::div, Div::div)
                     }
                 }
                 Number::Float(rhs) => (*lhs as f64 / rhs).into(),
@@ -728,26 +1271,28 @@ impl Div for &Number {
                     if lhs.to_i32().is_some() {
                         match Rational32::from_integer(*lhs as i32).checked_div(rhs) {
                             Some(num) => num.into(),
-                            None => (*lhs as f64 / rhs.to_f64().unwrap_or(f64::NAN)).into(),
+                            None => big_rational_op(self, other, Div::div, Div::div),
                         }
                     } else {
-                        (*lhs as f64 / rhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Div::div, Div::div)
                     }
                 }
+                Number::BigRational(_) => big_rational_op(self, other, Div::div, Div::div),
+                Number::Complex(_) => complex_div(self, other),
             },
-            Number::BigInt(lhs) => match rhs {
+            Number::BigInt(lhs) => match other {
                 Number::Fixnum(rhs) => {
                     if lhs.to_i32().is_some() && rhs.to_i32().is_some() {
                         (Rational32::new(lhs.to_i32().unwrap(), *rhs as i32)).into()
                     } else {
-                        (lhs.to_f64().unwrap_or(f64::NAN) / *rhs as f64).into()
+                        big_rational_op(self, other, Div::div, Div::div)
                     }
                 }
                 Number::BigInt(rhs) => {
                     if lhs.to_i32().is_some() && rhs.to_i32().is_some() {
                         (Rational32::new(lhs.to_i32().unwrap(), rhs.to_i32().unwrap())).into()
                     } else {
-                        (lhs.to_f64().unwrap_or(f64::NAN) / rhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Div::div, Div::div)
                     }
                 }
                 Number::Float(rhs) => (lhs.to_f64().unwrap() / *rhs).into(),
@@ -755,30 +1300,32 @@ impl Div for &Number {
                     if lhs.to_i32().is_some() {
                         match Rational32::from_integer(lhs.to_i32().unwrap()).checked_div(rhs) {
                             Some(num) => num.into(),
-                            None => {
-                                (lhs.to_f64().unwrap() / rhs.to_f64().unwrap_or(f64::NAN)).into()
-                            }
+                            None => big_rational_op(self, other, Div::div, Div::div),
                         }
                     } else {
-                        (lhs.to_f64().unwrap() / rhs.to_f64().unwrap_or(f64::NAN)).into()
+                        big_rational_op(self, other, Div::div, Div::div)
                     }
                 }
+                Number::BigRational(_) => big_rational_op(self, other, Div::div, Div::div),
+                Number::Complex(_) => complex_div(self, other),
             },
-            Number::Float(lhs) => match rhs {
+            Number::Float(lhs) => match other {
                 Number::Fixnum(rhs) => (*lhs / *rhs as f64).into(),
                 Number::Float(rhs) => (*lhs / *rhs).into(),
                 Number::BigInt(rhs) => (*lhs / rhs.to_f64().unwrap()).into(),
                 Number::Rational(rhs) => (lhs / rhs.to_f64().unwrap_or(f64::NAN)).into(),
+                Number::BigRational(_) => big_rational_op(self, other, Div::div, Div::div),
+                Number::Complex(_) => complex_div(self, other),
             },
-            Number::Rational(lhs) => match rhs {
+            Number::Rational(lhs) => match other {
                 Number::Fixnum(rhs) => {
                     if rhs.to_i32().is_some() {
                         match lhs.checked_div(&Rational32::from_integer(*rhs as i32)) {
                             Some(num) => num.into(),
-                            None => (lhs.to_f64().unwrap_or(f64::MAX) / *rhs as f64).into(),
+                            None => big_rational_op(self, other, Div::div, Div::div),
                         }
                     } else {
-                        (lhs.to_f64().unwrap_or(f64::MAX) / *rhs as f64).into()
+                        big_rational_op(self, other, Div::div, Div::div)
                     }
                 }
                 Number::Float(rhs) => (lhs.to_f64().unwrap_or(f64::NAN) / *rhs).into(),
@@ -786,21 +1333,24 @@ impl Div for &Number {
                     if rhs.to_i32().is_some() {
                         match lhs.checked_div(&Rational32::from_integer(rhs.to_i32().unwrap())) {
                             Some(num) => num.into(),
-                            None => {
-                                (lhs.to_f64().unwrap_or(f64::MAX) / rhs.to_f64().unwrap()).into()
-                            }
+                            None => big_rational_op(self, other, Div::div, Div::div),
                         }
                     } else {
-                        (lhs.to_f64().unwrap_or(f64::MAX) / rhs.to_f64().unwrap()).into()
+                        big_rational_op(self, other, Div::div, Div::div)
                     }
                 }
                 Number::Rational(rhs) => match lhs.checked_div(rhs) {
                     Some(num) => num.into(),
-                    None => {
-                        (lhs.to_f64().unwrap_or(f64::NAN) / rhs.to_f64().unwrap_or(f64::NAN)).into()
-                    }
+                    None => big_rational_op(self, other, Div::div, Div::div),
                 },
+                Number::BigRational(_) => big_rational_op(self, other, Div::div, Div::div),
+                Number::Complex(_) => complex_div(self, other),
             },
+            Number::BigRational(_) => match other {
+                Number::Complex(_) => complex_div(self, other),
+                _ => big_rational_op(self, other, Div::div, Div::div),
+            },
+            Number::Complex(_) => complex_div(self, other),
         }
     }
 }
@@ -814,16 +1364,18 @@ impl Number {
     pub fn quotient(&self, rhs: &Self) -> Option<Number> {
         match self {
             Number::Fixnum(lhs) => match rhs {
-                Number::Fixnum(rhs) => Some((lhs / rhs).into()),
+                Number::Fixnum(rhs) => Some(fixnum_quotient(*lhs, *rhs)),
                 Number::BigInt(rhs) => Some((BigInt::from(*lhs) / &**rhs).into()),
                 Number::Float(rhs) => lhs.to_f64().map(|lhs| (lhs as f64 / rhs).trunc().into()),
                 Number::Rational(rhs) => {
                     if rhs.is_integer() {
-                        Some((*lhs / rhs.to_i64().unwrap()).into())
+                        Some(fixnum_quotient(*lhs, rhs.to_i64().unwrap()))
                     } else {
                         None
                     }
                 }
+                Number::BigRational(_) => None,
+                Number::Complex(_) => None,
             },
             Number::BigInt(lhs) => match rhs {
                 Number::Fixnum(rhs) => Some((&**lhs / rhs).into()),
@@ -836,15 +1388,19 @@ impl Number {
                         None
                     }
                 }
+                Number::BigRational(_) => None,
+                Number::Complex(_) => None,
             },
             Number::Float(lhs) => match rhs {
-                Number::Fixnum(rhs) => Some((lhs / *rhs as f64).into()),
+                Number::Fixnum(rhs) => Some((lhs / *rhs as f64).trunc().into()),
                 Number::Float(rhs) => Some((lhs / rhs).trunc().into()),
                 Number::BigInt(_) => None,
-                Number::Rational(rhs) => rhs.to_f64().map(|rhs| (lhs / rhs).into()),
+                Number::Rational(rhs) => rhs.to_f64().map(|rhs| (lhs / rhs).trunc().into()),
+                Number::BigRational(_) => None,
+                Number::Complex(_) => None,
             },
             Number::Rational(lhs) if lhs.is_integer() => match rhs {
-                Number::Fixnum(rhs) => Some((lhs.to_i64().unwrap() / *rhs).into()),
+                Number::Fixnum(rhs) => Some(fixnum_quotient(lhs.to_i64().unwrap(), *rhs)),
                 Number::Float(rhs) => lhs.to_f64().map(|lhs| (lhs / rhs).trunc().into()),
                 Number::BigInt(rhs) => Some((BigInt::from(lhs.to_i64().unwrap()) / &**rhs).into()),
                 Number::Rational(rhs) => {
@@ -854,12 +1410,128 @@ impl Number {
                         None
                     }
                 }
+                Number::BigRational(_) => None,
+                Number::Complex(_) => None,
             },
             Number::Rational(_) => None,
+            Number::BigRational(_) => None,
+            Number::Complex(_) => None,
         }
     }
 }
 
+/// Fixnum Quotient
+///
+/// Truncating division of two fixnums, promoting to a BigInt for the one
+/// case that overflows (i64::MIN / -1).
+fn fixnum_quotient(lhs: i64, rhs: i64) -> Number {
+    match lhs.checked_div(rhs) {
+        Some(num) => Number::Fixnum(num),
+        None => (BigInt::from(lhs) / rhs).into(),
+    }
+}
+
+/// Integer division and number theory
+impl Number {
+    /// Quotient with flooring (rounding towards negative infinity).
+    ///
+    /// Like quotient, this is defined over all integers including integral
+    /// floats.
+    pub fn floor_quotient(&self, rhs: &Self) -> Option<Number> {
+        if rhs.is_zero() {
+            return None;
+        }
+        let quotient = self.quotient(rhs)?;
+        let remainder = (self % rhs)?;
+        if !remainder.is_zero() && (remainder < Number::from(0)) != (*rhs < Number::from(0)) {
+            Some(&quotient - &Number::from(1))
+        } else {
+            Some(quotient)
+        }
+    }
+
+    /// Exact Integer Sqrt
+    ///
+    /// Return s and r such that s^2 + r = self, where s is the largest
+    /// integer whose square is no greater than self. This is only defined
+    /// for exact non-negative integers.
+    pub fn exact_integer_sqrt(&self) -> Option<(Number, Number)> {
+        if !self.is_exact() || !self.is_integer() || *self < Number::from(0) {
+            return None;
+        }
+        let num = self.to_big_rational()?.to_integer();
+        let root = num.sqrt();
+        let remainder = &num - &root * &root;
+        Some((
+            BigRational::from_integer(root).into(),
+            BigRational::from_integer(remainder).into(),
+        ))
+    }
+
+    /// Greatest common divisor, which is always non-negative.
+    pub fn gcd(&self, rhs: &Self) -> Option<Number> {
+        let (mut lhs, mut rhs) = (self.abs(), rhs.abs());
+        while !rhs.is_zero() {
+            let remainder = (&lhs % &rhs)?;
+            lhs = rhs;
+            rhs = remainder;
+        }
+        Some(lhs)
+    }
+
+    /// Least common multiple, which is always non-negative.
+    pub fn lcm(&self, rhs: &Self) -> Option<Number> {
+        let product = (self * rhs).abs();
+        if product.is_zero() {
+            return Some(product);
+        }
+        product.quotient(&self.gcd(rhs)?)
+    }
+
+    /// Rationalize
+    ///
+    /// Return the simplest rational number that differs from this number
+    /// by no more than y. The result is inexact if either argument is
+    /// inexact.
+    ///
+    /// # Arguments
+    /// `y` - The maximum difference
+    pub fn rationalize(&self, y: &Self) -> Option<Number> {
+        let to_exact = |num: &Number| match num {
+            Number::Float(num) => BigRational::from_float(*num),
+            _ => num.to_big_rational(),
+        };
+        let (lhs, rhs) = (to_exact(self)?, to_exact(y)?.abs());
+        let result = Number::from(simplest_rational(&lhs - &rhs, &lhs + &rhs));
+        match self.is_exact() && y.is_exact() {
+            true => Some(result),
+            false => result.to_inexact(),
+        }
+    }
+}
+
+/// Simplest Rational
+///
+/// Return the simplest rational within the interval [lo, hi], which is the
+/// rational with the smallest denominator, and the smallest numerator of those.
+fn simplest_rational(lo: BigRational, hi: BigRational) -> BigRational {
+    if lo > BigRational::zero() {
+        let floor = lo.floor();
+        if floor == lo {
+            floor
+        } else if floor < hi.floor() {
+            floor + BigRational::one()
+        } else {
+            let rest = simplest_rational((hi - &floor).recip(), (lo - &floor).recip());
+            floor + rest.recip()
+        }
+    } else if hi < BigRational::zero() {
+        -simplest_rational(-hi, -lo)
+    } else {
+        BigRational::zero()
+    }
+}
+
 impl Rem for Number {
     type Output = Option<Number>;
     fn rem(self, rhs: Self) -> Self::Output {
@@ -877,7 +1549,7 @@ impl Rem for &Number {
     fn rem(self, rhs: Self) -> Self::Output {
         match self {
             Number::Fixnum(lhs) => match rhs {
-                Number::Fixnum(rhs) => Some((lhs % rhs).into()),
+                Number::Fixnum(rhs) => Some(lhs.wrapping_rem(*rhs).into()),
                 Number::BigInt(rhs) => Some((BigInt::from(*lhs) % &**rhs).into()),
                 Number::Float(rhs) => Some((*lhs as f64 % rhs).into()),
                 Number::Rational(rhs) => {
@@ -887,6 +1559,8 @@ impl Rem for &Number {
                     // both numerator and denominator still fit in an i32.
                     Some(Rational32::from((*result.numer() as i32, *result.denom() as i32)).into())
                 }
+                Number::BigRational(_) => None,
+                Number::Complex(_) => None,
             },
             Number::BigInt(lhs) => match rhs {
                 Number::Fixnum(rhs) => Some((&**lhs % rhs).into()),
@@ -906,6 +1580,8 @@ impl Rem for &Number {
                         Some(Rational32::new(numer_mod.to_i32().unwrap(), *denom).into())
                     }
                 }
+                Number::BigRational(_) => None,
+                Number::Complex(_) => None,
             },
             Number::Float(lhs) => match rhs {
                 Number::Fixnum(rhs) => Some((lhs % *rhs as f64).into()),
@@ -914,13 +1590,19 @@ impl Rem for &Number {
                 // which yields the correct result since a % b = a if a < b
                 Number::BigInt(rhs) => rhs.to_f64().map(|rhs| (lhs % rhs).into()),
                 Number::Rational(rhs) => rhs.to_f64().map(|rhs| (lhs % rhs).into()),
+                Number::BigRational(_) => None,
+                Number::Complex(_) => None,
             },
             Number::Rational(lhs) => match rhs {
                 Number::Fixnum(rhs) => Some((lhs.to_i64().unwrap() % *rhs).into()),
                 Number::Float(rhs) => lhs.to_f64().map(|lhs| (lhs % rhs).into()),
                 Number::BigInt(rhs) => Some((BigInt::from(lhs.to_i64().unwrap()) % &**rhs).into()),
                 Number::Rational(rhs) => Some((lhs % rhs).into()),
+                Number::BigRational(_) => None,
+                Number::Complex(_) => None,
             },
+            Number::BigRational(_) => None,
+            Number::Complex(_) => None,
         }
     }
 }
@@ -932,6 +1614,11 @@ impl Hash for Number {
             Number::Float(_) => panic!("unexpected hash of f64"),
             Number::BigInt(num) => num.hash(state),
             Number::Rational(num) => num.hash(state),
+            Number::BigRational(num) => num.hash(state),
+            Number::Complex(num) => {
+                num.real.hash(state);
+                num.imag.hash(state);
+            }
         }
     }
 }
@@ -941,10 +1628,77 @@ impl fmt::Display for Number {
         match self {
             Number::Fixnum(num) => write!(f, "{}", num),
             Number::BigInt(num) => write!(f, "{}", num),
-            Number::Float(num) if *num > 1E10 => write!(f, "{:e}", num),
-            Number::Float(num) if self.is_integer() => write!(f, "{:.1}", num),
-            Number::Float(num) => write!(f, "{}", num),
+            Number::Float(num) => write_float(*num, f),
             Number::Rational(num) => write!(f, "{}", num),
+            Number::BigRational(num) => write!(f, "{}", num),
+            Number::Complex(num) => write_complex(num, f, fmt::Display::fmt),
+        }
+    }
+}
+
+/// Write Float
+///
+/// Write the float in the shortest form that reads back as the same float,
+/// using scientific notation for very large and very small magnitudes and
+/// +inf.0, -inf.0 and +nan.0 for the special values.
+fn write_float(num: f64, f: &mut Formatter<'_>) -> fmt::Result {
+    if num.is_nan() {
+        write!(f, "+nan.0")
+    } else if num.is_infinite() {
+        write!(f, "{}inf.0", if num > 0.0 { "+" } else { "-" })
+    } else if num != 0.0 && (num.abs() > 1E10 || num.abs() < 1E-7) {
+        write!(f, "{:e}", num)
+    } else if num.fract() == 0.0 {
+        write!(f, "{:.1}", num)
+    } else {
+        write!(f, "{}", num)
+    }
+}
+
+/// Write Float Radix
+///
+/// Write the float in the given radix, which must be a power of two
+/// for the fractional digits to terminate. Integral floats are written
+/// with a trailing .0 so that they read back as inexact.
+fn write_float_radix(num: f64, radix: u32, f: &mut Formatter<'_>) -> fmt::Result {
+    if !num.is_finite() {
+        return write_float(num, f);
+    }
+    if num < 0_f64 {
+        write!(f, "-")?;
+    }
+    let integer = BigInt::from_f64(num.trunc().abs()).unwrap();
+    write!(f, "{}", integer.to_str_radix(radix))?;
+    match num.fract() == 0_f64 {
+        true => write!(f, ".0"),
+        false => write_float_fract(num, radix as usize, f),
+    }
+}
+
+/// Write Complex
+///
+/// Write the complex number in rectangular form, formatting each part
+/// with fmt. A zero real part is omitted, as is an imaginary part of
+/// exactly 1 or -1.
+fn write_complex(
+    num: &Complex,
+    f: &mut Formatter<'_>,
+    fmt: impl Fn(&Number, &mut Formatter<'_>) -> fmt::Result,
+) -> fmt::Result {
+    if !num.real.is_zero() {
+        fmt(&num.real, f)?;
+    }
+    match num.imag.to_f64() {
+        _ if num.imag.is_exact() && num.imag == Number::from(1) => write!(f, "+i"),
+        _ if num.imag.is_exact() && num.imag == Number::from(-1) => write!(f, "-i"),
+        Some(imag) if imag.is_sign_negative() || !imag.is_finite() => {
+            fmt(&num.imag, f)?;
+            write!(f, "i")
+        }
+        _ => {
+            write!(f, "+")?;
+            fmt(&num.imag, f)?;
+            write!(f, "i")
         }
     }
 }
@@ -966,54 +1720,47 @@ fn write_float_fract(mut num: f64, radix: usize, f: &mut Formatter<'_>) -> fmt::
     Ok(())
 }
 
+/// Write Radix
+///
+/// Write the number in the given radix. Negative numbers are written with
+/// a sign rather than in two's complement.
+fn write_radix(num: &Number, radix: u32, f: &mut Formatter<'_>) -> fmt::Result {
+    let integer = |num: i64| BigInt::from(num).to_str_radix(radix);
+    match num {
+        Number::Fixnum(num) => write!(f, "{}", integer(*num)),
+        Number::BigInt(num) => write!(f, "{}", num.to_str_radix(radix)),
+        Number::Float(num) => write_float_radix(*num, radix, f),
+        Number::Rational(num) => write!(
+            f,
+            "{}/{}",
+            integer(*num.numer() as i64),
+            integer(*num.denom() as i64)
+        ),
+        Number::BigRational(num) => write!(
+            f,
+            "{}/{}",
+            num.numer().to_str_radix(radix),
+            num.denom().to_str_radix(radix)
+        ),
+        Number::Complex(num) => write_complex(num, f, |num, f| write_radix(num, radix, f)),
+    }
+}
+
 impl LowerHex for Number {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Number::Fixnum(num) => fmt::LowerHex::fmt(num, f),
-            Number::Float(num) => {
-                if *num < 0_f64 {
-                    write!(f, "-")?;
-                }
-                write!(f, "{:x}", num.trunc().abs() as i64)?;
-                write_float_fract(*num, 16, f)
-            }
-            Number::BigInt(num) => fmt::LowerHex::fmt(num.as_ref(), f),
-            Number::Rational(num) => fmt::LowerHex::fmt(num, f),
-        }
+        write_radix(self, 16, f)
     }
 }
 
 impl Octal for Number {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Number::Fixnum(num) => fmt::Octal::fmt(num, f),
-            Number::Float(num) => {
-                if *num < 0_f64 {
-                    write!(f, "-")?;
-                }
-                write!(f, "{:o}", num.trunc().abs() as i64)?;
-                write_float_fract(*num, 8, f)
-            }
-            Number::BigInt(num) => fmt::Octal::fmt(num.as_ref(), f),
-            Number::Rational(num) => fmt::Octal::fmt(num, f),
-        }
+        write_radix(self, 8, f)
     }
 }
 
 impl Binary for Number {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Number::Fixnum(num) => fmt::Binary::fmt(num, f),
-            Number::Float(num) => {
-                if *num < 0_f64 {
-                    write!(f, "-")?;
-                }
-                write!(f, "{:b}", num.trunc().abs() as i64)?;
-                write_float_fract(*num, 2, f)
-            }
-            Number::BigInt(num) => fmt::Binary::fmt(num.as_ref(), f),
-            Number::Rational(num) => fmt::Binary::fmt(num, f),
-        }
+        write_radix(self, 2, f)
     }
 }
 
@@ -1073,23 +1820,61 @@ impl From<Rational32> for Number {
     }
 }
 
+impl From<BigRational> for Number {
+    fn from(num: BigRational) -> Self {
+        if num.is_integer() {
+            let num = num.to_integer();
+            match num.to_i64() {
+                Some(num) => Number::Fixnum(num),
+                None => num.into(),
+            }
+        } else {
+            match (num.numer().to_i32(), num.denom().to_i32()) {
+                (Some(numer), Some(denom)) => Rational32::new_raw(numer, denom).into(),
+                _ => Number::BigRational(Rc::new(num)),
+            }
+        }
+    }
+}
+
+impl From<Complex64> for Number {
+    fn from(num: Complex64) -> Self {
+        Number::new_complex(num.re.into(), num.im.into())
+    }
+}
+
 /// Trigonometric functions
+///
+/// Each function returns a complex result for complex arguments, and for
+/// real arguments outside of the function's real domain.
 impl Number {
+    fn to_complex64(&self) -> Option<Complex64> {
+        Some(Complex64::new(
+            self.real_part().to_f64()?,
+            self.imag_part().to_f64()?,
+        ))
+    }
+
     pub fn exp(&self) -> Option<Number> {
         match self {
             Number::Fixnum(num) => num.to_f64().map(|num| num.exp().into()),
             Number::Float(num) => Some(num.exp().into()),
             Number::BigInt(num) => num.to_f64().map(|num| num.exp().into()),
             Number::Rational(num) => num.to_f64().map(|num| num.exp().into()),
+            Number::BigRational(num) => num.to_f64().map(|num| num.exp().into()),
+            Number::Complex(_) => self.to_complex64().map(|num| num.exp().into()),
         }
     }
 
     pub fn log(&self) -> Option<Number> {
         match self {
+            _ if *self < Number::from(0) => self.to_complex64().map(|num| num.ln().into()),
             Number::Fixnum(num) => num.to_f64().map(|num| num.log(f64::E()).into()),
             Number::Float(num) => Some(num.log(f64::E()).into()),
             Number::BigInt(num) => num.to_f64().map(|num| num.log(f64::E()).into()),
             Number::Rational(num) => num.to_f64().map(|num| num.log(f64::E()).into()),
+            Number::BigRational(num) => num.to_f64().map(|num| num.log(f64::E()).into()),
+            Number::Complex(_) => self.to_complex64().map(|num| num.ln().into()),
         }
     }
 
@@ -1099,6 +1884,8 @@ impl Number {
             Number::Float(num) => Some(num.sin().into()),
             Number::BigInt(num) => num.to_f64().map(|num| num.sin().into()),
             Number::Rational(num) => num.to_f64().map(|num| num.sin().into()),
+            Number::BigRational(num) => num.to_f64().map(|num| num.sin().into()),
+            Number::Complex(_) => self.to_complex64().map(|num| num.sin().into()),
         }
     }
 
@@ -1108,6 +1895,8 @@ impl Number {
             Number::Float(num) => Some(num.cos().into()),
             Number::BigInt(num) => num.to_f64().map(|num| num.cos().into()),
             Number::Rational(num) => num.to_f64().map(|num| num.cos().into()),
+            Number::BigRational(num) => num.to_f64().map(|num| num.cos().into()),
+            Number::Complex(_) => self.to_complex64().map(|num| num.cos().into()),
         }
     }
 
@@ -1117,24 +1906,36 @@ impl Number {
             Number::Float(num) => Some(num.tan().into()),
             Number::BigInt(num) => num.to_f64().map(|num| num.tan().into()),
             Number::Rational(num) => num.to_f64().map(|num| num.tan().into()),
+            Number::BigRational(num) => num.to_f64().map(|num| num.tan().into()),
+            Number::Complex(_) => self.to_complex64().map(|num| num.tan().into()),
         }
     }
 
     pub fn asin(&self) -> Option<Number> {
         match self {
+            _ if *self < Number::from(-1) || *self > Number::from(1) => {
+                self.to_complex64().map(|num| num.asin().into())
+            }
             Number::Fixnum(num) => num.to_f64().map(|num| num.asin().into()),
             Number::Float(num) => Some(num.asin().into()),
             Number::BigInt(num) => num.to_f64().map(|num| num.asin().into()),
             Number::Rational(num) => num.to_f64().map(|num| num.asin().into()),
+            Number::BigRational(num) => num.to_f64().map(|num| num.asin().into()),
+            Number::Complex(_) => self.to_complex64().map(|num| num.asin().into()),
         }
     }
 
     pub fn acos(&self) -> Option<Number> {
         match self {
+            _ if *self < Number::from(-1) || *self > Number::from(1) => {
+                self.to_complex64().map(|num| num.acos().into())
+            }
             Number::Fixnum(num) => num.to_f64().map(|num| num.acos().into()),
             Number::Float(num) => Some(num.acos().into()),
             Number::BigInt(num) => num.to_f64().map(|num| num.acos().into()),
             Number::Rational(num) => num.to_f64().map(|num| num.acos().into()),
+            Number::BigRational(num) => num.to_f64().map(|num| num.acos().into()),
+            Number::Complex(_) => self.to_complex64().map(|num| num.acos().into()),
         }
     }
 
@@ -1144,6 +1945,8 @@ impl Number {
             Number::Float(num) => Some(num.atan().into()),
             Number::BigInt(num) => num.to_f64().map(|num| num.atan().into()),
             Number::Rational(num) => num.to_f64().map(|num| num.atan().into()),
+            Number::BigRational(num) => num.to_f64().map(|num| num.atan().into()),
+            Number::Complex(_) => self.to_complex64().map(|num| num.atan().into()),
         }
     }
 
@@ -1154,19 +1957,34 @@ impl Number {
                 Number::Float(num) => Some(num.atan2(x).into()),
                 Number::BigInt(num) => num.to_f64().map(|num| num.atan2(x).into()),
                 Number::Rational(num) => num.to_f64().map(|num| num.atan2(x).into()),
+                Number::BigRational(num) => num.to_f64().map(|num| num.atan2(x).into()),
+                Number::Complex(_) => None,
             }
         } else {
             None
         }
     }
 
-    /// sqrt is undefined for complex results
+    /// The square root of a negative number is complex, and the square root
+    /// of an exact number is exact if the root is rational.
     pub fn sqrt(&self) -> Option<Number> {
+        if let Some(root) = self.exact_sqrt() {
+            return Some(root);
+        }
+
         let result: Option<Number> = match self {
+            _ if *self < Number::from(0) => {
+                return self
+                    .abs()
+                    .sqrt()
+                    .map(|root| Number::new_complex(Number::from(0), root))
+            }
             Number::Fixnum(num) => num.to_f64().map(|num| num.sqrt().into()),
             Number::Float(num) => Some(num.sqrt().into()),
             Number::BigInt(num) => num.to_f64().map(|num| num.sqrt().into()),
             Number::Rational(num) => num.to_f64().map(|num| num.sqrt().into()),
+            Number::BigRational(num) => num.to_f64().map(|num| num.sqrt().into()),
+            Number::Complex(_) => return self.to_complex64().map(|num| num.sqrt().into()),
         };
 
         // convert NaN and infinity to None
@@ -1187,7 +2005,9 @@ mod tests {
     use crate::number::Number;
     use num::bigint::BigInt;
     use num::traits::FloatConst;
-    use num::{FromPrimitive, Rational32};
+    use num::{BigRational, FromPrimitive, Rational32};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
     use std::mem;
     use std::str::FromStr;
 
@@ -1280,7 +2100,7 @@ mod tests {
             100, BigInt::from(50) => BigInt::from(150),
             100, 50.0 => 150.0,
             100, Rational32::from_integer(50) => Rational32::from_integer(150),
-            i32_overflow, Rational32::from_integer(50) => i32_overflow as f64 + 50_f64
+            i32_overflow, Rational32::from_integer(50) => i32_overflow + 50
         ];
 
         // BIGINT + RHS
@@ -1289,7 +2109,7 @@ mod tests {
             BigInt::from(100), i64::MAX => BigInt::from(i64::MAX) + 100,
             BigInt::from(100), 50.0 => 150.0,
             BigInt::from(100), Rational32::from_integer(50) => BigInt::from(150),
-            BigInt::from(100), Rational32::new(1, 2) => 100.50
+            BigInt::from(100), Rational32::new(1, 2) => Rational32::new(201, 2)
         ];
 
         // FLOAT + RHS
@@ -1303,11 +2123,11 @@ mod tests {
         // RATIONAL + RHS
         verify![|x, y| x + y,
             Rational32::from_integer(100), 50 => Rational32::from_integer(150),
-            Rational32::from_integer(100), i32_overflow => 100.0 + i32_overflow as f64,
+            Rational32::from_integer(100), i32_overflow => 100 + i32_overflow,
             Rational32::from_integer(100), 50.0 => 150.0,
             Rational32::from_integer(100), BigInt::from(50) => BigInt::from(150),
-            Rational32::new(1, 2), BigInt::from(50) => 50.50,
-            Rational32::from_integer(i32::MAX), Rational32::from_integer(1) => i32::MAX as f64 + 1_f64
+            Rational32::new(1, 2), BigInt::from(50) => Rational32::new(101, 2),
+            Rational32::from_integer(i32::MAX), Rational32::from_integer(1) => i32::MAX as i64 + 1
         ];
     }
 
@@ -1322,7 +2142,7 @@ mod tests {
             100, BigInt::from(50) => BigInt::from(5000),
             100, 50.0 => 5000.0,
             100, Rational32::from_integer(50) => Rational32::from_integer(5000),
-            i32_overflow, Rational32::from_integer(50) => i32_overflow as f64 * 50_f64
+            i32_overflow, Rational32::from_integer(50) => i32_overflow * 50
         ];
 
         // // BIGINT * RHS
@@ -1331,7 +2151,7 @@ mod tests {
             BigInt::from(100), BigInt::from(50) => BigInt::from(5000),
             BigInt::from(100), 50.0 => 5000.0,
             BigInt::from(100), Rational32::from_integer(50) => BigInt::from(5000),
-            BigInt::from(100), Rational32::new(1, 2) => 50.0
+            BigInt::from(100), Rational32::new(1, 2) => 50
         ];
         //
         // // FLOAT * RHS
@@ -1345,12 +2165,12 @@ mod tests {
         // // RATIONAL * RHS
         verify![|x, y| x * y,
             Rational32::from_integer(100), 50 => Rational32::from_integer(5000),
-            Rational32::from_integer(100), i32_overflow => 100.0 * i32_overflow as f64,
+            Rational32::from_integer(100), i32_overflow => 100 * i32_overflow,
             Rational32::from_integer(100), 50.0 => 5000.0,
             Rational32::from_integer(100), BigInt::from(50) => BigInt::from(5000),
-            Rational32::new(1, 2), BigInt::from(100) => 50.0,
+            Rational32::new(1, 2), BigInt::from(100) => 50,
             Rational32::from_integer(100), Rational32::from_integer(50) => Rational32::from_integer(5000),
-            Rational32::from_integer(i32::MAX), Rational32::from_integer(2) => i32::MAX as f64 * 2_f64
+            Rational32::from_integer(i32::MAX), Rational32::from_integer(2) => i32::MAX as i64 * 2
         ];
     }
 
@@ -1365,8 +2185,8 @@ mod tests {
             100, BigInt::from(50) => BigInt::from(50),
             100, 50.0 => 50.0,
             100, Rational32::from_integer(50) => Rational32::from_integer(50),
-            i32::MIN as i64, Rational32::from_integer(50) => i32::MIN as f64 - 50_f64,
-            i32_overflow, Rational32::from_integer(50) => i32_overflow as f64 - 50_f64
+            i32::MIN as i64, Rational32::from_integer(50) => i32::MIN as i64 - 50,
+            i32_overflow, Rational32::from_integer(50) => i32_overflow - 50
         ];
 
         // // BIGINT - RHS
@@ -1375,7 +2195,7 @@ mod tests {
             BigInt::from(100), BigInt::from(50) => BigInt::from(50),
             BigInt::from(100), 50.0 => 50.0,
             BigInt::from(100), Rational32::from_integer(50) => BigInt::from(50),
-            BigInt::from(100), Rational32::new(1, 2) => 99.50
+            BigInt::from(100), Rational32::new(1, 2) => Rational32::new(199, 2)
         ];
 
         // FLOAT - RHS
@@ -1390,13 +2210,13 @@ mod tests {
         // RATIONAL - RHS
         verify![|x, y| x - y,
             Rational32::from_integer(100), 50 => Rational32::from_integer(50),
-            Rational32::from_integer(100), i32::MIN as i64 => 100.0 - i32::MIN as f64,
-            Rational32::from_integer(100), i32_overflow => 100.0 - i32_overflow as f64,
+            Rational32::from_integer(100), i32::MIN as i64 => 100 - i32::MIN as i64,
+            Rational32::from_integer(100), i32_overflow => 100 - i32_overflow,
             Rational32::from_integer(100), 50.0 => 50.0,
             Rational32::from_integer(100), BigInt::from(50) => BigInt::from(50),
-            Rational32::new(1, 2), BigInt::from(50) => -49.5,
+            Rational32::new(1, 2), BigInt::from(50) => Rational32::new(-99, 2),
             Rational32::from_integer(100), Rational32::from_integer(50) => Rational32::from_integer(50),
-            Rational32::from_integer(i32::MIN), Rational32::from_integer(1) => i32::MIN as f64 - 1_f64
+            Rational32::from_integer(i32::MIN), Rational32::from_integer(1) => i32::MIN as i64 - 1
         ];
     }
 
@@ -1408,30 +2228,30 @@ mod tests {
         // FIXNUM / RHS
         verify![|x, y| x / y,
             100, 50 => Rational32::from_integer(2),
-            i32_uflow, i32_uflow => 1.0,
-            i32_oflow, i32_oflow => 1.0,
+            i32_uflow, i32_uflow => 1,
+            i32_oflow, i32_oflow => 1,
             100, BigInt::from(50) => Rational32::from_integer(2),
-            i32_oflow, BigInt::from(i32_oflow) => 1.0,
-            i32_oflow, BigInt::from(2) => i32_oflow as f64 / 2_f64,
+            i32_oflow, BigInt::from(i32_oflow) => 1,
+            i32_oflow, BigInt::from(2) => i32_oflow / 2,
             100, Rational32::from_integer(50) => Rational32::from_integer(2),
             100, Rational32::new(1, 2) => Rational32::from_integer(200),
-            i32_oflow, Rational32::from_integer(2) => i32_oflow as f64 / 2_f64,
-            i32::MAX as i64, Rational32::new(1, 2) => i32::MAX as f64 * 2.0,
+            i32_oflow, Rational32::from_integer(2) => i32_oflow / 2,
+            i32::MAX as i64, Rational32::new(1, 2) => i32::MAX as i64 * 2,
             -100, 1.0 => -100.0
         ];
 
         // BIGINT / RHS
         verify![|x, y| x / y,
             BigInt::from(100), 50 => Rational32::from_integer(2),
-            BigInt::from(i32_oflow), 2 => i32_oflow as f64 / 2_f64,
-            BigInt::from(100), i32_oflow => 100 as f64 / i32_oflow as f64,
+            BigInt::from(i32_oflow), 2 => i32_oflow / 2,
+            BigInt::from(100), i32_oflow => Rational32::new(25, 536870912),
             BigInt::from(100), BigInt::from(50) => Rational32::from_integer(2),
-            BigInt::from(i32_oflow), BigInt::from(100) => i32_oflow as f64 / 100_f64,
-            BigInt::from(100), BigInt::from(i32_oflow) => 100_f64 / i32_oflow as f64,
+            BigInt::from(i32_oflow), BigInt::from(100) => Rational32::new(536870912, 25),
+            BigInt::from(100), BigInt::from(i32_oflow) => Rational32::new(25, 536870912),
             BigInt::from(100), 50.0 => 2.0,
             BigInt::from(100), Rational32::from_integer(50) => Rational32::from_integer(2),
-            BigInt::from(i32_oflow), Rational32::from_integer(50) =>  i32_oflow as f64 / 50_f64,
-            BigInt::from(i32::MAX), Rational32::new(1, 2) => Number::from(i32::MAX as f64 * 2_f64)
+            BigInt::from(i32_oflow), Rational32::from_integer(50) => Rational32::new(1073741824, 25),
+            BigInt::from(i32::MAX), Rational32::new(1, 2) => i32::MAX as i64 * 2
         ];
 
         // FLOAT / RHS
@@ -1446,12 +2266,12 @@ mod tests {
         // RATIONAL / RHS
         verify![|x, y| x / y,
             Rational32::from_integer(100), 50 => Rational32::from_integer(2),
-            Rational32::from_integer(100), i32_oflow => 100_f64 / i32_oflow as f64,
+            Rational32::from_integer(100), i32_oflow => Rational32::new(25, 536870912),
             Rational32::from_integer(100), 50_f64 => 2_f64,
             Rational32::from_integer(100), BigInt::from(50) => Rational32::from_integer(2),
-            Rational32::from_integer(100), BigInt::from(i32_oflow) => 100_f64 / i32_oflow as f64,
+            Rational32::from_integer(100), BigInt::from(i32_oflow) => Rational32::new(25, 536870912),
             Rational32::from_integer(100), Rational32::from_integer(50) => Rational32::from_integer(2),
-            Rational32::from_integer(i32::MAX), Rational32::new(1,2) => i32::MAX as f64 * 2.0
+            Rational32::from_integer(i32::MAX), Rational32::new(1,2) => i32::MAX as i64 * 2
         ];
     }
 
@@ -1618,4 +2438,185 @@ mod tests {
             .unwrap()
             .is_nan());
     }
+
+    #[test]
+    fn big_rational() {
+        let big = |numer: i64, denom: i64| BigRational::new(numer.into(), denom.into());
+        let i32_oflow = i32::MAX as i64 + 1;
+
+        // Rational32 overflow is promoted to BigRational
+        verify![|x, y| x + y,
+            Rational32::new(1, i32::MAX), Rational32::new(1, 2) => big(i32::MAX as i64 + 2, 2 * i32::MAX as i64),
+            big(1, i32_oflow), big(1, i32_oflow) => Rational32::new(1, 1 << 30),
+            big(1, i32_oflow), 1 => big(i32_oflow + 1, i32_oflow),
+            big(1, i32_oflow), 0.5 => 0.5 + 1.0 / i32_oflow as f64
+        ];
+        verify![|x, y| x - y,
+            big(1, i32_oflow), big(1, i32_oflow) => 0,
+            1, big(1, i32_oflow) => big(i32_oflow - 1, i32_oflow)
+        ];
+        verify![|x, y| x * y,
+            big(1, i32_oflow), i32_oflow => 1,
+            Rational32::new(1, 1 << 16), Rational32::new(1, 1 << 16) => big(1, 1 << 32),
+            big(1, i32_oflow), BigInt::from(2) => Rational32::new(1, 1 << 30)
+        ];
+        verify![|x, y| x / y,
+            1, i32_oflow => big(1, i32_oflow),
+            big(1, i32_oflow), big(1, i32_oflow) => 1,
+            Rational32::new(1, i32::MAX), 2 => big(1, 2 * i32::MAX as i64)
+        ];
+
+        let num = Number::from(big(3, i32_oflow));
+        assert_eq!(num.numerator(), Number::from(3));
+        assert_eq!(num.denominator(), Number::from(i32_oflow));
+        assert_eq!(num.to_string(), "3/2147483648");
+        assert!(num.is_rational() && num.is_exact() && !num.is_integer());
+        assert!(num > Number::from(Rational32::new(1, i32::MAX)));
+        assert!(num < Number::from(Rational32::new(1, 1 << 29)));
+        assert_eq!(num.to_inexact(), Some(Number::from(3.0 / i32_oflow as f64)));
+        assert_eq!(
+            Number::from(Rational32::new(5, 7)).pow(30),
+            Number::from(BigRational::new(
+                BigInt::from(5).pow(30),
+                BigInt::from(7).pow(30)
+            ))
+        );
+        assert_eq!(
+            Number::parse_rational("1/2147483648", 10),
+            Some(num / Number::from(3))
+        );
+    }
+
+    #[test]
+    fn integer_division() {
+        let num = |n: i64| Number::from(n);
+        assert_eq!(num(-7).floor_quotient(&num(2)), Some(num(-4)));
+        assert_eq!(num(7).floor_quotient(&num(-2)), Some(num(-4)));
+        assert_eq!(num(6).floor_quotient(&num(-2)), Some(num(-3)));
+        assert_eq!(num(1).floor_quotient(&num(0)), None);
+        assert_eq!(num(17).exact_integer_sqrt(), Some((num(4), num(1))));
+        assert_eq!(num(-1).exact_integer_sqrt(), None);
+        assert_eq!(num(12).gcd(&num(-18)), Some(num(6)));
+        assert_eq!(num(4).lcm(&num(-6)), Some(num(12)));
+        assert_eq!(num(0).lcm(&num(6)), Some(num(0)));
+        assert_eq!(
+            Number::from(Rational32::new(3, 10)).rationalize(&Number::from(Rational32::new(1, 10))),
+            Some(Number::from(Rational32::new(1, 3)))
+        );
+    }
+
+    // Format the number in the given radix, as number->string does.
+    fn format_radix(num: &Number, radix: u32) -> String {
+        match radix {
+            2 => format!("{:b}", num),
+            8 => format!("{:o}", num),
+            16 => format!("{:x}", num),
+            _ => format!("{}", num),
+        }
+    }
+
+    // Assert that num is written in each radix as text that reads back as
+    // the same number with the same exactness.
+    fn assert_round_trips(num: Number, radices: &[u32]) {
+        for &radix in radices {
+            let text = format_radix(&num, radix);
+            let parsed = Number::parse(&text, radix);
+            assert_eq!(
+                parsed,
+                Some(num.clone()),
+                "{:?} radix {} as {}",
+                num,
+                radix,
+                text
+            );
+            assert_eq!(parsed.unwrap().is_exact(), num.is_exact(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn float_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let num = f64::from_bits(rng.gen());
+            if !num.is_nan() {
+                assert_round_trips(Number::from(num), &[10, 16]);
+            }
+            assert_round_trips(Number::from(rng.gen_range(-1e12..1e12)), &[2, 8, 10, 16]);
+            assert_round_trips(Number::from(rng.gen_range(-1e-6..1e-6)), &[2, 8, 10, 16]);
+            assert_round_trips(Number::from(rng.gen::<i32>() as f64), &[2, 8, 10, 16]);
+        }
+        for num in [0.1, 1.0, -0.0, 1e21, 5e-324, f64::MAX, f64::MIN_POSITIVE] {
+            assert_round_trips(Number::from(num), &[2, 8, 10, 16]);
+        }
+        assert_eq!(Number::from(1e21).to_string(), "1e21");
+        assert_eq!(Number::from(0.1).to_string(), "0.1");
+        assert_eq!(Number::from(100.0).to_string(), "100.0");
+        assert_eq!(Number::from(1e-8).to_string(), "1e-8");
+        assert_eq!(Number::from(f64::INFINITY).to_string(), "+inf.0");
+        assert_eq!(Number::from(f64::NEG_INFINITY).to_string(), "-inf.0");
+        assert_eq!(Number::from(f64::NAN).to_string(), "+nan.0");
+        assert!(matches!(Number::parse("+nan.0", 10), Some(Number::Float(num)) if num.is_nan()));
+    }
+
+    #[test]
+    fn exact_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let radices = [2, 8, 10, 16];
+        for _ in 0..1000 {
+            let (numer, denom) = (rng.gen::<i64>(), rng.gen::<i64>());
+            assert_round_trips(Number::from(numer), &radices);
+            assert_round_trips(
+                Number::from(BigInt::from(numer) * BigInt::from(denom)),
+                &radices,
+            );
+            if denom != 0 {
+                let num = BigRational::new(BigInt::from(numer), BigInt::from(denom));
+                assert_round_trips(Number::from(num), &radices);
+                let num = Rational32::new(numer as i32, denom as i32 | 1);
+                assert_round_trips(Number::from(num), &radices);
+            }
+        }
+    }
+
+    #[test]
+    fn complex_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let real = Number::from(rng.gen_range(-100..100));
+            let imag = Number::from(Rational32::new(rng.gen_range(-100..100), 3));
+            assert_round_trips(Number::new_complex(real, imag), &[2, 8, 10, 16]);
+            let real = Number::from(rng.gen_range(-1e6..1e6));
+            let imag = Number::from(rng.gen_range(-1e6..1e6));
+            assert_round_trips(Number::new_complex(real, imag), &[2, 8, 10, 16]);
+        }
+        for imag in [f64::INFINITY, f64::NEG_INFINITY] {
+            assert_round_trips(Number::new_complex(1.into(), imag.into()), &[10]);
+        }
+    }
+
+    #[test]
+    fn parse_prefixed() {
+        let parse = |text| Number::parse_prefixed(text, 10);
+        assert_eq!(parse("#e1.5e3"), Some(Number::from(1500)));
+        assert_eq!(parse("#e1.25"), Some(Number::from(Rational32::new(5, 4))));
+        assert_eq!(parse("#x#e1.8"), Some(Number::from(Rational32::new(3, 2))));
+        assert_eq!(parse("#i#b101"), Some(Number::from(5.0)));
+        assert_eq!(
+            parse("#B#E-101/11"),
+            Some(Number::from(Rational32::new(-5, 3)))
+        );
+        assert_eq!(
+            parse("#e1e30"),
+            Some(Number::from(BigInt::from(10).pow(30)))
+        );
+        assert_eq!(parse("#i1e400"), Some(Number::from(f64::INFINITY)));
+        assert_eq!(Number::parse_prefixed("ff", 16), Some(Number::from(255)));
+        assert_eq!(Number::parse_prefixed("#d10", 16), Some(Number::from(10)));
+        for text in [
+            "#e#e1", "#x#d1", "#e", "#q1", "#e+inf.0", "#enan.0", "inf", "nan", "infinity", "1e",
+            "e1", ".", "+", "1/", "/2", "1/-2", "1.5/2", "--1", "1e1.5", "1_000", "#b102", "1 ",
+        ] {
+            assert_eq!(parse(text), None, "{}", text);
+        }
+    }
 }
@@ -2,14 +2,15 @@ use crate::cell::Cell;
 use crate::char::named_to_char;
 use crate::lex::TokenType::NumberPrefix;
 use crate::lex::{Token, TokenType};
-use crate::number::{Exactness, Number};
+use crate::number::Number;
 use crate::parse::Error::{
-    ExpectedListTerminator, ExpectedVectorTerminator, Incomplete, UnexpectedToken, UnknownChar,
+    ExpectedByte, ExpectedListTerminator, ExpectedVectorTerminator, Incomplete, UnexpectedToken,
+    UnknownChar,
 };
 use crate::{lex, list};
 use std::iter::Peekable;
 
-#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
 pub enum Error {
     #[error("incomplete")]
     Incomplete,
@@ -23,6 +24,8 @@ pub enum Error {
     ExpectedListTerminator(char, char),
     #[error("expected vector terminator ), but encountered {0}")]
     ExpectedVectorTerminator(char),
+    #[error("expected byte, but encountered {0}")]
+    ExpectedByte(String),
     #[error("syntax error: {0}")]
     SyntaxError(String),
     #[error("unknown character {0}")]
@@ -48,6 +51,23 @@ pub fn parse_text(text: &str) -> Result<(Cell, Option<&str>), Error> {
     Ok((cell, remaining_text))
 }
 
+/// Parse All
+///
+/// Tokenize and parse every expression in text, returning the resulting
+/// Cells in order, or Error if an error occurred.
+///
+/// # Arguments
+/// *`text` - the text to parse
+pub fn parse_all(text: &str) -> Result<Vec<Cell>, Error> {
+    let tokens = lex::scan(text)?;
+    let mut cur = tokens.iter().peekable();
+    let mut cells = vec![];
+    while cur.peek().is_some() {
+        cells.push(parse(text, &mut cur)?);
+    }
+    Ok(cells)
+}
+
 /// Parse one expression from the token stream.
 ///
 /// # Arguments
@@ -66,9 +86,11 @@ pub fn parse<'a, T: Iterator<Item = &'a Token>>(
         TokenType::SingleQuote => Ok(list!["quote", parse(text, cur)?]),
         TokenType::Quasiquote => Ok(list!["quasiquote", parse(text, cur)?]),
         TokenType::Unquote => Ok(list!["unquote", parse(text, cur)?]),
+        TokenType::UnquoteSplicing => Ok(list!["unquote-splicing", parse(text, cur)?]),
         TokenType::RightParen => Err(Error::UnexpectedToken(")".into())),
         TokenType::LeftParen => parse_list(text, cur, token),
         TokenType::HashParen => parse_vector(text, cur),
+        TokenType::HashU8Paren => parse_bytevector(text, cur),
         TokenType::True => Ok(Cell::Bool(true)),
         TokenType::False => Ok(Cell::Bool(false)),
         TokenType::Char => parse_char(text, token),
@@ -201,6 +223,40 @@ fn parse_vector<'a, T: Iterator<Item = &'a Token>>(
     }
 }
 
+/// Bytevector
+///
+/// This function is called by a parser that's encountered a '#u8('.
+/// It will parse every value up to the closing ')', each of which
+/// must be an exact integer between 0 and 255.
+///
+/// # Arguments
+/// *`cur` - an iterator over the token stream. The parser will only
+///          advance the iterator enough to satisfy one expression.
+/// *`text` - the text backed by the token spans.
+fn parse_bytevector<'a, T: Iterator<Item = &'a Token>>(
+    text: &str,
+    cur: &mut Peekable<T>,
+) -> Result<Cell, Error> {
+    let mut bytevector = vec![];
+    loop {
+        match cur.peek().ok_or(Error::Incomplete)?.token_type {
+            TokenType::RightParen => {
+                let end_token = cur.next().unwrap().span(text).chars().next().unwrap();
+                if end_token != ')' {
+                    return Err(ExpectedVectorTerminator(end_token));
+                }
+                return Ok(Cell::Bytevector(bytevector));
+            }
+            _ => match parse(text, cur)? {
+                Cell::Number(Number::Fixnum(byte)) if u8::try_from(byte).is_ok() => {
+                    bytevector.push(byte as u8);
+                }
+                cell => return Err(ExpectedByte(format!("{:#}", cell))),
+            },
+        }
+    }
+}
+
 /// Parse Char
 ///
 /// Parse the character token, skipping the character prefix
@@ -297,7 +353,8 @@ pub fn parse_string(span: &str) -> Result<Cell, Error> {
 /// token.
 ///
 /// If this function is unable to parse the number it is treated as
-/// as symbol.
+/// as symbol, unless the number has a prefix such as #x or #e, in which
+/// case it is an error.
 ///
 /// # Arguments
 /// *`cur` - an iterator over the token stream. The parser will only
@@ -308,25 +365,18 @@ fn parse_number<'a, T: Iterator<Item = &'a Token>>(
     cur: &mut Peekable<T>,
     mut token: &'a Token,
 ) -> Result<Cell, Error> {
-    let mut exactness = Exactness::Unspecified;
-    let mut radix = 10;
-
+    let start = token.span.0;
     while token.token_type == NumberPrefix {
-        match token.span(text) {
-            "#e" => exactness = Exactness::Exact,
-            "#i" => exactness = Exactness::Inexact,
-            "#d" => radix = 10,
-            "#b" => radix = 2,
-            "#o" => radix = 8,
-            "#x" => radix = 16,
-            _ => panic!("unexpected number prefix {}", token.span(text)),
-        }
         token = cur.next().ok_or(Incomplete)?;
     }
 
-    let span = token.span(text);
-    match Number::parse_with_exactness(span, exactness, radix) {
+    let span = &text[start..token.span.1];
+    match Number::parse_prefixed(span, 10) {
         Some(num) => Ok(Cell::Number(num)),
+        None if start != token.span.0 => Err(Error::SyntaxError(format!(
+            "{} is not a valid number",
+            span
+        ))),
         None => Ok(Cell::Symbol(span.to_string())),
     }
 }
@@ -358,6 +408,7 @@ mod tests {
     use crate::lex;
     use crate::list;
     use crate::vector;
+    use num::Rational32;
 
     macro_rules! parses {
         ($($lhs:expr => $rhs:expr),+) => {{
@@ -416,6 +467,16 @@ mod tests {
         assert_eq!(parse(text, &mut cur), Err(Error::Incomplete));
     }
 
+    #[test]
+    fn parses_all_expressions() {
+        assert_eq!(
+            parse_all("(define x 1) x ; comment\n"),
+            Ok(vec![list!["define", "x", 1], cell!["x"]])
+        );
+        assert_eq!(parse_all(""), Ok(vec![]));
+        assert_eq!(parse_all("x (y"), Err(Error::Incomplete));
+    }
+
     #[test]
     fn procedures() {
         parses! {
@@ -431,7 +492,9 @@ mod tests {
     fn quasiquote() {
         parses! {
             "`(1 2 3)" => list!["quasiquote", list![1,2,3]],
-            ",(1 2 3)" => list!["unquote", list![1,2,3]]
+            ",(1 2 3)" => list!["unquote", list![1,2,3]],
+            ",@(1 2 3)" => list!["unquote-splicing", list![1,2,3]],
+            "`(1 ,@x)" => list!["quasiquote", list![1, list!["unquote-splicing", "x"]]]
         };
     }
 
@@ -460,6 +523,24 @@ mod tests {
         fails!["#(1 2 3}", "#(1 2 3]", "#(1 2 . 3)"];
     }
 
+    #[test]
+    fn bytevectors() {
+        parses! {
+            "#u8()" => Cell::Bytevector(vec![]),
+            "#u8(0 1 255)" => Cell::Bytevector(vec![0, 1, 255]),
+            "#u8(#xff #b10)" => Cell::Bytevector(vec![255, 2])
+        };
+
+        fails![
+            "#u8(1 2 3]",
+            "#u8(256)",
+            "#u8(-1)",
+            "#u8(1.0)",
+            "#u8(a)",
+            "#u8((1))"
+        ];
+    }
+
     #[test]
     fn numbers() {
         parses! {
@@ -482,6 +563,42 @@ mod tests {
             "#x7fffffff/1" => cell![0x7fffffff],
             "#xffffffff/1" => cell![0xffffffff]
         }
+
+        parses! {
+            "1+2i" => Cell::Number(Number::new_complex(1.into(), 2.into())),
+            "-1/2-2.5i" => Cell::Number(Number::new_complex((-0.5).into(), (-2.5).into())),
+            "+i" => Cell::Number(Number::new_complex(0.into(), 1.into())),
+            "-i" => Cell::Number(Number::new_complex(0.into(), (-1).into())),
+            "1e-2+1e2i" => Cell::Number(Number::new_complex(0.01.into(), 100.0.into())),
+            "#xa+bi" => Cell::Number(Number::new_complex(10.into(), 11.into())),
+            "#e1.5+2.5i" => Cell::Number(Number::new_complex(
+                Rational32::new(3, 2).into(),
+                Rational32::new(5, 2).into()
+            )),
+            "1+0i" => cell![1],
+            "2@0" => cell![2],
+            "1+" => cell!["1+"],
+            "1+2" => cell!["1+2"],
+            "1+2i+3i" => cell!["1+2i+3i"]
+        }
+
+        parses! {
+            "+inf.0" => Cell::Number(f64::INFINITY.into()),
+            "-inf.0" => Cell::Number(f64::NEG_INFINITY.into()),
+            "1e3" => Cell::Number(1000.0.into()),
+            "1." => Cell::Number(1.0.into()),
+            ".5E-1" => Cell::Number(0.05.into()),
+            "#e1.5e3" => cell![1500],
+            "#e1.2" => Cell::Number(Rational32::new(6, 5).into()),
+            "#e#x10" => cell![16],
+            "#x#e10" => cell![16],
+            "#X#I10" => Cell::Number(16.0.into()),
+            "#i1/4" => Cell::Number(0.25.into()),
+            "1e" => cell!["1e"],
+            "1e+" => cell!["1e+"],
+            "-inf" => cell!["-inf"]
+        }
+        fails!["#e#i1", "#x#b1", "#e+inf.0", "#x1e+2", "#b2", "#x (1)"];
     }
 
     #[test]
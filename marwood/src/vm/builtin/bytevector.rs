@@ -0,0 +1,168 @@
+use crate::error::Error;
+use crate::error::Error::{InvalidBytevectorIndex, InvalidStringIndex, InvalidSyntax};
+use crate::number::Number;
+use crate::vm::builtin::{
+    pop_argc, pop_bytevector, pop_index, pop_range, pop_string, range_bounds,
+};
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+
+pub fn load_builtins(vm: &mut Vm) {
+    vm.load_builtin("bytevector", bytevector);
+    vm.load_builtin("make-bytevector", make_bytevector);
+    vm.load_builtin("bytevector-length", bytevector_length);
+    vm.load_builtin("bytevector-u8-ref", bytevector_u8_ref);
+    vm.load_builtin("bytevector-u8-set!", bytevector_u8_set);
+    vm.load_builtin("bytevector-copy", bytevector_copy);
+    vm.load_builtin("bytevector-copy!", bytevector_mut_copy);
+    vm.load_builtin("bytevector-append", bytevector_append);
+    vm.load_builtin("utf8->string", utf8_string);
+    vm.load_builtin("string->utf8", string_utf8);
+}
+
+/// Pop Byte
+///
+/// Pop an exact integer between 0 and 255 off the stack
+fn pop_byte(vm: &mut Vm, proc: &str) -> Result<u8, Error> {
+    match vm.heap.get(vm.stack.pop()?) {
+        VCell::Number(Number::Fixnum(byte)) if u8::try_from(byte).is_ok() => Ok(byte as u8),
+        vcell => Err(InvalidSyntax(format!(
+            "bad argument to {}: {:#} is not a byte",
+            proc,
+            vm.heap.get_as_cell(&vcell)
+        ))),
+    }
+}
+
+pub fn bytevector(vm: &mut Vm) -> Result<VCell, Error> {
+    let len = pop_argc(vm, 0, None, "bytevector")?;
+    let mut outv = vec![0; len];
+    for idx in (0..len).rev() {
+        *outv.get_mut(idx).unwrap() = pop_byte(vm, "bytevector")?;
+    }
+    Ok(VCell::bytevector(outv))
+}
+
+pub fn make_bytevector(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, Some(2), "make-bytevector")?;
+    let fill = match argc {
+        2 => pop_byte(vm, "make-bytevector")?,
+        _ => 0,
+    };
+    let len = pop_index(vm, "make-bytevector")?;
+    Ok(VCell::bytevector(vec![fill; len]))
+}
+
+pub fn bytevector_length(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "bytevector-length")?;
+    let bytevector = pop_bytevector(vm, "bytevector-length")?;
+    let len = bytevector.borrow().len();
+    Ok(Number::from(len as i64).into())
+}
+
+pub fn bytevector_u8_ref(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "bytevector-u8-ref")?;
+    let idx = pop_index(vm, "bytevector-u8-ref")?;
+    let bytevector = pop_bytevector(vm, "bytevector-u8-ref")?;
+    let bytevector = bytevector.borrow();
+    match bytevector.get(idx) {
+        Some(byte) => Ok(Number::from(*byte as i64).into()),
+        None => Err(InvalidBytevectorIndex(idx, bytevector.len())),
+    }
+}
+
+pub fn bytevector_u8_set(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 3, Some(3), "bytevector-u8-set!")?;
+    let byte = pop_byte(vm, "bytevector-u8-set!")?;
+    let idx = pop_index(vm, "bytevector-u8-set!")?;
+    let bytevector = pop_bytevector(vm, "bytevector-u8-set!")?;
+    let mut bytevector = bytevector.borrow_mut();
+    let len = bytevector.len();
+    match bytevector.get_mut(idx) {
+        Some(it) => *it = byte,
+        None => return Err(InvalidBytevectorIndex(idx, len)),
+    }
+    Ok(VCell::Void)
+}
+
+// (bytevector-copy bytevector start end)
+pub fn bytevector_copy(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, Some(3), "bytevector-copy")?;
+    let (start, end) = pop_range(vm, argc, 1, "bytevector-copy")?;
+    let bytevector = pop_bytevector(vm, "bytevector-copy")?;
+    let bytevector = bytevector.borrow();
+    let (start, end) = range_bounds(start, end, bytevector.len())
+        .map_err(|idx| InvalidBytevectorIndex(idx, bytevector.len()))?;
+    Ok(VCell::bytevector(&bytevector[start..end]))
+}
+
+// (bytevector-copy! to at from start end)
+pub fn bytevector_mut_copy(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 3, Some(5), "bytevector-copy!")?;
+    let (start, end) = pop_range(vm, argc, 3, "bytevector-copy!")?;
+    let from = pop_bytevector(vm, "bytevector-copy!")?;
+    let at = pop_index(vm, "bytevector-copy!")?;
+    let to = pop_bytevector(vm, "bytevector-copy!")?;
+
+    // Copy the source range first, which allows from and to to be the
+    // same bytevector.
+    let bytes = {
+        let from = from.borrow();
+        let (start, end) = range_bounds(start, end, from.len())
+            .map_err(|idx| InvalidBytevectorIndex(idx, from.len()))?;
+        from[start..end].to_vec()
+    };
+
+    let mut to = to.borrow_mut();
+    if at > to.len() {
+        return Err(InvalidBytevectorIndex(at, to.len()));
+    }
+    if at + bytes.len() > to.len() {
+        return Err(InvalidSyntax(
+            "bytevector-copy!: to bytevector is too small".into(),
+        ));
+    }
+    to[at..at + bytes.len()].copy_from_slice(&bytes);
+    Ok(VCell::Void)
+}
+
+pub fn bytevector_append(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 0, None, "bytevector-append")?;
+    let mut bytevectors = vec![];
+    for _ in 0..argc {
+        bytevectors.push(pop_bytevector(vm, "bytevector-append")?);
+    }
+    let mut outv = vec![];
+    for bytevector in bytevectors.iter().rev() {
+        outv.extend_from_slice(&bytevector.borrow());
+    }
+    Ok(VCell::bytevector(outv))
+}
+
+// (utf8->string bytevector start end)
+pub fn utf8_string(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, Some(3), "utf8->string")?;
+    let (start, end) = pop_range(vm, argc, 1, "utf8->string")?;
+    let bytevector = pop_bytevector(vm, "utf8->string")?;
+    let bytevector = bytevector.borrow();
+    let (start, end) = range_bounds(start, end, bytevector.len())
+        .map_err(|idx| InvalidBytevectorIndex(idx, bytevector.len()))?;
+    match std::str::from_utf8(&bytevector[start..end]) {
+        Ok(s) => Ok(VCell::string(s)),
+        Err(_) => Err(InvalidSyntax(
+            "bad argument to utf8->string: bytevector is not valid utf-8".into(),
+        )),
+    }
+}
+
+// (string->utf8 string start end)
+pub fn string_utf8(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, Some(3), "string->utf8")?;
+    let (start, end) = pop_range(vm, argc, 1, "string->utf8")?;
+    let s = pop_string(vm, "string->utf8")?;
+    let s = s.borrow();
+    let len = s.chars().count();
+    let (start, end) = range_bounds(start, end, len).map_err(|idx| InvalidStringIndex(idx, len))?;
+    let substr = s.chars().skip(start).take(end - start).collect::<String>();
+    Ok(VCell::bytevector(substr.into_bytes()))
+}
@@ -0,0 +1,139 @@
+use crate::error::Error;
+use crate::error::Error::InvalidSyntax;
+use crate::number::Number;
+use crate::vm::builtin::{pop_argc, pop_integer};
+use crate::vm::environment::Environment;
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+use std::rc::Rc;
+
+pub fn load_builtins(vm: &mut Vm) {
+    vm.load_builtin("copy-environment", copy_environment);
+    vm.load_builtin("environment", environment);
+    vm.load_builtin("environment?", is_environment);
+    vm.load_builtin("interaction-environment", interaction_environment);
+    vm.load_builtin("null-environment", null_environment);
+    vm.load_builtin("scheme-report-environment", scheme_report_environment);
+}
+
+/// Pop Environment
+///
+/// Pop an environment off the stack, returning an error if the value
+/// is not an environment.
+pub fn pop_environment(vm: &mut Vm, proc: &str) -> Result<Rc<Environment>, Error> {
+    match vm.heap.get(vm.stack.pop()?) {
+        VCell::Environment(environment) => Ok(environment),
+        vcell => Err(InvalidSyntax(format!(
+            "bad argument to {}: {:#} is not an environment",
+            proc,
+            vm.heap.get_as_cell(&vcell)
+        ))),
+    }
+}
+
+/// Pop Version
+///
+/// Pop the version of the report given to scheme-report-environment or
+/// null-environment off the stack. Versions 5 and 7 are supported.
+fn pop_version(vm: &mut Vm, proc: &str) -> Result<(), Error> {
+    match pop_integer(vm)? {
+        num if num == Number::from(5) || num == Number::from(7) => Ok(()),
+        num => Err(InvalidSyntax(format!(
+            "{}: unsupported version {}",
+            proc, num
+        ))),
+    }
+}
+
+fn is_environment(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "environment?")?;
+    Ok(vm.heap.get(vm.stack.pop()?).is_environment().into())
+}
+
+fn interaction_environment(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 0, Some(0), "interaction-environment")?;
+    Ok(VCell::Environment(vm.interaction_environment()))
+}
+
+/// environment
+///
+/// (environment import-set ...)
+///
+/// Return a new environment containing the bindings named by each import
+/// set, loading the source of any library that isn't yet defined.
+fn environment(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 0, None, "environment")?;
+    let mut sets = VCell::Nil;
+    for _ in 0..argc {
+        let set = vm.heap.put(vm.stack.pop()?.clone());
+        let rest = vm.heap.put(sets);
+        sets = VCell::pair(set.as_ptr()?, rest.as_ptr()?);
+    }
+    let sets = vm.heap.put(sets);
+    let environment = Rc::new(Environment::derived());
+    vm.import_sets(sets, environment.clone(), VCell::Environment(environment))
+}
+
+/// scheme-report-environment
+///
+/// Return a new environment containing the standard bindings, which is
+/// unaffected by definitions made in the interaction environment.
+fn scheme_report_environment(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "scheme-report-environment")?;
+    pop_version(vm, "scheme-report-environment")?;
+    let report = vm.globenv.report_environment();
+    Ok(VCell::Environment(vm.copy_environment(&report, None)?))
+}
+
+/// null-environment
+///
+/// Return a new environment containing only the standard syntax.
+fn null_environment(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "null-environment")?;
+    pop_version(vm, "null-environment")?;
+    Ok(VCell::Environment(vm.null_environment()))
+}
+
+/// copy-environment
+///
+/// (copy-environment environment)
+/// (copy-environment environment symbols)
+///
+/// Return a new environment containing the bindings of environment, or the
+/// standard syntax and only the bindings of the given list of symbols. The
+/// new environment is isolated, so definitions and assignments made in
+/// either environment are not visible in the other.
+fn copy_environment(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, Some(2), "copy-environment")?;
+    let symbols = match argc {
+        2 => {
+            let list = vm.stack.pop()?.clone();
+            let mut rest = vm.heap.get(&list);
+            let mut symbols = vec![];
+            while let VCell::Pair(car, cdr) = rest {
+                match vm.heap.get_at_index(car) {
+                    VCell::Symbol(_) => symbols.push(car),
+                    _ => {
+                        return Err(InvalidSyntax(format!(
+                            "bad argument to copy-environment: {:#} is not a list of symbols",
+                            vm.heap.get_as_cell(&list)
+                        )))
+                    }
+                }
+                rest = vm.heap.get(VCell::ptr(cdr));
+            }
+            if !rest.is_nil() {
+                return Err(InvalidSyntax(format!(
+                    "bad argument to copy-environment: {:#} is not a list of symbols",
+                    vm.heap.get_as_cell(&list)
+                )));
+            }
+            Some(symbols)
+        }
+        _ => None,
+    };
+    let environment = pop_environment(vm, "copy-environment")?;
+    Ok(VCell::Environment(
+        vm.copy_environment(&environment, symbols.as_deref())?,
+    ))
+}
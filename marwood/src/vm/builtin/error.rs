@@ -0,0 +1,106 @@
+use crate::cell::Cell;
+use crate::error::Error;
+use crate::error::Error::{ErrorSignal, InvalidSyntax};
+use crate::vm::builtin::pop_argc;
+use crate::vm::condition::{Condition, ConditionKind};
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+use std::rc::Rc;
+
+pub fn load_builtins(vm: &mut Vm) {
+    vm.load_builtin("error", error);
+    vm.load_builtin("error-object?", is_error_object);
+    vm.load_builtin("error-object-irritants", error_object_irritants);
+    vm.load_builtin("error-object-kind", error_object_kind);
+    vm.load_builtin("error-object-message", error_object_message);
+    vm.load_builtin("file-error?", is_file_error);
+    vm.load_builtin("read-error?", is_read_error);
+}
+
+/// Error
+///
+/// Create a condition from the message and irritants on the stack, and
+/// raise it to the current exception handler. If there is no handler, the
+/// condition results in an ErrorSignal.
+fn error(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, None, "error")?;
+    let mut irritants = vec![VCell::Void; argc - 1];
+    for it in 0..argc - 1 {
+        *irritants.get_mut(argc - it - 2).unwrap() = vm.stack.pop()?.clone();
+    }
+    let message = vm.stack.pop()?.clone();
+
+    let message = vm.heap.get_as_cell(&message);
+    let signal = std::iter::once(message.clone())
+        .chain(irritants.iter().map(|it| vm.heap.get_as_cell(it)))
+        .collect::<Vec<Cell>>();
+    let condition = Condition::new(
+        ConditionKind::Error,
+        message.to_string(),
+        irritants,
+        ErrorSignal(signal),
+    );
+    vm.raise(VCell::Condition(Rc::new(condition)), false)
+}
+
+fn pop_condition(vm: &mut Vm, proc: &str) -> Result<Rc<Condition>, Error> {
+    match vm.heap.get(vm.stack.pop()?) {
+        VCell::Condition(condition) => Ok(condition),
+        vcell => Err(InvalidSyntax(format!(
+            "bad argument to {}: {:#} is not an error object",
+            proc,
+            vm.heap.get_as_cell(&vcell)
+        ))),
+    }
+}
+
+fn is_error_object(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "error-object?")?;
+    let result = vm.heap.get(vm.stack.pop()?);
+    Ok(result.is_condition().into())
+}
+
+fn is_file_error(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "file-error?")?;
+    Ok(match vm.heap.get(vm.stack.pop()?) {
+        VCell::Condition(condition) => condition.kind() == ConditionKind::FileError,
+        _ => false,
+    }
+    .into())
+}
+
+fn is_read_error(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "read-error?")?;
+    Ok(match vm.heap.get(vm.stack.pop()?) {
+        VCell::Condition(condition) => condition.kind() == ConditionKind::ReadError,
+        _ => false,
+    }
+    .into())
+}
+
+fn error_object_message(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "error-object-message")?;
+    let condition = pop_condition(vm, "error-object-message")?;
+    Ok(VCell::string(condition.message()))
+}
+
+fn error_object_irritants(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "error-object-irritants")?;
+    let condition = pop_condition(vm, "error-object-irritants")?;
+    let mut tail = vm.heap.put(VCell::Nil);
+    for irritant in condition.irritants().iter().rev() {
+        let car = vm.heap.put(irritant.clone());
+        tail = vm.heap.put(VCell::Pair(car.as_ptr()?, tail.as_ptr()?));
+    }
+    Ok(tail)
+}
+
+/// Error Object Kind
+///
+/// Return the kind of the condition as a symbol, such as type-error or
+/// range-error. Conditions created by error are of the kind error.
+fn error_object_kind(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "error-object-kind")?;
+    let condition = pop_condition(vm, "error-object-kind")?;
+    Ok(vm.heap.put(VCell::symbol(condition.kind().symbol())))
+}
@@ -0,0 +1,89 @@
+use crate::cell::Cell;
+use crate::error::Error;
+use crate::vm::builtin::environment::pop_environment;
+use crate::vm::builtin::{pop_argc, pop_string};
+use crate::vm::port::Port;
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+use std::rc::Rc;
+
+pub fn load_builtins(vm: &mut Vm) {
+    vm.load_builtin("open-input-file", open_input_file);
+    vm.load_builtin("open-output-file", open_output_file);
+    vm.load_builtin("file-exists?", is_file_exists);
+    vm.load_builtin("delete-file", delete_file);
+    vm.load_builtin("load", load);
+}
+
+/// Open Input File
+///
+/// The contents of the file are read when it's opened, and the resulting
+/// port reads from them as a string input port.
+pub fn open_input_file(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "open-input-file")?;
+    let path = pop_string(vm, "open-input-file")?;
+    let text = vm.filesystem().read_file(path.borrow().as_str())?;
+    Ok(VCell::Port(Rc::new(Port::new_input_string(text))))
+}
+
+/// Open Output File
+///
+/// The file is created, or truncated if it already exists, and the
+/// resulting port writes to it through a handle that's held open until the
+/// port is closed.
+pub fn open_output_file(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "open-output-file")?;
+    let path = pop_string(vm, "open-output-file")?;
+    let path = path.borrow().clone();
+    let handle = vm.filesystem().open_output_file(&path)?;
+    Ok(VCell::Port(Rc::new(Port::new_output_file(path, handle))))
+}
+
+pub fn is_file_exists(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "file-exists?")?;
+    let path = pop_string(vm, "file-exists?")?;
+    let exists = vm.filesystem().file_exists(path.borrow().as_str());
+    Ok(exists.into())
+}
+
+pub fn delete_file(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "delete-file")?;
+    let path = pop_string(vm, "delete-file")?;
+    vm.filesystem().delete_file(path.borrow().as_str())?;
+    Ok(VCell::Void)
+}
+
+/// Load
+///
+/// (load filename)
+/// (load filename environment)
+///
+/// Read each expression in the file and evaluate them in order against
+/// environment, or the interaction environment if none is given. An error
+/// reading or evaluating the file is reported as a LoadError naming it.
+pub fn load(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, Some(2), "load")?;
+    let environment = match argc {
+        2 => pop_environment(vm, "load")?,
+        _ => vm.interaction_environment(),
+    };
+    let path = pop_string(vm, "load")?;
+    let path = path.borrow().clone();
+    let exprs = vm.read_source(&path, false)?;
+    let exprs = vm.heap.put_cell(&Cell::new_list(exprs));
+    vm.eval_sequence(
+        exprs,
+        environment,
+        Some(&path),
+        "load",
+        load_resume,
+        VCell::Void,
+    )
+}
+
+fn load_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "load")?;
+    vm.stack.pop()?;
+    vm.stack.pop()?;
+    Ok(VCell::Void)
+}
@@ -1,16 +1,22 @@
 use crate::error::Error;
 use crate::error::Error::{ExpectedPairButFound, InvalidSyntax};
-use crate::vm::builtin::{pop_argc, pop_index};
+use crate::number::Number;
+use crate::vm::builtin::{pop_argc, pop_index, pop_procedure, pop_usize};
 use crate::vm::vcell::VCell;
 use crate::vm::Vm;
 
 pub fn load_builtins(vm: &mut Vm) {
     vm.load_builtin("append", append);
+    vm.load_builtin("assoc", assoc);
     vm.load_builtin("car", car);
     vm.load_builtin("cdr", cdr);
     vm.load_builtin("cons", cons);
+    vm.load_builtin("list-copy", list_copy);
     vm.load_builtin("list-ref", list_ref);
+    vm.load_builtin("list-set!", list_set);
     vm.load_builtin("list-tail", list_tail);
+    vm.load_builtin("make-list", make_list);
+    vm.load_builtin("member", member);
     vm.load_builtin("reverse", reverse);
     vm.load_builtin("set-car!", set_car);
     vm.load_builtin("set-cdr!", set_cdr);
@@ -224,3 +230,201 @@ pub fn list_tail(vm: &mut Vm) -> Result<VCell, Error> {
     }
     get_list_tail(vm, &list_ptr, idx)
 }
+
+pub fn list_set(vm: &mut Vm) -> Result<VCell, Error> {
+    let _ = pop_argc(vm, 3, Some(3), "list-set!")?;
+    let obj = vm.heap.put(vm.stack.pop()?.clone());
+    let idx = pop_index(vm, "list-set!")?;
+    let list_ptr = vm.stack.pop()?.clone();
+    let list = vm.heap.get(&list_ptr);
+    if !list.is_pair() && !list.is_nil() {
+        return Err(ExpectedPairButFound(vm.heap.get_as_cell(&list)));
+    }
+    let tail = get_list_tail(vm, &list_ptr, idx)?;
+    let new_pair = match vm.heap.get(&tail) {
+        VCell::Pair(_, cdr) => VCell::Pair(obj.as_ptr()?, cdr),
+        _ => {
+            return Err(InvalidSyntax(format!(
+                "{} is out of range for {:#}",
+                idx,
+                vm.heap.get_as_cell(&list)
+            )))
+        }
+    };
+    *vm.heap.get_at_index_mut(tail.as_ptr()?) = new_pair;
+    Ok(VCell::Void)
+}
+
+pub fn list_copy(vm: &mut Vm) -> Result<VCell, Error> {
+    let _ = pop_argc(vm, 1, Some(1), "list-copy")?;
+    let mut tail = vm.stack.pop()?.clone();
+    let mut cars = vec![];
+    while let VCell::Pair(car, cdr) = vm.heap.get(&tail) {
+        cars.push(car);
+        tail = VCell::ptr(cdr);
+    }
+    if cars.is_empty() {
+        return Ok(tail);
+    }
+    for car in cars.into_iter().rev() {
+        tail = vm.heap.put(VCell::Pair(car, tail.as_ptr()?));
+    }
+    Ok(tail)
+}
+
+pub fn make_list(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, Some(2), "make-list")?;
+    let fill = match argc {
+        2 => vm.stack.pop()?.clone(),
+        _ => VCell::Number(Number::from(0)),
+    };
+    let fill = vm.heap.put(fill).as_ptr()?;
+    let len = pop_usize(vm)?;
+    let mut tail = vm.heap.put(VCell::Nil);
+    for _ in 0..len {
+        tail = vm.heap.put(VCell::Pair(fill, tail.as_ptr()?));
+    }
+    Ok(tail)
+}
+
+/// member
+///
+/// Return the first tail of list whose car is equal to obj, or #f. Equality
+/// is tested with equal?, or by applying compare to obj and each element if
+/// compare is given.
+pub fn member(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 2, Some(3), "member")?;
+    let compare = match argc {
+        3 => Some(pop_procedure(vm, "member")?),
+        _ => None,
+    };
+    let list = vm.heap.put(vm.stack.pop()?.clone());
+    let obj = vm.stack.pop()?.clone();
+    match compare {
+        Some(compare) => member_next(vm, compare, obj, list),
+        None => {
+            let mut rest = list;
+            loop {
+                match vm.heap.get(&rest) {
+                    VCell::Nil => return Ok(VCell::Bool(false)),
+                    VCell::Pair(car, _) if vm.equal(&obj, &VCell::ptr(car))? => return Ok(rest),
+                    VCell::Pair(_, cdr) => rest = VCell::ptr(cdr),
+                    vcell => return Err(ExpectedPairButFound(vm.heap.get_as_cell(&vcell))),
+                }
+            }
+        }
+    }
+}
+
+/// Member Next
+///
+/// Apply compare to obj and the car of rest, resuming with member_resume
+/// and a state of #(compare obj rest).
+fn member_next(vm: &mut Vm, compare: VCell, obj: VCell, rest: VCell) -> Result<VCell, Error> {
+    match vm.heap.get(&rest) {
+        VCell::Nil => Ok(VCell::Bool(false)),
+        VCell::Pair(car, _) => {
+            let state = VCell::vector(vec![compare.clone(), obj.clone(), rest]);
+            vm.callback(
+                compare,
+                &[obj, VCell::ptr(car)],
+                "member",
+                member_resume,
+                state,
+            )
+        }
+        vcell => Err(ExpectedPairButFound(vm.heap.get_as_cell(&vcell))),
+    }
+}
+
+fn member_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "member")?;
+    let found = vm.heap.get(vm.stack.pop()?);
+    let state = vm.heap.get(vm.stack.pop()?);
+    let state = state.as_vector()?;
+    let rest = state.get(2).unwrap();
+    if found != VCell::Bool(false) {
+        return Ok(rest);
+    }
+    let rest = vm.heap.get(&rest).as_cdr()?;
+    member_next(vm, state.get(0).unwrap(), state.get(1).unwrap(), rest)
+}
+
+/// assoc
+///
+/// Return the first pair in alist whose car is equal to obj, or #f. Equality
+/// is tested with equal?, or by applying compare to obj and each key if
+/// compare is given. Elements of alist that are not pairs are skipped.
+pub fn assoc(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 2, Some(3), "assoc")?;
+    let compare = match argc {
+        3 => Some(pop_procedure(vm, "assoc")?),
+        _ => None,
+    };
+    let alist = vm.heap.put(vm.stack.pop()?.clone());
+    let obj = vm.stack.pop()?.clone();
+    match compare {
+        Some(compare) => assoc_next(vm, compare, obj, alist),
+        None => {
+            let mut rest = alist;
+            loop {
+                match vm.heap.get(&rest) {
+                    VCell::Nil => return Ok(VCell::Bool(false)),
+                    VCell::Pair(car, cdr) => {
+                        if let VCell::Pair(key, _) = vm.heap.get(VCell::ptr(car)) {
+                            if vm.equal(&obj, &VCell::ptr(key))? {
+                                return Ok(VCell::ptr(car));
+                            }
+                        }
+                        rest = VCell::ptr(cdr);
+                    }
+                    vcell => return Err(ExpectedPairButFound(vm.heap.get_as_cell(&vcell))),
+                }
+            }
+        }
+    }
+}
+
+/// Assoc Next
+///
+/// Apply compare to obj and the key of the first pair in rest, resuming
+/// with assoc_resume and a state of #(compare obj rest).
+fn assoc_next(vm: &mut Vm, compare: VCell, obj: VCell, rest: VCell) -> Result<VCell, Error> {
+    let mut rest = rest;
+    loop {
+        match vm.heap.get(&rest) {
+            VCell::Nil => return Ok(VCell::Bool(false)),
+            VCell::Pair(car, cdr) => match vm.heap.get(VCell::ptr(car)) {
+                VCell::Pair(key, _) => {
+                    let state = VCell::vector(vec![compare.clone(), obj.clone(), rest]);
+                    return vm.callback(
+                        compare,
+                        &[obj, VCell::ptr(key)],
+                        "assoc",
+                        assoc_resume,
+                        state,
+                    );
+                }
+                _ => rest = VCell::ptr(cdr),
+            },
+            vcell => return Err(ExpectedPairButFound(vm.heap.get_as_cell(&vcell))),
+        }
+    }
+}
+
+fn assoc_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "assoc")?;
+    let found = vm.heap.get(vm.stack.pop()?);
+    let state = vm.heap.get(vm.stack.pop()?);
+    let state = state.as_vector()?;
+    let rest = vm.heap.get(state.get(2).unwrap());
+    if found != VCell::Bool(false) {
+        return rest.as_car();
+    }
+    assoc_next(
+        vm,
+        state.get(0).unwrap(),
+        state.get(1).unwrap(),
+        rest.as_cdr()?,
+    )
+}
@@ -7,13 +7,19 @@ use crate::vm::Vm;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+mod bytevector;
 mod char;
+mod environment;
+mod error;
+mod file;
 mod list;
 mod number;
 mod ports;
 mod predicate;
 mod procedure;
+mod promise;
 mod rand;
+mod sort;
 mod string;
 mod symbol;
 mod vector;
@@ -38,13 +44,19 @@ mod vector;
 
 impl Vm {
     pub fn load_builtins(&mut self) {
+        bytevector::load_builtins(self);
         char::load_builtins(self);
+        environment::load_builtins(self);
+        error::load_builtins(self);
+        file::load_builtins(self);
         list::load_builtins(self);
         number::load_builtins(self);
         ports::load_builtins(self);
         predicate::load_builtins(self);
         procedure::load_builtins(self);
+        promise::load_builtins(self);
         rand::load_builtins(self);
+        sort::load_builtins(self);
         string::load_builtins(self);
         symbol::load_builtins(self);
         vector::load_builtins(self);
@@ -66,7 +78,7 @@ impl Vm {
 ///
 /// Pop the number of arguments applied to a procedure off the top of
 /// the stack. Return an error if they don't match the expected.
-fn pop_argc(vm: &mut Vm, min: usize, max: Option<usize>, proc: &str) -> Result<usize, Error> {
+pub fn pop_argc(vm: &mut Vm, min: usize, max: Option<usize>, proc: &str) -> Result<usize, Error> {
     let argc = vm.stack.pop()?.as_argc()?;
     if argc < min || (max.is_some() && argc > max.unwrap()) {
         Err(InvalidNumArgs(proc.into()))
@@ -92,6 +104,14 @@ fn pop_number(vm: &mut Vm) -> Result<Number, Error> {
     }
 }
 
+fn pop_real(vm: &mut Vm) -> Result<Number, Error> {
+    match pop_number(vm) {
+        Ok(num) if num.is_real() => Ok(num),
+        Ok(num) => Err(InvalidSyntax(format!("{} is not a valid real number", num))),
+        Err(e) => Err(e),
+    }
+}
+
 fn pop_integer(vm: &mut Vm) -> Result<Number, Error> {
     match pop_number(vm) {
         Ok(num) if num.is_integer() => Ok(num),
@@ -135,6 +155,17 @@ fn pop_string(vm: &mut Vm, proc: &str) -> Result<Rc<RefCell<String>>, Error> {
     }
 }
 
+fn pop_bytevector(vm: &mut Vm, proc: &str) -> Result<Rc<RefCell<Vec<u8>>>, Error> {
+    match vm.heap.get(vm.stack.pop()?) {
+        VCell::Bytevector(bytevector) => Ok(bytevector),
+        vcell => Err(InvalidSyntax(format!(
+            "bad argument to {}: {:#} is not a bytevector",
+            proc,
+            vm.heap.get_as_cell(&vcell)
+        ))),
+    }
+}
+
 fn pop_symbol(vm: &mut Vm, proc: &str) -> Result<Rc<String>, Error> {
     match vm.heap.get(vm.stack.pop()?) {
         VCell::Symbol(s) => Ok(s),
@@ -176,3 +207,62 @@ fn pop_vector(vm: &mut Vm) -> Result<Rc<Vector>, Error> {
         }
     }
 }
+
+fn pop_procedure(vm: &mut Vm, proc: &str) -> Result<VCell, Error> {
+    match vm.stack.pop()?.clone() {
+        vcell if vm.heap.get(&vcell).is_procedure() => Ok(vcell),
+        vcell => Err(InvalidSyntax(format!(
+            "bad argument to {}: {:#} is not a procedure",
+            proc,
+            vm.heap.get_as_cell(&vcell)
+        ))),
+    }
+}
+
+/// Pop Range
+///
+/// Pop the optional start and end arguments off the stack, given the
+/// number of arguments that precede them.
+///
+/// # Arguments
+/// `argc` - The number of arguments applied to the procedure
+/// `required` - The number of arguments that precede start and end
+/// `proc` - The name of the procedure, used in error messages
+fn pop_range(
+    vm: &mut Vm,
+    argc: usize,
+    required: usize,
+    proc: &str,
+) -> Result<(Option<usize>, Option<usize>), Error> {
+    let end = match argc > required + 1 {
+        true => Some(pop_index(vm, proc)?),
+        false => None,
+    };
+    let start = match argc > required {
+        true => Some(pop_index(vm, proc)?),
+        false => None,
+    };
+    Ok((start, end))
+}
+
+/// Range Bounds
+///
+/// Resolve the optional start and end of a range over a sequence of
+/// length len, which defaults to the entire sequence. The index that's out
+/// of range is returned as the error, so that the caller may report it in
+/// terms of a bytevector or string index.
+fn range_bounds(
+    start: Option<usize>,
+    end: Option<usize>,
+    len: usize,
+) -> Result<(usize, usize), usize> {
+    let start = start.unwrap_or(0);
+    let end = end.unwrap_or(len);
+    if end > len {
+        Err(end)
+    } else if start > end {
+        Err(start)
+    } else {
+        Ok((start, end))
+    }
+}
@@ -1,7 +1,7 @@
 use crate::error::Error;
 use crate::error::Error::{InvalidArgs, InvalidSyntax};
-use crate::number::{Exactness, Number};
-use crate::vm::builtin::{pop_argc, pop_integer, pop_number, pop_string, pop_usize};
+use crate::number::Number;
+use crate::vm::builtin::{pop_argc, pop_integer, pop_number, pop_real, pop_string, pop_usize};
 use crate::vm::vcell::VCell;
 use crate::vm::Vm;
 
@@ -18,21 +18,39 @@ pub fn load_builtins(vm: &mut Vm) {
     vm.load_builtin("%", remainder);
     vm.load_builtin("abs", abs);
     vm.load_builtin("acos", acos);
+    vm.load_builtin("angle", angle);
     vm.load_builtin("asin", asin);
     vm.load_builtin("atan", atan);
     vm.load_builtin("ceiling", ceiling);
     vm.load_builtin("cos", cos);
     vm.load_builtin("denominator", denominator);
     vm.load_builtin("even?", even);
+    vm.load_builtin("exact", exact);
+    vm.load_builtin("exact?", is_exact);
     vm.load_builtin("exact->inexact", exact_inexact);
+    vm.load_builtin("exact-integer-sqrt", exact_integer_sqrt);
     vm.load_builtin("exp", exp);
     vm.load_builtin("expt", expt);
+    vm.load_builtin("finite?", is_finite);
     vm.load_builtin("floor", floor);
-    vm.load_builtin("inexact->exact", inexact_exact);
+    vm.load_builtin("floor/", floor_div);
+    vm.load_builtin("floor-quotient", floor_quotient);
+    vm.load_builtin("floor-remainder", modulo);
+    vm.load_builtin("gcd", gcd);
+    vm.load_builtin("imag-part", imag_part);
+    vm.load_builtin("inexact", exact_inexact);
+    vm.load_builtin("inexact?", is_inexact);
+    vm.load_builtin("inexact->exact", exact);
+    vm.load_builtin("infinite?", is_infinite);
+    vm.load_builtin("lcm", lcm);
     vm.load_builtin("log", log);
+    vm.load_builtin("magnitude", magnitude);
+    vm.load_builtin("make-polar", make_polar);
+    vm.load_builtin("make-rectangular", make_rectangular);
     vm.load_builtin("min", min);
     vm.load_builtin("max", max);
     vm.load_builtin("modulo", modulo);
+    vm.load_builtin("nan?", is_nan);
     vm.load_builtin("numerator", numerator);
     vm.load_builtin("number->string", number_string);
     vm.load_builtin("negative?", negative);
@@ -40,13 +58,19 @@ pub fn load_builtins(vm: &mut Vm) {
     vm.load_builtin("pow", expt);
     vm.load_builtin("positive?", positive);
     vm.load_builtin("quotient", quotient);
+    vm.load_builtin("rationalize", rationalize);
+    vm.load_builtin("real-part", real_part);
     vm.load_builtin("remainder", remainder);
     vm.load_builtin("round", round);
     vm.load_builtin("sin", sin);
     vm.load_builtin("sqrt", sqrt);
+    vm.load_builtin("square", square);
     vm.load_builtin("string->number", string_number);
     vm.load_builtin("tan", tan);
     vm.load_builtin("truncate", truncate);
+    vm.load_builtin("truncate/", truncate_div);
+    vm.load_builtin("truncate-quotient", quotient);
+    vm.load_builtin("truncate-remainder", remainder);
     vm.load_builtin("zero?", zero);
 }
 
@@ -282,6 +306,164 @@ pub fn quotient(vm: &mut Vm) -> Result<VCell, Error> {
     Ok(result.into())
 }
 
+/// Pop Division Args
+///
+/// Pop the dividend and divisor off the stack for the integer division
+/// procedure name, returning an error if the divisor is zero.
+fn pop_division_args(vm: &mut Vm, name: &str) -> Result<(Number, Number), Error> {
+    pop_argc(vm, 2, Some(2), name)?;
+    let y = pop_integer(vm)?;
+    let x = pop_integer(vm)?;
+
+    if y.is_zero() {
+        return Err(InvalidSyntax(format!("{} is undefined for 0", name)));
+    }
+
+    Ok((x, y))
+}
+
+fn undefined_division(name: &str, x: &Number, y: &Number) -> Error {
+    InvalidSyntax(format!("{} is undefined for {} % {}", name, x, y))
+}
+
+pub fn floor_quotient(vm: &mut Vm) -> Result<VCell, Error> {
+    let (x, y) = pop_division_args(vm, "floor-quotient")?;
+    match x.floor_quotient(&y) {
+        Some(num) => Ok(num.into()),
+        None => Err(undefined_division("floor-quotient", &x, &y)),
+    }
+}
+
+/// (floor/ n1 n2)
+///
+/// Return the floor quotient and floor remainder of n1 and n2 as
+/// multiple values.
+pub fn floor_div(vm: &mut Vm) -> Result<VCell, Error> {
+    let (x, y) = pop_division_args(vm, "floor/")?;
+    match (x.floor_quotient(&y), x.modulo(&y)) {
+        (Some(quotient), Some(remainder)) => {
+            Ok(VCell::values(vec![quotient.into(), remainder.into()]))
+        }
+        _ => Err(undefined_division("floor/", &x, &y)),
+    }
+}
+
+/// (truncate/ n1 n2)
+///
+/// Return the truncate quotient and truncate remainder of n1 and n2 as
+/// multiple values.
+pub fn truncate_div(vm: &mut Vm) -> Result<VCell, Error> {
+    let (x, y) = pop_division_args(vm, "truncate/")?;
+    match (x.quotient(&y), &x % &y) {
+        (Some(quotient), Some(remainder)) => {
+            Ok(VCell::values(vec![quotient.into(), remainder.into()]))
+        }
+        _ => Err(undefined_division("truncate/", &x, &y)),
+    }
+}
+
+/// (exact-integer-sqrt k)
+///
+/// Return s and r as multiple values, where s^2 + r = k and s is the
+/// integer square root of k.
+pub fn exact_integer_sqrt(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "exact-integer-sqrt")?;
+    let x = pop_number(vm)?;
+    match x.exact_integer_sqrt() {
+        Some((root, remainder)) => Ok(VCell::values(vec![root.into(), remainder.into()])),
+        None => Err(InvalidSyntax(format!(
+            "exact-integer-sqrt is undefined for {}",
+            x
+        ))),
+    }
+}
+
+/// (gcd n1 ...)
+///
+/// Return the greatest common divisor of the arguments, or 0 if there
+/// are no arguments.
+pub fn gcd(vm: &mut Vm) -> Result<VCell, Error> {
+    num_fold(vm, "gcd", Number::from(0), Number::gcd)
+}
+
+/// (lcm n1 ...)
+///
+/// Return the least common multiple of the arguments, or 1 if there
+/// are no arguments.
+pub fn lcm(vm: &mut Vm) -> Result<VCell, Error> {
+    num_fold(vm, "lcm", Number::from(1), Number::lcm)
+}
+
+fn num_fold(
+    vm: &mut Vm,
+    name: &str,
+    init: Number,
+    op: impl Fn(&Number, &Number) -> Option<Number>,
+) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 0, None, name)?;
+    let mut result = init;
+    for _ in 0..argc {
+        let x = pop_integer(vm)?;
+        result = match op(&result, &x) {
+            Some(num) => num,
+            None => {
+                return Err(InvalidSyntax(format!(
+                    "{} is undefined for {} {}",
+                    name, result, x
+                )))
+            }
+        };
+    }
+    Ok(result.abs().into())
+}
+
+pub fn square(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "square")?;
+    let x = pop_number(vm)?;
+    Ok((x.clone() * x).into())
+}
+
+/// (rationalize x y)
+///
+/// Return the simplest rational number differing from x by no more than y.
+pub fn rationalize(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "rationalize")?;
+    let y = pop_real(vm)?;
+    let x = pop_real(vm)?;
+    match x.rationalize(&y) {
+        Some(num) => Ok(num.into()),
+        None => Err(InvalidSyntax(format!(
+            "rationalize is undefined for {} {}",
+            x, y
+        ))),
+    }
+}
+
+pub fn is_exact(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "exact?")?;
+    Ok(pop_number(vm)?.is_exact().into())
+}
+
+pub fn is_inexact(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "inexact?")?;
+    Ok((!pop_number(vm)?.is_exact()).into())
+}
+
+pub fn is_nan(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "nan?")?;
+    Ok(pop_number(vm)?.is_nan().into())
+}
+
+pub fn is_infinite(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "infinite?")?;
+    Ok(pop_number(vm)?.is_infinite().into())
+}
+
+pub fn is_finite(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "finite?")?;
+    Ok(pop_number(vm)?.is_finite().into())
+}
+
 macro_rules! unary_trig {
     ($vm:ident, $name:ident) => {{
         pop_argc($vm, 1, Some(1), stringify!($name))?;
@@ -331,8 +513,8 @@ pub fn atan(vm: &mut Vm) -> Result<VCell, Error> {
     let argc = pop_argc(vm, 1, Some(2), "atan2")?;
     match argc {
         2 => {
-            let y = pop_number(vm)?;
-            let x = pop_number(vm)?;
+            let y = pop_real(vm)?;
+            let x = pop_real(vm)?;
 
             if let Some(result) = y.atan2(x.clone()) {
                 Ok(result.into())
@@ -358,17 +540,20 @@ pub fn sqrt(vm: &mut Vm) -> Result<VCell, Error> {
 
 pub fn expt(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 2, Some(2), "expt")?;
-    let exp = pop_integer(vm)?;
+    let exp = pop_number(vm)?;
     let x = pop_number(vm)?;
 
-    let exp = match exp.to_u32() {
-        Some(exp) => exp,
-        None => {
-            return Err(InvalidSyntax("expt: exponent is too large".into()));
-        }
-    };
+    if exp.is_integer() && exp.abs().to_u32().is_none() {
+        return Err(InvalidSyntax("expt: exponent is too large".into()));
+    }
 
-    Ok(x.pow(exp).into())
+    match x.expt(&exp) {
+        Some(result) => Ok(result.into()),
+        None => Err(InvalidSyntax(format!(
+            "expt is undefined for {} {}",
+            x, exp
+        ))),
+    }
 }
 
 pub fn exact_inexact(vm: &mut Vm) -> Result<VCell, Error> {
@@ -380,69 +565,119 @@ pub fn exact_inexact(vm: &mut Vm) -> Result<VCell, Error> {
     }
 }
 
-pub fn inexact_exact(vm: &mut Vm) -> Result<VCell, Error> {
-    pop_argc(vm, 1, Some(1), "inexact->exact")?;
+/// (exact z)
+///
+/// Return the exact representation of z, which is an error if z has no
+/// exact equivalent (e.g. +inf.0 or +nan.0).
+pub fn exact(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "exact")?;
     let x = pop_number(vm)?;
     match x.to_exact() {
         Some(num) => Ok(num.into()),
-        None => Ok(x.into()),
+        None => Err(InvalidSyntax(format!("exact is undefined for {}", x))),
     }
 }
 
 pub fn abs(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 1, Some(1), "exact->inexact")?;
-    let x = pop_number(vm)?;
+    let x = pop_real(vm)?;
     let x = x.abs();
     Ok(x.into())
 }
 
 pub fn ceiling(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 1, Some(1), "ceiling")?;
-    let x = pop_number(vm)?;
+    let x = pop_real(vm)?;
     let x = x.ceil();
     Ok(x.into())
 }
 
 pub fn floor(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 1, Some(1), "floor")?;
-    let x = pop_number(vm)?;
+    let x = pop_real(vm)?;
     let x = x.floor();
     Ok(x.into())
 }
 
 pub fn round(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 1, Some(1), "round")?;
-    let x = pop_number(vm)?;
+    let x = pop_real(vm)?;
     let x = x.round();
     Ok(x.into())
 }
 
 pub fn truncate(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 1, Some(1), "truncate")?;
-    let x = pop_number(vm)?;
+    let x = pop_real(vm)?;
     let x = x.truncate();
     Ok(x.into())
 }
 
 pub fn numerator(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 1, Some(1), "numerator")?;
-    let x = pop_number(vm)?;
+    let x = pop_real(vm)?;
     let x = x.numerator();
     Ok(x.into())
 }
 
 pub fn denominator(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 1, Some(1), "denominator")?;
-    let x = pop_number(vm)?;
+    let x = pop_real(vm)?;
     let x = x.denominator();
     Ok(x.into())
 }
 
+/// (make-rectangular x1 x2)
+///
+/// Return the complex number x1 + x2i
+pub fn make_rectangular(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "make-rectangular")?;
+    let imag = pop_real(vm)?;
+    let real = pop_real(vm)?;
+    Ok(Number::new_complex(real, imag).into())
+}
+
+/// (make-polar x3 x4)
+///
+/// Return the complex number with magnitude x3 and angle x4
+pub fn make_polar(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "make-polar")?;
+    let angle = pop_real(vm)?;
+    let magnitude = pop_real(vm)?;
+    match Number::new_polar(&magnitude, &angle) {
+        Some(num) => Ok(num.into()),
+        None => Err(InvalidSyntax(format!(
+            "make-polar is undefined for {} {}",
+            magnitude, angle
+        ))),
+    }
+}
+
+pub fn real_part(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "real-part")?;
+    Ok(pop_number(vm)?.real_part().into())
+}
+
+pub fn imag_part(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "imag-part")?;
+    Ok(pop_number(vm)?.imag_part().into())
+}
+
+pub fn magnitude(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "magnitude")?;
+    Ok(pop_number(vm)?.magnitude().into())
+}
+
+pub fn angle(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "angle")?;
+    Ok(pop_number(vm)?.angle().into())
+}
+
 fn min(vm: &mut Vm) -> Result<VCell, Error> {
     let argc = pop_argc(vm, 2, None, "min")?;
-    let mut result = pop_number(vm)?;
+    let mut result = pop_real(vm)?;
     for _ in 0..argc - 1 {
-        let num = pop_number(vm)?;
+        let num = pop_real(vm)?;
         if num < result {
             result = num;
         }
@@ -452,9 +687,9 @@ fn min(vm: &mut Vm) -> Result<VCell, Error> {
 
 fn max(vm: &mut Vm) -> Result<VCell, Error> {
     let argc = pop_argc(vm, 2, None, "max")?;
-    let mut result = pop_number(vm)?;
+    let mut result = pop_real(vm)?;
     for _ in 0..argc - 1 {
-        let num = pop_number(vm)?;
+        let num = pop_real(vm)?;
         if num > result {
             result = num;
         }
@@ -474,7 +709,13 @@ fn number_string(vm: &mut Vm) -> Result<VCell, Error> {
         16 => format!("{:x}", num),
         8 => format!("{:o}", num),
         2 => format!("{:b}", num),
-        _ => format!("{}", num),
+        10 => format!("{}", num),
+        _ => {
+            return Err(InvalidSyntax(format!(
+                "number->string: invalid radix {}",
+                radix
+            )))
+        }
     };
 
     Ok(VCell::string(result))
@@ -488,9 +729,15 @@ fn string_number(vm: &mut Vm) -> Result<VCell, Error> {
         _ => 10_u32,
     };
     let s = pop_string(vm, "string->number")?;
+    if ![2, 8, 10, 16].contains(&radix) {
+        return Err(InvalidSyntax(format!(
+            "string->number: invalid radix {}",
+            radix
+        )));
+    }
     let s = s.borrow();
     let s = s.as_str();
-    match Number::parse_with_exactness(s, Exactness::Unspecified, radix) {
+    match Number::parse_prefixed(s, radix) {
         Some(num) => Ok(VCell::Number(num)),
         None => Ok(false.into()),
     }
@@ -1,30 +1,296 @@
+use crate::cell::Cell;
 use crate::error::Error;
-use crate::vm::builtin::pop_argc;
+use crate::error::Error::InvalidSyntax;
+use crate::vm::builtin::{pop_argc, pop_char, pop_index, pop_string};
+use crate::vm::port::Port;
 use crate::vm::vcell::VCell;
 use crate::vm::Vm;
+use std::rc::Rc;
 
 pub fn load_builtins(vm: &mut Vm) {
     vm.load_builtin("display", display);
     vm.load_builtin("write", write);
+    vm.load_builtin("newline", newline);
+    vm.load_builtin("write-char", write_char);
+    vm.load_builtin("write-string", write_string);
+    vm.load_builtin("read-char", read_char);
+    vm.load_builtin("peek-char", peek_char);
+    vm.load_builtin("read-line", read_line);
+    vm.load_builtin("read-string", read_string);
+    vm.load_builtin("read", read);
+    vm.load_builtin("char-ready?", is_char_ready);
+    vm.load_builtin("open-input-string", open_input_string);
+    vm.load_builtin("open-output-string", open_output_string);
+    vm.load_builtin("get-output-string", get_output_string);
+    vm.load_builtin("close-port", close_port);
+    vm.load_builtin("close-input-port", close_input_port);
+    vm.load_builtin("close-output-port", close_output_port);
+    vm.load_builtin("current-input-port", current_input_port);
+    vm.load_builtin("current-output-port", current_output_port);
+    vm.load_builtin("set-current-input-port!", set_current_input_port);
+    vm.load_builtin("set-current-output-port!", set_current_output_port);
+    vm.load_builtin("input-port?", is_input_port);
+    vm.load_builtin("output-port?", is_output_port);
+    vm.load_builtin("textual-port?", is_textual_port);
+    vm.load_builtin("eof-object", eof_object);
+    vm.load_builtin("eof-object?", is_eof_object);
     vm.load_builtin("term-rows", term_rows);
     vm.load_builtin("term-cols", term_cols);
     vm.load_builtin("time-utc", time_utc);
 }
 
+/// Pop Port
+///
+/// Pop a port off the stack
+fn pop_port(vm: &mut Vm, proc: &str) -> Result<Rc<Port>, Error> {
+    match vm.heap.get(vm.stack.pop()?) {
+        VCell::Port(port) => Ok(port),
+        vcell => Err(InvalidSyntax(format!(
+            "bad argument to {}: {:#} is not a port",
+            proc,
+            vm.heap.get_as_cell(&vcell)
+        ))),
+    }
+}
+
+/// Pop Output Port
+///
+/// Pop the optional output port argument off the stack if it was given,
+/// returning the current output port otherwise.
+///
+/// # Arguments
+/// `given` - True if the port argument was applied to the procedure
+/// `proc` - The name of the procedure, used in error messages
+fn pop_output_port(vm: &mut Vm, given: bool, proc: &str) -> Result<Rc<Port>, Error> {
+    if !given {
+        vm.stack.push(vm.current_output_port());
+    }
+    let port = pop_port(vm, proc)?;
+    match port.is_output() {
+        true => Ok(port),
+        false => Err(InvalidSyntax(format!(
+            "bad argument to {}: port is not an output port",
+            proc
+        ))),
+    }
+}
+
+/// Pop Input Port
+///
+/// Pop the optional input port argument off the stack if it was given,
+/// returning the current input port otherwise.
+///
+/// # Arguments
+/// `given` - True if the port argument was applied to the procedure
+/// `proc` - The name of the procedure, used in error messages
+fn pop_input_port(vm: &mut Vm, given: bool, proc: &str) -> Result<Rc<Port>, Error> {
+    if !given {
+        vm.stack.push(vm.current_input_port());
+    }
+    let port = pop_port(vm, proc)?;
+    match port.is_input() {
+        true => Ok(port),
+        false => Err(InvalidSyntax(format!(
+            "bad argument to {}: port is not an input port",
+            proc
+        ))),
+    }
+}
+
 pub fn display(vm: &mut Vm) -> Result<VCell, Error> {
-    pop_argc(vm, 1, Some(1), "display")?;
+    let argc = pop_argc(vm, 1, Some(2), "display")?;
+    let port = pop_output_port(vm, argc == 2, "display")?;
     let obj = vm.heap.get_as_cell(vm.stack.pop()?);
-    vm.display(&obj);
+    vm.write_port(&port, &obj, false)?;
     Ok(VCell::Void)
 }
 
 pub fn write(vm: &mut Vm) -> Result<VCell, Error> {
-    pop_argc(vm, 1, Some(1), "write")?;
+    let argc = pop_argc(vm, 1, Some(2), "write")?;
+    let port = pop_output_port(vm, argc == 2, "write")?;
     let obj = vm.heap.get_as_cell(vm.stack.pop()?);
-    vm.write(&obj);
+    vm.write_port(&port, &obj, true)?;
+    Ok(VCell::Void)
+}
+
+pub fn newline(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 0, Some(1), "newline")?;
+    let port = pop_output_port(vm, argc == 1, "newline")?;
+    vm.write_port(&port, &Cell::Char('\n'), false)?;
+    Ok(VCell::Void)
+}
+
+pub fn write_char(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, Some(2), "write-char")?;
+    let port = pop_output_port(vm, argc == 2, "write-char")?;
+    let c = pop_char(vm)?;
+    vm.write_port(&port, &Cell::Char(c), false)?;
+    Ok(VCell::Void)
+}
+
+pub fn write_string(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, Some(2), "write-string")?;
+    let port = pop_output_port(vm, argc == 2, "write-string")?;
+    let s = pop_string(vm, "write-string")?;
+    let s = Cell::String(s.borrow().clone());
+    vm.write_port(&port, &s, false)?;
+    Ok(VCell::Void)
+}
+
+pub fn read_char(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 0, Some(1), "read-char")?;
+    let port = pop_input_port(vm, argc == 1, "read-char")?;
+    Ok(port.read_char().map(VCell::Char).unwrap_or(VCell::Eof))
+}
+
+pub fn peek_char(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 0, Some(1), "peek-char")?;
+    let port = pop_input_port(vm, argc == 1, "peek-char")?;
+    Ok(port.peek_char().map(VCell::Char).unwrap_or(VCell::Eof))
+}
+
+pub fn read_line(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 0, Some(1), "read-line")?;
+    let port = pop_input_port(vm, argc == 1, "read-line")?;
+    Ok(port.read_line().map(VCell::string).unwrap_or(VCell::Eof))
+}
+
+pub fn read_string(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, Some(2), "read-string")?;
+    let port = pop_input_port(vm, argc == 2, "read-string")?;
+    let k = pop_index(vm, "read-string")?;
+    Ok(port.read_string(k).map(VCell::string).unwrap_or(VCell::Eof))
+}
+
+pub fn read(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 0, Some(1), "read")?;
+    let port = pop_input_port(vm, argc == 1, "read")?;
+    match port.read()? {
+        Some(cell) => Ok(vm.heap.put_cell(&cell)),
+        None => Ok(VCell::Eof),
+    }
+}
+
+/// Char Ready
+///
+/// Input ports never block waiting for input, so a character is always
+/// ready, even if that character is eof.
+pub fn is_char_ready(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 0, Some(1), "char-ready?")?;
+    pop_input_port(vm, argc == 1, "char-ready?")?;
+    Ok(true.into())
+}
+
+pub fn open_input_string(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "open-input-string")?;
+    let s = pop_string(vm, "open-input-string")?;
+    let port = Port::new_input_string(s.borrow().as_str());
+    Ok(VCell::Port(Rc::new(port)))
+}
+
+pub fn open_output_string(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 0, Some(0), "open-output-string")?;
+    Ok(VCell::Port(Rc::new(Port::new_output_string())))
+}
+
+pub fn get_output_string(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "get-output-string")?;
+    let port = pop_port(vm, "get-output-string")?;
+    match port.output_string() {
+        Some(output) => Ok(VCell::string(output)),
+        None => Err(InvalidSyntax(
+            "bad argument to get-output-string: port is not a string output port".into(),
+        )),
+    }
+}
+
+pub fn close_port(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "close-port")?;
+    pop_port(vm, "close-port")?.close()?;
+    Ok(VCell::Void)
+}
+
+pub fn close_input_port(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "close-input-port")?;
+    pop_input_port(vm, true, "close-input-port")?.close()?;
     Ok(VCell::Void)
 }
 
+pub fn close_output_port(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "close-output-port")?;
+    pop_output_port(vm, true, "close-output-port")?.close()?;
+    Ok(VCell::Void)
+}
+
+pub fn current_input_port(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 0, Some(0), "current-input-port")?;
+    Ok(vm.current_input_port())
+}
+
+pub fn current_output_port(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 0, Some(0), "current-output-port")?;
+    Ok(vm.current_output_port())
+}
+
+/// Set Current Input Port
+///
+/// Set the port read from by input procedures when no port is given, which
+/// is used by the current-input-port parameter.
+pub fn set_current_input_port(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "set-current-input-port!")?;
+    let port = vm.stack.get_offset(0)?.clone();
+    pop_input_port(vm, true, "set-current-input-port!")?;
+    vm.input_port = port;
+    Ok(VCell::Void)
+}
+
+/// Set Current Output Port
+///
+/// Set the port written to by output procedures when no port is given,
+/// which is used by the current-output-port parameter.
+pub fn set_current_output_port(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "set-current-output-port!")?;
+    let port = vm.stack.get_offset(0)?.clone();
+    pop_output_port(vm, true, "set-current-output-port!")?;
+    vm.output_port = port;
+    Ok(VCell::Void)
+}
+
+pub fn is_input_port(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "input-port?")?;
+    Ok(match vm.heap.get(vm.stack.pop()?) {
+        VCell::Port(port) => port.is_input(),
+        _ => false,
+    }
+    .into())
+}
+
+pub fn is_output_port(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "output-port?")?;
+    Ok(match vm.heap.get(vm.stack.pop()?) {
+        VCell::Port(port) => port.is_output(),
+        _ => false,
+    }
+    .into())
+}
+
+pub fn is_textual_port(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "textual-port?")?;
+    let result = vm.heap.get(vm.stack.pop()?);
+    Ok(result.is_port().into())
+}
+
+pub fn eof_object(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 0, Some(0), "eof-object")?;
+    Ok(VCell::Eof)
+}
+
+pub fn is_eof_object(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "eof-object?")?;
+    let result = vm.heap.get(vm.stack.pop()?);
+    Ok(result.is_eof().into())
+}
+
 pub fn term_rows(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 0, Some(0), "term-rows")?;
     Ok(VCell::Number(vm.term_rows().into()))
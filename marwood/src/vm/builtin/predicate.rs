@@ -5,6 +5,7 @@ use crate::vm::Vm;
 
 pub fn load_builtins(vm: &mut Vm) {
     vm.load_builtin("boolean?", is_boolean);
+    vm.load_builtin("bytevector?", is_bytevector);
     vm.load_builtin("char?", is_char);
     vm.load_builtin("eq?", eq);
     vm.load_builtin("equal?", equal);
@@ -24,6 +25,8 @@ pub fn load_builtins(vm: &mut Vm) {
     vm.load_builtin("real?", is_real);
     vm.load_builtin("rational?", is_rational);
     vm.load_builtin("integer?", is_integer);
+    vm.load_builtin("exact-integer?", is_exact_integer);
+    vm.load_builtin("exact-rational?", is_exact_rational);
 }
 
 pub fn is_boolean(vm: &mut Vm) -> Result<VCell, Error> {
@@ -32,6 +35,12 @@ pub fn is_boolean(vm: &mut Vm) -> Result<VCell, Error> {
     Ok(result.is_boolean().into())
 }
 
+pub fn is_bytevector(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "bytevector?")?;
+    let result = vm.heap.get(vm.stack.pop()?);
+    Ok(result.is_bytevector().into())
+}
+
 pub fn is_char(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 1, Some(1), "char?")?;
     let result = vm.heap.get(vm.stack.pop()?);
@@ -90,6 +99,26 @@ pub fn is_integer(vm: &mut Vm) -> Result<VCell, Error> {
         .into())
 }
 
+pub fn is_exact_integer(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "exact-integer?")?;
+    let result = vm.heap.get(vm.stack.pop()?);
+    Ok(result
+        .as_number()
+        .map(|it| it.is_exact() && it.is_integer())
+        .unwrap_or(false)
+        .into())
+}
+
+pub fn is_exact_rational(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "exact-rational?")?;
+    let result = vm.heap.get(vm.stack.pop()?);
+    Ok(result
+        .as_number()
+        .map(|it| it.is_exact() && it.is_rational())
+        .unwrap_or(false)
+        .into())
+}
+
 pub fn is_pair(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 1, Some(1), "pair?")?;
     let result = vm.heap.get(vm.stack.pop()?);
@@ -98,8 +127,8 @@ pub fn is_pair(vm: &mut Vm) -> Result<VCell, Error> {
 
 pub fn is_port(vm: &mut Vm) -> Result<VCell, Error> {
     pop_argc(vm, 1, Some(1), "port?")?;
-    let _ = vm.stack.pop()?;
-    Ok(false.into())
+    let result = vm.heap.get(vm.stack.pop()?);
+    Ok(result.is_port().into())
 }
 
 pub fn is_procedure(vm: &mut Vm) -> Result<VCell, Error> {
@@ -1,9 +1,7 @@
-use crate::cell::Cell;
 use crate::error::Error;
-use crate::error::Error::{ErrorSignal, InvalidSyntax};
+use crate::error::Error::InvalidSyntax;
+use crate::vm::builtin::environment::pop_environment;
 use crate::vm::builtin::pop_argc;
-use crate::vm::lambda::Lambda;
-use crate::vm::opcode::OpCode;
 use crate::vm::vcell::VCell;
 use crate::vm::vcell::VCell::ArgumentCount;
 use crate::vm::Vm;
@@ -14,18 +12,13 @@ pub fn load_builtins(vm: &mut Vm) {
     vm.load_builtin("apply", apply);
     vm.load_builtin("call/cc", call_cc);
     vm.load_builtin("call-with-current-continuation", call_cc);
-    vm.load_builtin("error", error);
+    vm.load_builtin("call-with-values", call_with_values);
+    vm.load_builtin("dynamic-wind", dynamic_wind);
     vm.load_builtin("eval", eval);
-}
-
-fn error(vm: &mut Vm) -> Result<VCell, Error> {
-    let argc = pop_argc(vm, 1, None, "error")?;
-    let mut result = vec![Cell::Nil; argc];
-    for it in 0..argc {
-        let vcell = vm.stack.pop()?;
-        *result.get_mut(argc - it - 1).unwrap() = vm.heap.get_as_cell(vcell);
-    }
-    Err(ErrorSignal(result))
+    vm.load_builtin("raise", raise);
+    vm.load_builtin("raise-continuable", raise_continuable);
+    vm.load_builtin("values", values);
+    vm.load_builtin("with-exception-handler", with_exception_handler);
 }
 
 /// Eval
@@ -34,23 +27,25 @@ fn error(vm: &mut Vm) -> Result<VCell, Error> {
 /// (Cell) and then compiles a new top level Lambda given the AST
 /// with no IOF environment.
 ///
+/// The expression is compiled against the environment given as the
+/// second argument, or the interaction environment if there is none.
+///
 /// The lambda is returned by eval() to immediately be placed into
 /// %acc by the calling code.
 ///
 /// Before returning, this function decrements %ip so that the next
 /// instruction to execute is CALL %acc.
 fn eval(vm: &mut Vm) -> Result<VCell, Error> {
-    pop_argc(vm, 1, Some(1), "eval")?;
+    let argc = pop_argc(vm, 1, Some(2), "eval")?;
+    let environment = match argc {
+        2 => pop_environment(vm, "eval")?,
+        _ => vm.interaction_environment(),
+    };
 
     let expr = vm.pop()?;
     let expr = vm.heap.get_as_cell(&expr);
 
-    let mut lambda = Lambda::new(vec![]);
-    lambda.set_top_level();
-    lambda.emit(OpCode::Enter);
-    vm.compile(&mut lambda, true, &expr)?;
-    lambda.emit(OpCode::Ret);
-    let lambda = vm.heap.put(lambda);
+    let lambda = vm.compile_thunk(&expr, environment)?;
 
     vm.stack.push(ArgumentCount(0));
     vm.ip.1 -= 1;
@@ -132,3 +127,135 @@ fn call_cc(vm: &mut Vm) -> Result<VCell, Error> {
     vm.ip.1 -= 1;
     Ok(proc)
 }
+
+/// dynamic-wind
+///
+/// 1. Pop the before, thunk and after args off the stack, ensuring
+///    each is a procedure.
+/// 2. Build a procedure that applies before, thunk and after in order
+///    while maintaining the VM's wind list.
+/// 3. Push an arg count of 0 and decrement %ip by 1 so that the next
+///    instruction that executes is the CALL %acc that executed this
+///    builtin, applying the built procedure.
+fn dynamic_wind(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 3, Some(3), "dynamic-wind")?;
+    let mut procs = vec![];
+    for _ in 0..3 {
+        match vm.stack.pop()?.clone() {
+            proc if vm.heap.get(&proc).is_procedure() => procs.push(proc),
+            proc => {
+                return Err(InvalidSyntax(format!(
+                    "bad argument to dynamic-wind: {:#} is not a procedure",
+                    vm.heap.get_as_cell(&proc)
+                )));
+            }
+        }
+    }
+    let (after, thunk, before) = (procs.remove(0), procs.remove(0), procs.remove(0));
+    let proc = vm.build_dynamic_wind(before, thunk, after)?;
+    vm.stack.push(ArgumentCount(0));
+    vm.ip.1 -= 1;
+    Ok(proc)
+}
+
+/// values
+///
+/// Pop each argument off the stack, returning them as a single
+/// VCell::Values. If values is applied to a single argument, then
+/// the argument itself is returned.
+fn values(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 0, None, "values")?;
+    let mut result = vec![VCell::Void; argc];
+    for it in 0..argc {
+        *result.get_mut(argc - it - 1).unwrap() = vm.stack.pop()?.clone();
+    }
+    match argc {
+        1 => Ok(result.pop().unwrap()),
+        _ => Ok(VCell::values(result)),
+    }
+}
+
+/// call-with-values
+///
+/// 1. Pop the producer and consumer args off the stack, ensuring
+///    each is a procedure.
+/// 2. Build a procedure that applies producer, and then applies
+///    consumer to the values producer returned.
+/// 3. Push an arg count of 0 and decrement %ip by 1 so that the next
+///    instruction that executes is the CALL %acc that executed this
+///    builtin, applying the built procedure.
+fn call_with_values(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "call-with-values")?;
+    let mut procs = vec![];
+    for _ in 0..2 {
+        match vm.stack.pop()?.clone() {
+            proc if vm.heap.get(&proc).is_procedure() => procs.push(proc),
+            proc => {
+                return Err(InvalidSyntax(format!(
+                    "bad argument to call-with-values: {:#} is not a procedure",
+                    vm.heap.get_as_cell(&proc)
+                )));
+            }
+        }
+    }
+    let (consumer, producer) = (procs.remove(0), procs.remove(0));
+    let proc = vm.build_call_with_values(producer, consumer)?;
+    vm.stack.push(ArgumentCount(0));
+    vm.ip.1 -= 1;
+    Ok(proc)
+}
+
+/// raise
+///
+/// 1. Pop the single arg to raise off the stack.
+/// 2. Build a procedure that applies the current exception handler to
+///    the arg, raising it again to the outer handler if the handler
+///    returns.
+/// 3. Push an arg count of 0 and decrement %ip by 1 so that the next
+///    instruction that executes is the CALL %acc that executed this
+///    builtin, applying the built procedure.
+fn raise(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "raise")?;
+    let obj = vm.stack.pop()?.clone();
+    vm.raise(obj, false)
+}
+
+/// raise-continuable
+///
+/// Like raise, except that the built procedure evaluates to the result of
+/// the current exception handler.
+fn raise_continuable(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "raise-continuable")?;
+    let obj = vm.stack.pop()?.clone();
+    vm.raise(obj, true)
+}
+
+/// with-exception-handler
+///
+/// 1. Pop the handler and thunk args off the stack, ensuring each is a
+///    procedure.
+/// 2. Build a procedure that applies thunk with handler installed as the
+///    current exception handler.
+/// 3. Push an arg count of 0 and decrement %ip by 1 so that the next
+///    instruction that executes is the CALL %acc that executed this
+///    builtin, applying the built procedure.
+fn with_exception_handler(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "with-exception-handler")?;
+    let mut procs = vec![];
+    for _ in 0..2 {
+        match vm.stack.pop()?.clone() {
+            proc if vm.heap.get(&proc).is_procedure() => procs.push(proc),
+            proc => {
+                return Err(InvalidSyntax(format!(
+                    "bad argument to with-exception-handler: {:#} is not a procedure",
+                    vm.heap.get_as_cell(&proc)
+                )));
+            }
+        }
+    }
+    let (thunk, handler) = (procs.remove(0), procs.remove(0));
+    let proc = vm.build_with_exception_handler(handler, thunk)?;
+    vm.stack.push(ArgumentCount(0));
+    vm.ip.1 -= 1;
+    Ok(proc)
+}
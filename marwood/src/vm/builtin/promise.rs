@@ -0,0 +1,82 @@
+use crate::error::Error;
+use crate::error::Error::InvalidSyntax;
+use crate::vm::builtin::pop_argc;
+use crate::vm::promise::Promise;
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+use std::rc::Rc;
+
+pub fn load_builtins(vm: &mut Vm) {
+    vm.load_builtin("make-promise", make_promise);
+    vm.load_builtin("promise?", is_promise);
+    vm.load_builtin("promise-new", promise_new);
+    vm.load_builtin("promise-done?", is_promise_done);
+    vm.load_builtin("promise-value", promise_value);
+    vm.load_builtin("promise-update!", promise_update);
+}
+
+/// Pop Promise
+///
+/// Pop a promise off the stack
+fn pop_promise(vm: &mut Vm, proc: &str) -> Result<Rc<Promise>, Error> {
+    match vm.heap.get(vm.stack.pop()?) {
+        VCell::Promise(promise) => Ok(promise),
+        vcell => Err(InvalidSyntax(format!(
+            "bad argument to {}: {:#} is not a promise",
+            proc,
+            vm.heap.get_as_cell(&vcell)
+        ))),
+    }
+}
+
+/// (make-promise obj)
+///
+/// Return a promise which when forced results in obj, or obj itself if it's
+/// already a promise.
+pub fn make_promise(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "make-promise")?;
+    let obj = vm.stack.pop()?.clone();
+    match vm.heap.get(&obj).is_promise() {
+        true => Ok(obj),
+        false => Ok(VCell::Promise(Rc::new(Promise::new(true, obj)))),
+    }
+}
+
+pub fn is_promise(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "promise?")?;
+    let obj = vm.stack.pop()?.clone();
+    Ok(vm.heap.get(&obj).is_promise().into())
+}
+
+/// (promise-new done? obj)
+///
+/// Return a new promise, used by delay and delay-force. obj is the value of
+/// the promise if done? is true, or the procedure that computes it otherwise.
+pub fn promise_new(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "promise-new")?;
+    let obj = vm.stack.pop()?.clone();
+    let done = !matches!(vm.heap.get(vm.stack.pop()?), VCell::Bool(false));
+    Ok(VCell::Promise(Rc::new(Promise::new(done, obj))))
+}
+
+pub fn is_promise_done(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "promise-done?")?;
+    Ok(pop_promise(vm, "promise-done?")?.is_done().into())
+}
+
+pub fn promise_value(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "promise-value")?;
+    Ok(pop_promise(vm, "promise-value")?.value())
+}
+
+/// (promise-update! new old)
+///
+/// Replace the state of old with the state of new, and share old's state
+/// with new.
+pub fn promise_update(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "promise-update!")?;
+    let old = pop_promise(vm, "promise-update!")?;
+    let new = pop_promise(vm, "promise-update!")?;
+    old.update(&new);
+    Ok(VCell::Void)
+}
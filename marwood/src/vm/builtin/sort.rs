@@ -0,0 +1,239 @@
+use crate::error::Error;
+use crate::error::Error::{ExpectedPairButFound, InvalidSyntax};
+use crate::vm::builtin::{pop_argc, pop_procedure};
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+
+pub fn load_builtins(vm: &mut Vm) {
+    vm.load_builtin("sort", sort);
+}
+
+/// Sort State
+///
+/// The state of a merge sort in progress. Sorting is a bottom up merge sort
+/// of runs, where each run is a sorted list. Each pass merges the runs in
+/// pending two at a time into merged, until a single run remains.
+///
+/// Every field is a Scheme value that's never mutated, so that the state may
+/// be held by the procedure built by Vm::callback, and a continuation
+/// captured by less? resumes the sort where it was captured.
+struct SortState {
+    /// The procedure ordering elements
+    less: VCell,
+    /// The runs yet to be merged in this pass
+    pending: VCell,
+    /// The runs merged in this pass, most recent first
+    merged: VCell,
+    /// The remaining elements of the left run being merged
+    left: VCell,
+    /// The remaining elements of the right run being merged
+    right: VCell,
+    /// The elements merged so far from left and right, most recent first
+    out: VCell,
+    /// True if the sorted sequence is a vector
+    vector: VCell,
+}
+
+impl SortState {
+    fn from_vcell(vm: &Vm, state: &VCell) -> Result<SortState, Error> {
+        let state = vm.heap.get(state);
+        let state = state.as_vector()?;
+        let field = |idx| state.get(idx).unwrap();
+        Ok(SortState {
+            less: field(0),
+            pending: field(1),
+            merged: field(2),
+            left: field(3),
+            right: field(4),
+            out: field(5),
+            vector: field(6),
+        })
+    }
+
+    fn to_vcell(&self) -> VCell {
+        VCell::vector(vec![
+            self.less.clone(),
+            self.pending.clone(),
+            self.merged.clone(),
+            self.left.clone(),
+            self.right.clone(),
+            self.out.clone(),
+            self.vector.clone(),
+        ])
+    }
+}
+
+/// sort
+///
+/// (sort sequence less?)
+///
+/// Return a newly allocated list or vector of the elements of sequence
+/// sorted by less?. The sort is stable.
+pub fn sort(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "sort")?;
+    let less = pop_procedure(vm, "sort")?;
+    let sequence = vm.stack.pop()?.clone();
+
+    let (elements, vector) = match vm.heap.get(&sequence) {
+        VCell::Vector(vector) => (
+            (0..vector.len())
+                .map(|it| vector.get(it).unwrap())
+                .collect(),
+            true,
+        ),
+        VCell::Nil | VCell::Pair(_, _) => (list_to_vec(vm, &sequence)?, false),
+        vcell => {
+            return Err(InvalidSyntax(format!(
+                "bad argument to sort: {:#} is not a list or vector",
+                vm.heap.get_as_cell(&vcell)
+            )))
+        }
+    };
+
+    if elements.is_empty() {
+        return sort_finish(vm, VCell::Nil, &VCell::Bool(vector));
+    }
+
+    let nil = vm.heap.put(VCell::Nil);
+    let mut pending = nil.clone();
+    for it in elements.into_iter().rev() {
+        let run = cons(vm, it, nil.clone())?;
+        pending = cons(vm, run, pending)?;
+    }
+
+    let state = SortState {
+        less,
+        pending,
+        merged: nil.clone(),
+        left: nil.clone(),
+        right: nil.clone(),
+        out: nil,
+        vector: VCell::Bool(vector),
+    };
+    sort_next(vm, state)
+}
+
+/// Sort Next
+///
+/// Advance the sort until either a comparison is needed, in which case
+/// less? is applied to the heads of the right and left runs, or the
+/// sequence is sorted.
+fn sort_next(vm: &mut Vm, mut state: SortState) -> Result<VCell, Error> {
+    loop {
+        let left = vm.heap.get(&state.left);
+        let right = vm.heap.get(&state.right);
+        if left.is_pair() && right.is_pair() {
+            let args = [right.as_car()?, left.as_car()?];
+            let less = state.less.clone();
+            return vm.callback(less, &args, "sort", sort_resume, state.to_vcell());
+        }
+
+        // Finish the merge in progress, if any
+        if left.is_pair() || right.is_pair() || !vm.heap.get(&state.out).is_nil() {
+            let rest = if left.is_pair() {
+                &state.left
+            } else {
+                &state.right
+            };
+            let run = append_reverse(vm, &state.out, rest.clone())?;
+            state.merged = cons(vm, run, state.merged)?;
+            state.left = vm.heap.put(VCell::Nil);
+            state.right = state.left.clone();
+            state.out = state.left.clone();
+        }
+
+        // Start the next merge, or the next pass
+        match vm.heap.get(&state.pending) {
+            VCell::Nil => {
+                let merged = vm.heap.get(&state.merged);
+                match vm.heap.get(&merged.as_cdr()?) {
+                    VCell::Nil => return sort_finish(vm, merged.as_car()?, &state.vector),
+                    _ => {
+                        let nil = vm.heap.put(VCell::Nil);
+                        state.pending = append_reverse(vm, &state.merged, nil.clone())?;
+                        state.merged = nil;
+                    }
+                }
+            }
+            VCell::Pair(run, rest) => match vm.heap.get(VCell::ptr(rest)) {
+                VCell::Pair(next, rest) => {
+                    state.left = VCell::ptr(run);
+                    state.right = VCell::ptr(next);
+                    state.pending = VCell::ptr(rest);
+                }
+                _ => {
+                    state.merged = cons(vm, VCell::ptr(run), state.merged)?;
+                    state.pending = VCell::ptr(rest);
+                }
+            },
+            vcell => return Err(ExpectedPairButFound(vm.heap.get_as_cell(&vcell))),
+        }
+    }
+}
+
+/// Sort Resume
+///
+/// Move the head of the right run to the output if less? returned true,
+/// otherwise move the head of the left run, so that equal elements remain
+/// in their original order.
+fn sort_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "sort")?;
+    let less = vm.heap.get(vm.stack.pop()?);
+    let state = vm.stack.pop()?.clone();
+    let mut state = SortState::from_vcell(vm, &state)?;
+    if less == VCell::Bool(false) {
+        let left = vm.heap.get(&state.left);
+        state.out = cons(vm, left.as_car()?, state.out)?;
+        state.left = left.as_cdr()?;
+    } else {
+        let right = vm.heap.get(&state.right);
+        state.out = cons(vm, right.as_car()?, state.out)?;
+        state.right = right.as_cdr()?;
+    }
+    sort_next(vm, state)
+}
+
+fn sort_finish(vm: &mut Vm, run: VCell, vector: &VCell) -> Result<VCell, Error> {
+    match vector {
+        VCell::Bool(true) => Ok(VCell::vector(list_to_vec(vm, &run)?)),
+        _ => Ok(run),
+    }
+}
+
+fn cons(vm: &mut Vm, car: VCell, cdr: VCell) -> Result<VCell, Error> {
+    let car = vm.heap.put(car).as_ptr()?;
+    let cdr = vm.heap.put(cdr).as_ptr()?;
+    Ok(vm.heap.put(VCell::Pair(car, cdr)))
+}
+
+/// Append Reverse
+///
+/// Return the elements of list in reverse order followed by tail.
+fn append_reverse(vm: &mut Vm, list: &VCell, tail: VCell) -> Result<VCell, Error> {
+    let mut rest = vm.heap.get(list);
+    let mut tail = tail;
+    while let VCell::Pair(car, cdr) = rest {
+        tail = cons(vm, VCell::ptr(car), tail)?;
+        rest = vm.heap.get(VCell::ptr(cdr));
+    }
+    Ok(tail)
+}
+
+/// List To Vec
+///
+/// Return the elements of list, or an error if list is improper.
+fn list_to_vec(vm: &Vm, list: &VCell) -> Result<Vec<VCell>, Error> {
+    let mut rest = vm.heap.get(list);
+    let mut elements = vec![];
+    while let VCell::Pair(car, cdr) = rest {
+        elements.push(VCell::ptr(car));
+        rest = vm.heap.get(VCell::ptr(cdr));
+    }
+    match rest {
+        VCell::Nil => Ok(elements),
+        _ => Err(InvalidSyntax(format!(
+            "{:#} is an improper list",
+            vm.heap.get_as_cell(list)
+        ))),
+    }
+}
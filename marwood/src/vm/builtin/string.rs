@@ -1,7 +1,11 @@
 use crate::error::Error;
-use crate::error::Error::{InvalidStringIndex, InvalidSyntax};
+use crate::error::Error::{InvalidStringIndex, InvalidSyntax, InvalidVectorIndex};
 use crate::number::Number;
-use crate::vm::builtin::{pop_argc, pop_char, pop_index, pop_string, pop_usize, pop_vector};
+use crate::vm::builtin::vector::for_each_finish;
+use crate::vm::builtin::{
+    pop_argc, pop_char, pop_index, pop_procedure, pop_range, pop_string, pop_usize, pop_vector,
+    range_bounds,
+};
 use crate::vm::vcell::VCell;
 use crate::vm::Vm;
 use std::ops::DerefMut;
@@ -32,6 +36,9 @@ pub fn load_builtins(vm: &mut Vm) {
     vm.load_builtin("vector->string", vector_string);
     vm.load_builtin("list->string", list_string);
     vm.load_builtin("string-copy", string_copy);
+    vm.load_builtin("string-copy!", string_mut_copy);
+    vm.load_builtin("string-map", string_map);
+    vm.load_builtin("string-for-each", string_for_each);
 }
 
 pub fn string_append(vm: &mut Vm) -> Result<VCell, Error> {
@@ -162,21 +169,31 @@ pub fn string_list(vm: &mut Vm) -> Result<VCell, Error> {
 }
 
 pub fn string_vector(vm: &mut Vm) -> Result<VCell, Error> {
-    pop_argc(vm, 1, Some(1), "string->vector")?;
+    let argc = pop_argc(vm, 1, Some(3), "string->vector")?;
+    let (start, end) = pop_range(vm, argc, 1, "string->vector")?;
 
     let s = pop_string(vm, "string->vector")?;
     let s = s.borrow();
-    let s = s.as_str();
-    let v = s.chars().map(VCell::Char).collect::<Vec<_>>();
+    let len = s.chars().count();
+    let (start, end) = range_bounds(start, end, len).map_err(|idx| InvalidStringIndex(idx, len))?;
+    let v = s
+        .chars()
+        .skip(start)
+        .take(end - start)
+        .map(VCell::Char)
+        .collect::<Vec<_>>();
     Ok(VCell::vector(v))
 }
 
 pub fn vector_string(vm: &mut Vm) -> Result<VCell, Error> {
-    pop_argc(vm, 1, Some(1), "vector->string")?;
+    let argc = pop_argc(vm, 1, Some(3), "vector->string")?;
+    let (start, end) = pop_range(vm, argc, 1, "vector->string")?;
 
     let v = pop_vector(vm)?;
-    let mut s = String::with_capacity(v.len());
-    for it in 0..v.len() {
+    let (start, end) =
+        range_bounds(start, end, v.len()).map_err(|idx| InvalidVectorIndex(idx, v.len()))?;
+    let mut s = String::with_capacity(end - start);
+    for it in start..end {
         let vcell = vm.heap.get(&v.get(it).unwrap());
         s.push(vcell.as_char()?);
     }
@@ -227,6 +244,33 @@ pub fn string_copy(vm: &mut Vm) -> Result<VCell, Error> {
     Ok(VCell::string(substr))
 }
 
+// (string-copy! to at from start end)
+pub fn string_mut_copy(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 3, Some(5), "string-copy!")?;
+    let (start, end) = pop_range(vm, argc, 3, "string-copy!")?;
+
+    // from is copied before to is borrowed mutably, as they may be the same string
+    let from = pop_string(vm, "string-copy!")?;
+    let from = from.borrow().chars().collect::<Vec<_>>();
+    let (start, end) =
+        range_bounds(start, end, from.len()).map_err(|idx| InvalidStringIndex(idx, from.len()))?;
+
+    let at = pop_index(vm, "string-copy!")?;
+    let to = pop_string(vm, "string-copy!")?;
+    let mut to = to.borrow_mut();
+    let mut chars = to.chars().collect::<Vec<_>>();
+    if at > chars.len() {
+        return Err(InvalidStringIndex(at, chars.len()));
+    }
+    if chars.len() - at < end - start {
+        return Err(InvalidSyntax("string-copy!: to string is too small".into()));
+    }
+
+    chars[at..at + end - start].copy_from_slice(&from[start..end]);
+    *to = chars.into_iter().collect();
+    Ok(VCell::void())
+}
+
 pub fn string_fill(vm: &mut Vm) -> Result<VCell, Error> {
     let argc = pop_argc(vm, 2, Some(4), "string-fill")?;
 
@@ -364,3 +408,83 @@ fn string_comp(vm: &mut Vm, name: &str, comp: impl Fn(&str, &str) -> bool) -> Re
 
     Ok(result.into())
 }
+
+/// Pop Map Args
+///
+/// Pop the procedure and one or more strings applied to string-map or
+/// string-for-each, returning the procedure and the arguments of each
+/// application of it. Like map, the strings are traversed up to the length
+/// of the shortest string.
+///
+/// # Arguments
+/// `argc` - The number of arguments applied to the procedure
+/// `proc` - The name of the procedure, used in error messages
+fn pop_map_args(vm: &mut Vm, argc: usize, proc: &str) -> Result<(VCell, Vec<Vec<VCell>>), Error> {
+    let mut strings = vec![];
+    for _ in 1..argc {
+        let s = pop_string(vm, proc)?;
+        let s = s.borrow().chars().collect::<Vec<_>>();
+        strings.insert(0, s);
+    }
+    let f = pop_procedure(vm, proc)?;
+    let len = strings.iter().map(|it| it.len()).min().unwrap_or(0);
+    let argv = (0..len)
+        .map(|idx| strings.iter().map(|it| VCell::Char(it[idx])).collect())
+        .collect::<Vec<_>>();
+    Ok((f, argv))
+}
+
+/// string-map
+///
+/// Apply proc to the characters of each string in order, returning a string
+/// of the results.
+pub fn string_map(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 2, None, "string-map")?;
+    let (proc, argv) = pop_map_args(vm, argc, "string-map")?;
+    vm.map(
+        proc,
+        argv,
+        "string-map",
+        string_map_resume,
+        string_map_finish,
+    )
+}
+
+fn string_map_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    vm.map_resume("string-map", string_map_resume, string_map_finish)
+}
+
+fn string_map_finish(vm: &mut Vm, values: Vec<VCell>) -> Result<VCell, Error> {
+    let mut s = String::with_capacity(values.len());
+    for it in values {
+        match vm.heap.get(&it) {
+            VCell::Char(c) => s.push(c),
+            vcell => {
+                return Err(InvalidSyntax(format!(
+                    "string-map expected char but found {:#}",
+                    vm.heap.get_as_cell(&vcell)
+                )))
+            }
+        }
+    }
+    Ok(VCell::string(s))
+}
+
+/// string-for-each
+///
+/// Apply proc to the characters of each string in order for its side effects.
+pub fn string_for_each(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 2, None, "string-for-each")?;
+    let (proc, argv) = pop_map_args(vm, argc, "string-for-each")?;
+    vm.map(
+        proc,
+        argv,
+        "string-for-each",
+        string_for_each_resume,
+        for_each_finish,
+    )
+}
+
+fn string_for_each_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    vm.map_resume("string-for-each", string_for_each_resume, for_each_finish)
+}
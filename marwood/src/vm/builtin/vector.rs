@@ -1,9 +1,11 @@
 use crate::error::Error;
 use crate::error::Error::{ExpectedPairButFound, InvalidSyntax, InvalidVectorIndex};
 use crate::number::Number;
-use crate::vm::builtin::{pop_argc, pop_index, pop_vector};
+use crate::vm::builtin::{pop_argc, pop_index, pop_procedure, pop_range, pop_vector, range_bounds};
 use crate::vm::vcell::VCell;
+use crate::vm::vector::Vector;
 use crate::vm::Vm;
+use std::rc::Rc;
 
 pub fn load_builtins(vm: &mut Vm) {
     vm.load_builtin("make-vector", make_vector);
@@ -16,6 +18,9 @@ pub fn load_builtins(vm: &mut Vm) {
     vm.load_builtin("vector-fill!", vector_fill);
     vm.load_builtin("vector-copy", vector_copy);
     vm.load_builtin("vector-copy!", vector_mut_copy);
+    vm.load_builtin("vector-append", vector_append);
+    vm.load_builtin("vector-map", vector_map);
+    vm.load_builtin("vector-for-each", vector_for_each);
 }
 
 pub fn vector(vm: &mut Vm) -> Result<VCell, Error> {
@@ -73,20 +78,26 @@ pub fn vector_set(vm: &mut Vm) -> Result<VCell, Error> {
 }
 
 pub fn vector_fill(vm: &mut Vm) -> Result<VCell, Error> {
-    pop_argc(vm, 2, Some(2), "vector-fill!")?;
+    let argc = pop_argc(vm, 2, Some(4), "vector-fill!")?;
+    let (start, end) = pop_range(vm, argc, 2, "vector-fill!")?;
     let value = vm.heap.get(vm.stack.pop()?);
     let vector = pop_vector(vm)?;
-    for idx in 0..vector.len() {
+    let (start, end) = range_bounds(start, end, vector.len())
+        .map_err(|idx| InvalidVectorIndex(idx, vector.len()))?;
+    for idx in start..end {
         vector.put(idx, value.clone());
     }
     Ok(VCell::Void)
 }
 
 pub fn vector_to_list(vm: &mut Vm) -> Result<VCell, Error> {
-    pop_argc(vm, 1, Some(1), "vector->list")?;
+    let argc = pop_argc(vm, 1, Some(3), "vector->list")?;
+    let (start, end) = pop_range(vm, argc, 1, "vector->list")?;
     let vector = pop_vector(vm)?;
+    let (start, end) = range_bounds(start, end, vector.len())
+        .map_err(|idx| InvalidVectorIndex(idx, vector.len()))?;
     let mut tail = vm.heap.put(VCell::Nil);
-    for idx in (0..vector.len()).rev() {
+    for idx in (start..end).rev() {
         let car = vector.get(idx).unwrap();
         let car = vm.heap.put(car);
         tail = vm.heap.put(VCell::Pair(car.as_ptr()?, tail.as_ptr()?));
@@ -197,3 +208,91 @@ pub fn vector_mut_copy(vm: &mut Vm) -> Result<VCell, Error> {
 
     Ok(VCell::Void)
 }
+
+pub fn vector_append(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 0, None, "vector-append")?;
+    let mut vectors = vec![];
+    for _ in 0..argc {
+        vectors.push(pop_vector(vm)?);
+    }
+    let mut outv = vec![];
+    for vector in vectors.iter().rev() {
+        outv.extend((0..vector.len()).map(|idx| vector.get(idx).unwrap()));
+    }
+    Ok(VCell::vector(outv))
+}
+
+/// Pop Map Args
+///
+/// Pop the procedure and one or more vectors applied to vector-map or
+/// vector-for-each, returning the procedure and the arguments of each
+/// application of it. Like map, the vectors are traversed up to the length
+/// of the shortest vector.
+///
+/// # Arguments
+/// `argc` - The number of arguments applied to the procedure
+/// `proc` - The name of the procedure, used in error messages
+fn pop_map_args(vm: &mut Vm, argc: usize, proc: &str) -> Result<(VCell, Vec<Vec<VCell>>), Error> {
+    let mut vectors: Vec<Rc<Vector>> = vec![];
+    for _ in 1..argc {
+        vectors.insert(0, pop_vector(vm)?);
+    }
+    let f = pop_procedure(vm, proc)?;
+    let len = vectors.iter().map(|it| it.len()).min().unwrap_or(0);
+    let argv = (0..len)
+        .map(|idx| {
+            vectors
+                .iter()
+                .map(|it| it.get(idx).unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    Ok((f, argv))
+}
+
+/// vector-map
+///
+/// Apply proc to the elements of each vector in order, returning a vector
+/// of the results.
+pub fn vector_map(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 2, None, "vector-map")?;
+    let (proc, argv) = pop_map_args(vm, argc, "vector-map")?;
+    vm.map(
+        proc,
+        argv,
+        "vector-map",
+        vector_map_resume,
+        vector_map_finish,
+    )
+}
+
+fn vector_map_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    vm.map_resume("vector-map", vector_map_resume, vector_map_finish)
+}
+
+fn vector_map_finish(_: &mut Vm, values: Vec<VCell>) -> Result<VCell, Error> {
+    Ok(VCell::vector(values))
+}
+
+/// vector-for-each
+///
+/// Apply proc to the elements of each vector in order for its side effects.
+pub fn vector_for_each(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 2, None, "vector-for-each")?;
+    let (proc, argv) = pop_map_args(vm, argc, "vector-for-each")?;
+    vm.map(
+        proc,
+        argv,
+        "vector-for-each",
+        vector_for_each_resume,
+        for_each_finish,
+    )
+}
+
+fn vector_for_each_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    vm.map_resume("vector-for-each", vector_for_each_resume, for_each_finish)
+}
+
+pub fn for_each_finish(_: &mut Vm, _: Vec<VCell>) -> Result<VCell, Error> {
+    Ok(VCell::Void)
+}
@@ -0,0 +1,294 @@
+use crate::error::Error;
+use crate::error::Error::LoadError;
+use crate::number::Number;
+use crate::vm::builtin::pop_argc;
+use crate::vm::environment::Environment;
+use crate::vm::lambda::Lambda;
+use crate::vm::opcode::OpCode;
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+use std::rc::Rc;
+
+/// Resume
+///
+/// A builtin that is applied to a state and the value returned by a
+/// procedure applied with Vm::callback.
+pub type Resume = fn(&mut Vm) -> Result<VCell, Error>;
+
+/// Finish
+///
+/// A function that produces the result of a map given each value returned
+/// by the mapped procedure, in order.
+pub type Finish = fn(&mut Vm, Vec<VCell>) -> Result<VCell, Error>;
+
+impl Vm {
+    /// Callback
+    ///
+    /// Apply proc to args from a builtin, and then apply resume to state and
+    /// the value proc returned. A builtin returns the result of callback as
+    /// its own result: like apply, the built procedure is placed in %acc and
+    /// %ip is decremented so that the CALL or TCALL that applied the builtin
+    /// next applies the built procedure.
+    ///
+    /// Resume may itself return the result of callback to apply another
+    /// procedure. Because resume is applied in tail position, a builtin may
+    /// apply any number of procedures in constant space.
+    ///
+    /// The state belongs to the built procedure rather than the builtin, so a
+    /// continuation captured by proc resumes with the state it was captured
+    /// with. Resume should build a new state rather than modify the one it
+    /// is given.
+    ///
+    /// # Arguments
+    /// `proc` - The procedure to apply
+    /// `args` - The arguments to apply proc to
+    /// `desc` - The name of resume
+    /// `resume` - The builtin applied to state and the value proc returned
+    /// `state` - The state of the builtin
+    pub fn callback(
+        &mut self,
+        proc: VCell,
+        args: &[VCell],
+        desc: &'static str,
+        resume: Resume,
+        state: VCell,
+    ) -> Result<VCell, Error> {
+        let proc = self.build_callback(proc, args, VCell::builtin(desc, resume), state)?;
+        self.stack.push(VCell::ArgumentCount(0));
+        self.ip.1 -= 1;
+        Ok(proc)
+    }
+
+    /// Build Callback
+    ///
+    /// Build a procedure of no arguments that applies proc to args, and then
+    /// tail calls resume with state and the value proc returned.
+    ///
+    /// # Arguments
+    /// `proc` - The procedure to apply
+    /// `args` - The arguments to apply proc to
+    /// `resume` - The procedure applied to state and the value proc returned
+    /// `state` - The first argument to resume
+    pub fn build_callback(
+        &mut self,
+        proc: VCell,
+        args: &[VCell],
+        resume: VCell,
+        state: VCell,
+    ) -> Result<VCell, Error> {
+        let proc = self.heap.put(proc);
+        let args = args
+            .iter()
+            .map(|arg| self.heap.put(arg.clone()))
+            .collect::<Vec<_>>();
+        let resume = self.heap.put(resume);
+        let state = self.heap.put(state);
+
+        let mut lambda = Lambda::new(vec![]);
+        lambda.emit(OpCode::Enter);
+        lambda.emit_call(proc, &args);
+        lambda.emit(OpCode::PushImmediate);
+        lambda.emit(state);
+        lambda.emit(OpCode::PushAcc);
+        lambda.emit(OpCode::PushImmediate);
+        lambda.emit(VCell::ArgumentCount(2));
+        lambda.emit(OpCode::MovImmediate);
+        lambda.emit(resume);
+        lambda.emit(VCell::Acc);
+        lambda.emit(OpCode::TCallAcc);
+        lambda.emit(OpCode::Ret);
+        Ok(self.heap.put(lambda))
+    }
+
+    /// Map
+    ///
+    /// Apply proc to each argument list in argv in order from a builtin, and
+    /// return the result of finish applied to the values proc returned. This
+    /// backs procedures such as vector-map and string-for-each.
+    ///
+    /// The builtin's resume must call map_resume with the same finish.
+    ///
+    /// # Arguments
+    /// `proc` - The procedure to apply
+    /// `argv` - The arguments of each application of proc
+    /// `desc` - The name of resume
+    /// `resume` - The builtin that calls map_resume
+    /// `finish` - Produces the result given the values proc returned
+    pub fn map(
+        &mut self,
+        proc: VCell,
+        argv: Vec<Vec<VCell>>,
+        desc: &'static str,
+        resume: Resume,
+        finish: Finish,
+    ) -> Result<VCell, Error> {
+        let args = match argv.first() {
+            Some(args) => args.clone(),
+            None => return finish(self, vec![]),
+        };
+        let argv = argv.into_iter().map(VCell::vector).collect::<Vec<_>>();
+        let state = VCell::vector(vec![
+            proc.clone(),
+            VCell::vector(argv),
+            VCell::number(0),
+            VCell::Nil,
+        ]);
+        self.callback(proc, &args, desc, resume, state)
+    }
+
+    /// Map Resume
+    ///
+    /// Resume a map started by Vm::map with the value returned by the last
+    /// application of its procedure, applying the procedure to the next
+    /// argument list or returning the result of finish.
+    ///
+    /// The state is a vector of the procedure, the argument lists, the
+    /// index of the last application and a list of the values returned so
+    /// far, most recent first.
+    ///
+    /// # Arguments
+    /// `desc` - The name of resume
+    /// `resume` - The builtin that calls map_resume
+    /// `finish` - Produces the result given the values proc returned
+    pub fn map_resume(
+        &mut self,
+        desc: &'static str,
+        resume: Resume,
+        finish: Finish,
+    ) -> Result<VCell, Error> {
+        pop_argc(self, 2, Some(2), desc)?;
+        let value = self.heap.put(self.stack.pop()?.clone());
+        let state = self.heap.get(self.stack.pop()?);
+        let state = state.as_vector()?;
+        let proc = state.get(0).unwrap();
+        let argv = self.heap.get(state.get(1).unwrap());
+        let argv = argv.as_vector()?;
+        let index = self.heap.get(state.get(2).unwrap()).as_number()?.to_usize();
+        let index = index.unwrap() + 1;
+        let results = self.heap.put(state.get(3).unwrap());
+        let results = VCell::pair(value.as_ptr()?, results.as_ptr()?);
+
+        if index == argv.len() {
+            let mut values = vec![];
+            let mut rest = results;
+            while let VCell::Pair(car, cdr) = rest {
+                values.push(VCell::ptr(car));
+                rest = self.heap.get(VCell::ptr(cdr));
+            }
+            values.reverse();
+            return finish(self, values);
+        }
+
+        let args = self.heap.get(argv.get(index).unwrap());
+        let args = (0..args.as_vector()?.len())
+            .map(|it| args.as_vector().unwrap().get(it).unwrap())
+            .collect::<Vec<_>>();
+        let state = VCell::vector(vec![
+            proc.clone(),
+            state.get(1).unwrap(),
+            VCell::number(Number::from(index as u64)),
+            self.heap.put(results),
+        ]);
+        self.callback(proc, &args, desc, resume, state)
+    }
+
+    /// Eval Sequence
+    ///
+    /// Evaluate each expression in the list exprs in order against
+    /// environment from a builtin, and then apply resume to state and the
+    /// value of the last expression. Each expression is compiled only once
+    /// the expressions before it have been evaluated, so that it may use
+    /// the macros and libraries they define.
+    ///
+    /// If the expressions were read from a file, an error compiling them
+    /// or an error evaluating them that no exception handler handles is
+    /// reported as a LoadError naming the file (see Vm::load_thunk).
+    ///
+    /// # Arguments
+    /// `exprs` - The list of expressions to evaluate
+    /// `environment` - The environment to evaluate the expressions in
+    /// `source` - The path of the file the expressions were read from, if any
+    /// `desc` - The name of resume
+    /// `resume` - The builtin applied to state and the last value
+    /// `state` - The state of the builtin
+    pub fn eval_sequence(
+        &mut self,
+        exprs: VCell,
+        environment: Rc<Environment>,
+        source: Option<&str>,
+        desc: &'static str,
+        resume: Resume,
+        state: VCell,
+    ) -> Result<VCell, Error> {
+        let state = VCell::vector(vec![
+            exprs,
+            VCell::Environment(environment),
+            VCell::builtin(desc, resume),
+            state,
+            source.map(VCell::string).unwrap_or(VCell::Void),
+        ]);
+        self.eval_sequence_next(state, VCell::Void)
+    }
+
+    /// Eval Sequence Next
+    ///
+    /// Evaluate the next expression of a sequence started by
+    /// Vm::eval_sequence, or apply its resume if there are none left.
+    ///
+    /// The state is a vector of the expressions left to evaluate, the
+    /// environment, the resume builtin, the state of the builtin and the
+    /// source of the expressions.
+    ///
+    /// # Arguments
+    /// `state` - The state of the sequence
+    /// `value` - The value of the last expression evaluated
+    fn eval_sequence_next(&mut self, state: VCell, value: VCell) -> Result<VCell, Error> {
+        let sequence = state.as_vector()?;
+        let exprs = self.heap.get(sequence.get(0).unwrap());
+        let environment = self.heap.get(sequence.get(1).unwrap()).as_environment()?;
+        match exprs {
+            VCell::Pair(car, cdr) => {
+                let expr = self.heap.get_as_cell(&VCell::ptr(car));
+                let thunk = match self.heap.get(sequence.get(4).unwrap()) {
+                    VCell::String(path) => {
+                        let path = path.borrow().clone();
+                        let thunk = self
+                            .compile_thunk(&expr, environment)
+                            .map_err(|error| LoadError(path.clone(), Box::new(error)))?;
+                        self.load_thunk(&path, thunk)?
+                    }
+                    _ => self.compile_thunk(&expr, environment)?,
+                };
+                let state = VCell::vector(vec![
+                    VCell::ptr(cdr),
+                    sequence.get(1).unwrap(),
+                    sequence.get(2).unwrap(),
+                    sequence.get(3).unwrap(),
+                    sequence.get(4).unwrap(),
+                ]);
+                self.callback(thunk, &[], "eval", eval_sequence_resume, state)
+            }
+            _ => match self.heap.get(sequence.get(2).unwrap()) {
+                VCell::BuiltInProc(resume) => {
+                    let state = self.heap.put(sequence.get(3).unwrap());
+                    self.stack.push(state);
+                    self.stack.push(value);
+                    self.stack.push(VCell::ArgumentCount(2));
+                    resume.eval(self)
+                }
+                _ => Err(Error::InvalidBytecode),
+            },
+        }
+    }
+}
+
+/// Eval Sequence Resume
+///
+/// Resume a sequence started by Vm::eval_sequence with the value of the
+/// last expression evaluated.
+fn eval_sequence_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "eval")?;
+    let value = vm.stack.pop()?.clone();
+    let state = vm.heap.get(vm.stack.pop()?);
+    vm.eval_sequence_next(state, value)
+}
@@ -43,6 +43,7 @@ impl Vm {
             (VCell::Bool(left), VCell::Bool(right)) => Ok(left == right),
             (VCell::Number(left), VCell::Number(right)) => Ok(left == right),
             (VCell::Nil, VCell::Nil) => Ok(true),
+            (VCell::Eof, VCell::Eof) => Ok(true),
             (VCell::Pair(_, _), VCell::Pair(_, _)) => Ok(left == right),
             (VCell::Char(left), VCell::Char(right)) => Ok(left == right),
             (VCell::String(left), VCell::String(right)) => Ok(left == right),
@@ -54,7 +55,9 @@ impl Vm {
     ///
     /// This function backs the scheme equal? predicate.
     ///
-    /// When applied to pairs, vectors and strings it recursively compares them.
+    /// When applied to pairs, vectors, bytevectors and strings it recursively
+    /// compares them.
+    /// Records are recursively compared if both are of the same record type.
     /// If applied to any other type, it compares with eqv?.
     pub fn equal(&self, left: &VCell, right: &VCell) -> Result<bool, Error> {
         let mut left = left.clone();
@@ -76,6 +79,12 @@ impl Vm {
         if left.is_vector() && right.is_vector() {
             return self.compare_vector(left, right);
         }
+        if left.is_record() && right.is_record() {
+            return self.compare_record(left, right);
+        }
+        if let (VCell::Bytevector(left), VCell::Bytevector(right)) = (&left, &right) {
+            return Ok(left.borrow().as_slice() == right.borrow().as_slice());
+        }
         if left.is_string() && right.is_string() {
             return Ok(left.as_string()?.borrow().as_str() == right.as_string()?.borrow().as_str());
         }
@@ -110,6 +119,22 @@ impl Vm {
         }
         Ok(true)
     }
+
+    pub fn compare_record(&self, left: VCell, right: VCell) -> Result<bool, Error> {
+        let (left, right) = match (left, right) {
+            (VCell::Record(left), VCell::Record(right)) => (left, right),
+            _ => return Ok(false),
+        };
+        if !left.is_instance(right.record_type()) {
+            return Ok(false);
+        }
+        for idx in 0..left.len() {
+            if !self.equal(&left.get(idx).unwrap(), &right.get(idx).unwrap())? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
 }
 
 #[cfg(test)]
@@ -1,13 +1,21 @@
-use crate::cell::Cell;
+use crate::cell::{split_alias, unalias, Cell};
 use crate::error::Error;
 use crate::error::Error::{
-    InvalidArgs, InvalidNumArgs, InvalidSyntax, InvalidUsePrimitive, LambdaMissingExpression,
-    UnquotedNil,
+    InvalidArgs, InvalidBytecode, InvalidNumArgs, InvalidSyntax, InvalidUsePrimitive,
+    LambdaMissingExpression, UnquotedNil,
+};
+use crate::vm::environment::{
+    free_symbols, internally_defined_symbols, BindingLocation, Environment,
 };
-use crate::vm::environment::{free_symbols, internally_defined_symbols, BindingLocation};
 use crate::vm::lambda::Lambda;
+use crate::vm::library::{define_library, import};
+use crate::vm::load::{include, is_include};
 use crate::vm::opcode::OpCode;
-use crate::vm::transform::Transform;
+use crate::vm::record::{
+    make_record_type, record_accessor, record_constructor, record_modifier, record_predicate,
+};
+use crate::vm::transform::{SyntaxBinding, SyntaxEnvironment, Transform};
+use crate::vm::values::apply_values;
 use crate::vm::vcell::VCell;
 use crate::vm::vcell::VCell::{BasePointerOffset, LexicalEnvSlot};
 use crate::vm::Vm;
@@ -15,6 +23,10 @@ use log::trace;
 use std::ops::Deref;
 use std::rc::Rc;
 
+/// The keyword of a named let that's compiled into a loop, which the reader
+/// is unable to produce
+pub(crate) const NAMED_LET: &str = "#named-let";
+
 macro_rules! car {
     ($cell:expr) => {{
         $cell
@@ -57,6 +69,27 @@ impl Vm {
         Ok(entry_lambda)
     }
 
+    /// Compile Thunk
+    ///
+    /// Compile expr against environment to a new top level procedure of no
+    /// arguments, returning the procedure placed on the heap.
+    ///
+    /// # Arguments
+    /// `expr` - The expression to compile.
+    /// `environment` - The environment to compile against.
+    pub fn compile_thunk(
+        &mut self,
+        expr: &Cell,
+        environment: Rc<Environment>,
+    ) -> Result<VCell, Error> {
+        let mut lambda = Lambda::new(vec![]);
+        lambda.set_top_level();
+        lambda.emit(OpCode::Enter);
+        self.with_environment(environment, |vm| vm.compile(&mut lambda, true, expr))?;
+        lambda.emit(OpCode::Ret);
+        Ok(self.heap.put(lambda))
+    }
+
     /// Compile
     ///
     /// Apply any pre-compilation transforms to expr, compile it, and return
@@ -65,7 +98,13 @@ impl Vm {
     /// # Arguments
     /// `expr` - The expression to compile.
     pub fn compile(&mut self, lambda: &mut Lambda, tail: bool, expr: &Cell) -> Result<(), Error> {
+        // A top level include is evaluated when it's applied, so that the
+        // definitions and macros it includes are top level definitions.
+        if lambda.is_top_level() && is_include(expr) {
+            return self.compile_include(lambda, expr);
+        }
         trace!("transforming {}", expr);
+        self.syntax_env = SyntaxEnvironment::default();
         let expr = self.transform(expr)?;
         trace!("compiling {}", expr);
         self.compile_expression(lambda, tail, &expr)?;
@@ -79,6 +118,7 @@ impl Vm {
     pub fn transform(&mut self, expr: &Cell) -> Result<Cell, Error> {
         match expr {
             Cell::Pair(_, _) => self.transform_procedure_application(expr),
+            Cell::Symbol(_) => Ok(self.transform_symbol(expr)),
             cell => Ok(cell.clone()),
         }
     }
@@ -88,21 +128,34 @@ impl Vm {
         let mut rest = expr.cdr().unwrap();
 
         if let Cell::Symbol(proc) = proc.deref() {
-            if let "quote" | "define-syntax" = proc.as_str() {
-                return Ok(expr.clone());
+            match unalias(proc) {
+                "quote" | "define-syntax" | "define-library" | "import" => return Ok(expr.clone()),
+                "define" if rest.car().is_some_and(|it| it.is_pair()) => {
+                    return self.transform_definition(expr)
+                }
+                "lambda" | "λ" if rest.is_pair() => return self.transform_definition(expr),
+                "case-lambda" => return self.transform_case_lambda(expr),
+                "include" | "include-ci" => return self.transform_include(expr),
+                "let-syntax" => return self.transform_let_syntax(expr, false),
+                "letrec-syntax" => return self.transform_let_syntax(expr, true),
+                _ => {}
             }
         }
 
-        if let Some(sym) = self.heap.get_sym_ref(proc) {
-            let vcell = match self.globenv.get(sym.as_ptr()?) {
-                Some(VCell::Ptr(ptr)) => Some(self.heap.get_at_index(ptr).clone()),
-                vcell => vcell,
-            };
-            if let Some(VCell::Macro(transform)) = vcell {
-                let expansion = transform.transform(expr)?;
-                trace!("macro expansion: {} => {}", expr, expansion);
-                return self.transform(&expansion);
+        if let Some((tag, formals, body, inits)) = named_let(expr) {
+            return self.transform_named_let(tag, formals, body, inits);
+        }
+
+        if let Some((transform, depth)) = self.get_macro(proc)? {
+            self.expansions += 1;
+            self.syntax_env.add_expansion(self.expansions, depth);
+            if let Some(environment) = transform.environment() {
+                self.syntax_env
+                    .add_expansion_environment(self.expansions, environment);
             }
+            let expansion = transform.transform_hygienic(expr, self.expansions)?;
+            trace!("macro expansion: {} => {}", expr, expansion);
+            return self.transform(&expansion);
         }
 
         let mut v = vec![self.transform(proc)?];
@@ -118,6 +171,411 @@ impl Vm {
         }
     }
 
+    /// Transform Include
+    ///
+    /// Replace an include or include-ci expression that isn't top level, or
+    /// part of a body (see splice_includes), with a sequence of the
+    /// expressions contained in its files.
+    ///
+    /// # Arguments
+    /// `expr` - (include string ...) or (include-ci string ...)
+    fn transform_include(&mut self, expr: &Cell) -> Result<Cell, Error> {
+        let mut body = self.read_include(expr)?;
+        if body.is_empty() {
+            body.push(Cell::new_list(vec![
+                Cell::new_symbol("if"),
+                Cell::Bool(false),
+                Cell::Bool(false),
+            ]));
+        }
+        let lambda = Cell::new_list(
+            vec![Cell::new_symbol("lambda"), Cell::Nil]
+                .into_iter()
+                .chain(body),
+        );
+        self.transform(&Cell::new_list(vec![lambda]))
+    }
+
+    /// Get Macro
+    ///
+    /// Return the macro keyword refers to, along with the depth of the
+    /// syntax environment scope that free identifiers in its templates refer
+    /// to. Macros bound by let-syntax, letrec-syntax or an internal
+    /// define-syntax are searched before the global environment, and a
+    /// keyword shadowed by a local variable doesn't refer to a macro.
+    ///
+    /// A keyword introduced by a top level macro is looked up in the
+    /// environment that macro was defined in (see macro_environment_slot).
+    ///
+    /// # Arguments
+    /// `keyword` - The keyword to look up
+    fn get_macro(&mut self, keyword: &Cell) -> Result<Option<(Rc<Transform>, usize)>, Error> {
+        if !keyword.is_symbol() {
+            return Ok(None);
+        }
+        match self.syntax_env.get(keyword, self.syntax_env.depth()) {
+            Some((_, SyntaxBinding::Macro(transform, depth))) => {
+                return Ok(Some((transform, depth)))
+            }
+            Some((_, SyntaxBinding::Variable(_))) => return Ok(None),
+            None => {}
+        }
+        let vcell = match self.macro_environment_slot(keyword) {
+            Some(slot) => Some(self.globenv.get_slot(slot)),
+            None => match self.heap.get_sym_ref(&keyword.unaliased()) {
+                Some(sym) => self.globenv.get(sym.as_ptr()?),
+                None => None,
+            },
+        };
+        let vcell = match vcell {
+            Some(VCell::Ptr(ptr)) => Some(self.heap.get_at_index(ptr).clone()),
+            vcell => vcell,
+        };
+        if let Some(VCell::Macro(transform)) = vcell {
+            return Ok(Some((transform, 0)));
+        }
+        Ok(None)
+    }
+
+    /// Macro Environment Slot
+    ///
+    /// If sym is an alias introduced by the expansion of a top level macro,
+    /// and the symbol it refers to is bound in the environment that macro
+    /// was defined in, return that binding's slot. This allows a macro
+    /// exported by a library to refer to bindings the library doesn't
+    /// export.
+    ///
+    /// # Arguments
+    /// `sym` - The symbol to look up
+    fn macro_environment_slot(&self, sym: &Cell) -> Option<usize> {
+        let mut name = sym.as_symbol()?;
+        let sym_ref = self
+            .heap
+            .get_sym_ref(&Cell::new_symbol(unalias(name)))?
+            .as_ptr()
+            .ok()?;
+        while let Some((rest, expansion)) = split_alias(name) {
+            let slot = self
+                .syntax_env
+                .get_expansion_environment(expansion)
+                .and_then(|environment| environment.get(sym_ref));
+            if slot.is_some() {
+                return slot;
+            }
+            name = rest;
+        }
+        None
+    }
+
+    /// Global Slot
+    ///
+    /// Return the global environment slot that a reference to variable,
+    /// which isn't lexically bound, is compiled to. This is the binding in
+    /// the environment of the macro that introduced variable if there is
+    /// one (see macro_environment_slot), or else the binding in the
+    /// current environment.
+    ///
+    /// # Arguments
+    /// `variable` - The variable to look up
+    fn global_slot(&mut self, variable: &Cell) -> usize {
+        if let Some(slot) = self.macro_environment_slot(variable) {
+            return slot;
+        }
+        let sym_ref = self
+            .heap
+            .put_cell(&variable.unaliased())
+            .as_ptr()
+            .expect("expected ptr");
+        self.globenv.get_binding(sym_ref)
+    }
+
+    /// Transform Symbol
+    ///
+    /// A symbol that refers to a variable bound in the syntax environment is
+    /// replaced by the identifier that variable is compiled as (see
+    /// SyntaxEnvironment::bind_variable). An alias introduced by a local
+    /// macro may refer to a variable bound in the scope the macro was
+    /// defined in. Any other symbol is left for the compiler.
+    ///
+    /// # Arguments
+    /// `sym` - The symbol to transform
+    fn transform_symbol(&mut self, sym: &Cell) -> Cell {
+        match self.syntax_env.get(sym, self.syntax_env.depth()) {
+            Some((_, SyntaxBinding::Variable(variable))) => variable,
+            _ => sym.clone(),
+        }
+    }
+
+    /// Bind Variable
+    ///
+    /// Bind variable in the innermost syntax environment scope, returning the
+    /// identifier it's compiled as.
+    ///
+    /// # Arguments
+    /// `variable` - The variable to bind
+    fn bind_variable(&mut self, variable: &Cell) -> Cell {
+        self.syntax_env
+            .bind_variable(variable.clone(), &mut self.expansions)
+    }
+
+    /// Transform Definition
+    ///
+    /// Transform a lambda expression or procedure definition, whose formals
+    /// are bound in a new syntax environment scope that the body is
+    /// transformed in.
+    ///
+    /// # Arguments
+    /// `expr` - (lambda formals body ...) or (define (variable . formals) body ...)
+    fn transform_definition(&mut self, expr: &Cell) -> Result<Cell, Error> {
+        let keyword = car!(expr).clone();
+        let formals = car!(cdr!(expr));
+        let (name, mut args) = match car!(expr).is_define() {
+            true => (Some(self.transform_symbol(car!(formals))), cdr!(formals)),
+            false => (None, formals),
+        };
+
+        self.syntax_env.push_scope();
+        let mut variables = name.into_iter().collect::<Vec<_>>();
+        while let Cell::Pair(arg, rest) = args {
+            variables.push(self.bind_variable(arg));
+            args = rest;
+        }
+        let formals = match args {
+            Cell::Nil => Cell::new_list(variables),
+            rest if rest.is_symbol() => {
+                let rest = self.bind_variable(rest);
+                match variables.is_empty() {
+                    true => rest,
+                    false => Cell::new_improper_list(variables, rest),
+                }
+            }
+            rest => Cell::new_improper_list(variables, rest.clone()),
+        };
+        let body = self.transform_body(cdr!(cdr!(expr)));
+        self.syntax_env.pop_scope();
+
+        Ok(Cell::new_list(
+            vec![keyword, formals].into_iter().chain(body?),
+        ))
+    }
+
+    /// Transform Case Lambda
+    ///
+    /// Transform each clause of a case-lambda expression as if it were a
+    /// lambda expression with the clause's formals and body.
+    ///
+    /// # Arguments
+    /// `expr` - (case-lambda (formals body ...) ...)
+    fn transform_case_lambda(&mut self, expr: &Cell) -> Result<Cell, Error> {
+        let mut v = vec![car!(expr).clone()];
+        for clause in cdr!(expr) {
+            if !clause.is_pair() {
+                return Err(InvalidSyntax(format!(
+                    "expected case-lambda clause, but got {}",
+                    clause
+                )));
+            }
+            let lambda = Cell::new_pair(Cell::new_symbol("lambda"), clause.clone());
+            v.push(cdr!(self.transform_definition(&lambda)?).clone());
+        }
+        Ok(Cell::new_list(v))
+    }
+
+    /// Transform Named Let
+    ///
+    /// Transform the expansion of a named let, which named let and do expand
+    /// to (see named_let):
+    ///
+    /// (((lambda (tag) (set! tag (lambda (variable ...) body ...)) tag) #f) init ...)
+    ///
+    /// The inits are transformed outside of the loop, and the body is
+    /// transformed as the body of a procedure bound to tag whose formals are
+    /// the loop variables.
+    ///
+    /// If the body may be compiled into a loop (see is_loop), the expression
+    /// is transformed into the following form for compile_named_let, whose
+    /// keyword can't be named by a program:
+    ///
+    /// (#named-let tag ((variable init) ...) body ...)
+    ///
+    /// Otherwise it's kept as the equivalent recursive procedure.
+    ///
+    /// # Arguments
+    /// `tag` - The tag of the named let
+    /// `formals` - The loop variables
+    /// `body` - The body of the loop
+    /// `inits` - The initial values of the loop variables
+    fn transform_named_let(
+        &mut self,
+        tag: &Cell,
+        formals: &Cell,
+        body: &Cell,
+        inits: &Cell,
+    ) -> Result<Cell, Error> {
+        let mut variables = vec![];
+        for variable in std::iter::once(tag).chain(formals) {
+            if !variable.is_symbol() || variable.is_primitive_symbol() {
+                return Err(InvalidSyntax(format!(
+                    "expected variable, but got {:#}",
+                    variable
+                )));
+            }
+            variables.push(variable.clone());
+        }
+        let tag = variables.remove(0);
+        let inits = inits
+            .iter()
+            .map(|init| self.transform(init))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let lambda = Cell::new_list(
+            vec![
+                Cell::new_symbol("lambda"),
+                Cell::new_list(variables.clone()),
+            ]
+            .into_iter()
+            .chain(body.iter().cloned()),
+        );
+        self.syntax_env.push_scope();
+        let tag = self.bind_variable(&tag);
+        let lambda = self.transform_definition(&lambda);
+        self.syntax_env.pop_scope();
+        let lambda = lambda?;
+
+        let variables = car!(cdr!(lambda)).iter().cloned().collect::<Vec<_>>();
+        let body = cdr!(cdr!(lambda));
+        if is_loop(&tag, &variables, body) {
+            let bindings = variables
+                .into_iter()
+                .zip(inits)
+                .map(|(variable, init)| Cell::new_list(vec![variable, init]));
+            Ok(Cell::new_list(
+                vec![Cell::new_symbol(NAMED_LET), tag, Cell::new_list(bindings)]
+                    .into_iter()
+                    .chain(body.iter().cloned()),
+            ))
+        } else {
+            let set = Cell::new_list(vec![Cell::new_symbol("set!"), tag.clone(), lambda]);
+            let proc = Cell::new_list(vec![
+                Cell::new_symbol("lambda"),
+                Cell::new_list(vec![tag.clone()]),
+                set,
+                tag,
+            ]);
+            Ok(Cell::new_list(
+                vec![Cell::new_list(vec![proc, Cell::Bool(false)])]
+                    .into_iter()
+                    .chain(inits),
+            ))
+        }
+    }
+
+    /// Transform Let Syntax
+    ///
+    /// Transform (let-syntax ((keyword transformer) ...) body ...) or the
+    /// letrec-syntax equivalent into ((lambda () body ...)), where body is
+    /// transformed with each keyword bound to its macro.
+    ///
+    /// The templates of a let-syntax macro refer to the environment outside
+    /// of the let-syntax, while the templates of a letrec-syntax macro may
+    /// also refer to the keywords it binds.
+    ///
+    /// # Arguments
+    /// `expr` - The let-syntax or letrec-syntax expression
+    /// `recursive` - True if expr is a letrec-syntax
+    fn transform_let_syntax(&mut self, expr: &Cell, recursive: bool) -> Result<Cell, Error> {
+        let bindings = car!(cdr!(expr));
+        let depth = match recursive {
+            true => self.syntax_env.depth() + 1,
+            false => self.syntax_env.depth(),
+        };
+
+        let mut macros = vec![];
+        for binding in bindings {
+            let keyword = match binding.collect_vec().as_slice() {
+                [keyword, _] if binding.is_list() && keyword.is_symbol() => (*keyword).clone(),
+                _ => {
+                    return Err(InvalidSyntax(format!(
+                        "expected syntax binding, but got {:#}",
+                        binding
+                    )));
+                }
+            };
+            let define_syntax = Cell::new_pair(Cell::new_symbol("define-syntax"), binding.clone());
+            let transform = Transform::try_new(&define_syntax.strip_aliases())?;
+            macros.push((keyword, SyntaxBinding::Macro(Rc::new(transform), depth)));
+        }
+
+        self.syntax_env.push_scope();
+        for (keyword, binding) in macros {
+            self.syntax_env.bind(keyword, binding);
+        }
+        let body = self.transform_body(cdr!(cdr!(expr)));
+        self.syntax_env.pop_scope();
+
+        let lambda = vec![Cell::new_symbol("lambda"), Cell::Nil];
+        Ok(Cell::new_list(vec![Cell::new_list(
+            lambda.into_iter().chain(body?),
+        )]))
+    }
+
+    /// Transform Body
+    ///
+    /// Transform each expression of a body in order. The variables defined
+    /// by the body, and the macro of any internal define-syntax, are bound
+    /// in the innermost syntax environment scope.
+    ///
+    /// # Arguments
+    /// `body` - The body to transform
+    fn transform_body(&mut self, body: &Cell) -> Result<Vec<Cell>, Error> {
+        let body = &self.splice_includes(body)?;
+        if let Ok(variables) = internally_defined_symbols(body) {
+            for variable in variables {
+                self.bind_variable(variable);
+            }
+        }
+
+        let mut transformed = vec![];
+        for expr in body {
+            if expr.is_pair() && car!(expr).is_define_syntax() {
+                let keyword = car!(cdr!(expr)).clone();
+                let transform = Transform::try_new(&expr.strip_aliases())?;
+                let depth = self.syntax_env.depth();
+                self.syntax_env
+                    .bind(keyword, SyntaxBinding::Macro(Rc::new(transform), depth));
+                transformed.push(expr.clone());
+            } else {
+                transformed.push(self.transform(expr)?);
+            }
+        }
+        Ok(transformed)
+    }
+
+    /// Splice Includes
+    ///
+    /// Replace each include or include-ci expression in a body with the
+    /// expressions contained in its files, as if they were written in place
+    /// of the include. Definitions in the files are then internal
+    /// definitions of the body, and visible to the rest of it.
+    ///
+    /// # Arguments
+    /// `body` - The body to splice includes into
+    fn splice_includes(&self, body: &Cell) -> Result<Cell, Error> {
+        if !body.iter().any(is_include) {
+            return Ok(body.clone());
+        }
+        let mut spliced = vec![];
+        for expr in body {
+            if is_include(expr) {
+                let included = Cell::new_list(self.read_include(expr)?);
+                spliced.extend(self.splice_includes(&included)?.iter().cloned());
+            } else {
+                spliced.push(expr.clone());
+            }
+        }
+        Ok(Cell::new_list(spliced))
+    }
+
     /// Compile Expression
     ///
     /// Compile expression compiles a single expression, emitting its byte code to the currently
@@ -140,11 +598,22 @@ impl Vm {
             Cell::Procedure(_)
             | Cell::Void
             | Cell::Undefined
+            | Cell::Values(_)
+            | Cell::Eof
             | Cell::Macro
-            | Cell::Continuation => Err(InvalidSyntax(expr.to_string())),
-            Cell::Bool(_) | Cell::Char(_) | Cell::Number(_) | Cell::String(_) | Cell::Vector(_) => {
-                self.compile_quote(lambda, expr)
-            }
+            | Cell::Port
+            | Cell::Promise
+            | Cell::Condition(_)
+            | Cell::Continuation
+            | Cell::Environment
+            | Cell::Record(_)
+            | Cell::RecordType(_) => Err(InvalidSyntax(expr.to_string())),
+            Cell::Bool(_)
+            | Cell::Bytevector(_)
+            | Cell::Char(_)
+            | Cell::Number(_)
+            | Cell::String(_)
+            | Cell::Vector(_) => self.compile_quote(lambda, expr),
         }
     }
 
@@ -170,18 +639,53 @@ impl Vm {
         let proc = expr.car().unwrap();
         let rest = expr.cdr().unwrap();
         match proc.deref() {
-            Cell::Symbol(proc) => match proc.as_str() {
+            Cell::Symbol(_) if lambda.loop_entry.as_ref().is_some_and(|it| it.0 == *proc) => {
+                self.compile_loop_jump(lambda, expr)
+            }
+            Cell::Symbol(proc) => match unalias(proc) {
                 "define" => self.compile_define(lambda, expr),
                 "define-syntax" => self.compile_define_syntax(lambda, expr),
+                "define-library" => self.compile_define_library(lambda, expr),
+                "import" => self.compile_import(lambda, expr),
+                "define-record-type" => self.compile_define_record_type(lambda, expr),
+                "define-values" => self.compile_define_values(lambda, expr),
                 "lambda" | "λ" => self.compile_lambda(lambda, expr, false),
+                "case-lambda" => self.compile_case_lambda(lambda, expr),
+                NAMED_LET => self.compile_named_let(lambda, tail, expr),
                 "quasiquote" => self.compile_quasiquote(lambda, car!(rest), 0),
                 "quote" => self.compile_quote(lambda, car!(rest)),
                 "if" => self.compile_if(lambda, tail, expr),
                 "set!" => self.compile_set(lambda, tail, expr),
                 _ => self.compile_runtime_procedure_application(lambda, tail, expr),
             },
-            _ => self.compile_runtime_procedure_application(lambda, tail, expr),
+            _ => match sequence_body(expr) {
+                Some(body) => self.compile_sequence(lambda, tail, body),
+                None => self.compile_runtime_procedure_application(lambda, tail, expr),
+            },
+        }
+    }
+
+    /// Compile Sequence
+    ///
+    /// Compile each expression of body in order, in place of applying a
+    /// lambda with no formals to no arguments (e.g. the expansion of begin).
+    ///
+    /// # Arguments
+    /// `lambda` - The lambda to emit byte code to
+    /// `tail` - Tail is true if the sequence is in a tail position.
+    /// `body` - The expressions to compile, as returned by sequence_body
+    fn compile_sequence(
+        &mut self,
+        lambda: &mut Lambda,
+        tail: bool,
+        body: &Cell,
+    ) -> Result<(), Error> {
+        let mut body = body;
+        while body.is_pair() {
+            self.compile_expression(lambda, tail && cdr!(body).is_nil(), car!(body))?;
+            body = cdr!(body);
         }
+        Ok(())
     }
 
     /// Compile Symbol Expression
@@ -202,8 +706,7 @@ impl Vm {
         let sym_ref = self.heap.put_cell(sym);
         match lambda.binding_location(&sym_ref) {
             BindingLocation::Global => {
-                let sym_ref = sym_ref.as_ptr().expect("expected ptr");
-                let env_slot = VCell::env_slot(self.globenv.get_binding(sym_ref));
+                let env_slot = VCell::env_slot(self.global_slot(sym));
                 lambda.emit(OpCode::Mov);
                 lambda.emit(env_slot);
                 lambda.emit(VCell::Acc);
@@ -268,23 +771,299 @@ impl Vm {
             return Err(InvalidUsePrimitive(symbol.to_string()));
         }
 
-        let sym_ref = self.heap.put_cell(symbol);
-        lambda.emit(OpCode::Mov);
+        self.compile_store_acc(lambda, symbol, true)?;
+
+        lambda.emit(OpCode::MovImmediate);
+        lambda.emit(VCell::Void);
         lambda.emit(VCell::Acc);
+        Ok(())
+    }
+
+    /// Compile Store Acc
+    ///
+    /// Emit the bytecode to store %acc in the binding location of variable,
+    /// which may be the global environment, an argument or the lexical
+    /// environment.
+    ///
+    /// A global definition of an imported variable shadows the import,
+    /// while assigning an imported variable is an error.
+    ///
+    /// `lambda` - The lambda to emit bytecode to
+    /// `variable` - The variable to store %acc in
+    /// `define` - True if the store is a definition of variable
+    fn compile_store_acc(
+        &mut self,
+        lambda: &mut Lambda,
+        variable: &Cell,
+        define: bool,
+    ) -> Result<(), Error> {
+        let sym_ref = self.heap.put_cell(variable);
         match lambda.binding_location(&sym_ref) {
             BindingLocation::Global => {
-                let sym_ref = sym_ref.as_ptr().expect("expected ptr");
-                let env_slot = VCell::env_slot(self.globenv.get_binding(sym_ref));
-                lambda.emit(env_slot);
+                let sym_ref = self
+                    .heap
+                    .put_cell(&variable.unaliased())
+                    .as_ptr()
+                    .expect("expected ptr");
+                let slot = match define {
+                    true => self.globenv.get_definition(sym_ref),
+                    false if !variable.is_alias() && self.globenv.is_imported(sym_ref) => {
+                        return Err(InvalidSyntax(format!(
+                            "cannot assign imported variable {}",
+                            variable
+                        )));
+                    }
+                    false => self.global_slot(variable),
+                };
+                lambda.emit(OpCode::Mov);
+                lambda.emit(VCell::Acc);
+                lambda.emit(VCell::env_slot(slot));
             }
             BindingLocation::Argument(n) => {
                 let arg_offset = 0_i64 - lambda.argc() as i64 + n as i64 + 1;
+                lambda.emit(OpCode::Mov);
+                lambda.emit(VCell::Acc);
                 lambda.emit(BasePointerOffset(arg_offset));
             }
             BindingLocation::Environment(n) => {
+                lambda.emit(OpCode::Mov);
+                lambda.emit(VCell::Acc);
                 lambda.emit(LexicalEnvSlot(n));
             }
         }
+        Ok(())
+    }
+
+    /// Compile Define Record Type
+    ///
+    /// A define-record-type should be in the following form:
+    ///
+    /// * (define-record-type ⟨name⟩ (⟨constructor⟩ ⟨field⟩ ...) ⟨pred⟩ ⟨field spec⟩ ...)
+    ///
+    /// where each field spec is either (⟨field⟩ ⟨accessor⟩) or
+    /// (⟨field⟩ ⟨accessor⟩ ⟨modifier⟩).
+    ///
+    /// A new record type is bound to name, and the constructor, predicate,
+    /// accessors and modifiers are built for it at runtime and bound to their
+    /// variables.
+    ///
+    /// `lambda` - The lambda to emit bytecode to
+    /// `expr` - (define-record-type name constructor pred field spec ...)
+    pub fn compile_define_record_type(
+        &mut self,
+        lambda: &mut Lambda,
+        expr: &Cell,
+    ) -> Result<(), Error> {
+        let rest = cdr!(expr).collect_vec();
+        let (type_name, constructor, predicate, specs) = match rest.as_slice() {
+            [type_name, constructor, predicate, specs @ ..] => {
+                (*type_name, *constructor, *predicate, specs)
+            }
+            _ => {
+                return Err(InvalidNumArgs("define-record-type".into()));
+            }
+        };
+
+        let variable = |variable: &Cell| -> Result<(), Error> {
+            if !variable.is_symbol() || variable.is_primitive_symbol() {
+                return Err(InvalidSyntax(format!(
+                    "expected variable, but got {:#}",
+                    variable
+                )));
+            }
+            Ok(())
+        };
+        variable(type_name)?;
+        variable(predicate)?;
+        if !constructor.is_pair() || !constructor.is_list() {
+            return Err(InvalidSyntax(format!(
+                "expected constructor, but got {:#}",
+                constructor
+            )));
+        }
+        for it in constructor {
+            variable(it)?;
+        }
+
+        let mut fields = vec![];
+        for spec in specs {
+            match spec.collect_vec().as_slice() {
+                [field, accessor] if spec.is_list() => {
+                    variable(field)?;
+                    variable(accessor)?;
+                    fields.push((*field, *accessor, None));
+                }
+                [field, accessor, modifier] if spec.is_list() => {
+                    variable(field)?;
+                    variable(accessor)?;
+                    variable(modifier)?;
+                    fields.push((*field, *accessor, Some(*modifier)));
+                }
+                _ => {
+                    return Err(InvalidSyntax(format!(
+                        "expected field spec, but got {:#}",
+                        spec
+                    )));
+                }
+            }
+        }
+
+        // The record type
+        let field_names = Cell::new_list(fields.iter().map(|it| it.0.clone()));
+        let args = [
+            self.heap.put_cell(&type_name.unaliased()),
+            self.heap.put_cell(&field_names),
+        ];
+        let builtin = VCell::builtin("define-record-type", make_record_type);
+        lambda.emit_call(self.heap.put(builtin), &args);
+        self.compile_store_acc(lambda, type_name, true)?;
+
+        // The constructor, predicate, accessors and modifiers
+        let args = [self.heap.put_cell(cdr!(constructor))];
+        self.compile_record_procedure(lambda, type_name, record_constructor, &args)?;
+        self.compile_store_acc(lambda, car!(constructor), true)?;
+
+        self.compile_record_procedure(lambda, type_name, record_predicate, &[])?;
+        self.compile_store_acc(lambda, predicate, true)?;
+
+        for (field, accessor, modifier) in fields {
+            let args = [self.heap.put_cell(field), self.heap.put_cell(accessor)];
+            self.compile_record_procedure(lambda, type_name, record_accessor, &args)?;
+            self.compile_store_acc(lambda, accessor, true)?;
+            if let Some(modifier) = modifier {
+                let args = [self.heap.put_cell(field), self.heap.put_cell(modifier)];
+                self.compile_record_procedure(lambda, type_name, record_modifier, &args)?;
+                self.compile_store_acc(lambda, modifier, true)?;
+            }
+        }
+
+        lambda.emit(OpCode::MovImmediate);
+        lambda.emit(VCell::Void);
+        lambda.emit(VCell::Acc);
+        Ok(())
+    }
+
+    /// Compile Record Procedure
+    ///
+    /// Emit the bytecode to apply builtin to the record type bound to
+    /// type_name followed by args, leaving the procedure it builds in %acc.
+    ///
+    /// `lambda` - The lambda to emit bytecode to
+    /// `type_name` - The variable the record type is bound to
+    /// `builtin` - The builtin that builds the procedure
+    /// `args` - The remaining arguments to builtin
+    fn compile_record_procedure(
+        &mut self,
+        lambda: &mut Lambda,
+        type_name: &Cell,
+        builtin: fn(&mut Vm) -> Result<VCell, Error>,
+        args: &[VCell],
+    ) -> Result<(), Error> {
+        self.compile_symbol_expression(lambda, type_name)?;
+        lambda.emit(OpCode::PushAcc);
+        for arg in args {
+            lambda.emit(OpCode::PushImmediate);
+            lambda.emit(arg.clone());
+        }
+        lambda.emit(OpCode::PushImmediate);
+        lambda.emit(VCell::ArgumentCount(args.len() + 1));
+        lambda.emit(OpCode::MovImmediate);
+        lambda.emit(self.heap.put(VCell::builtin("define-record-type", builtin)));
+        lambda.emit(VCell::Acc);
+        lambda.emit(OpCode::CallAcc);
+        Ok(())
+    }
+
+    /// Compile Define Values
+    ///
+    /// A define-values should be in the following form:
+    ///
+    /// * (define-values ⟨formals⟩ ⟨expression⟩)
+    ///
+    /// The values of expression are applied to a consumer procedure whose
+    /// formals mirror the shape of formals, and which sets each variable
+    /// being defined to its corresponding argument.
+    ///
+    /// `lambda` - The lambda to emit bytecode to
+    /// `expr` - (define-values formals expression)
+    pub fn compile_define_values(&mut self, lambda: &mut Lambda, expr: &Cell) -> Result<(), Error> {
+        let rest = cdr!(expr);
+        let (formals, expression) = match rest.collect_vec().as_slice() {
+            [formals, expression] => (*formals, *expression),
+            _ => {
+                return Err(InvalidNumArgs("define-values".into()));
+            }
+        };
+
+        // The consumer's arguments are temporary symbols that the reader is
+        // unable to produce, so that they may not shadow any variable.
+        let mut body = vec![];
+        let mut variables = vec![];
+        let mut bind = |variable: &Cell| -> Result<Cell, Error> {
+            if !variable.is_symbol() || variable.is_primitive_symbol() {
+                return Err(InvalidSyntax(format!(
+                    "expected variable, but got {:#}",
+                    variable
+                )));
+            }
+            let temp = Cell::new_symbol(&format!("#values{}", body.len()));
+            variables.push(variable.unaliased());
+            body.push(Cell::new_list(vec![
+                Cell::new_symbol("set!"),
+                variable.clone(),
+                temp.clone(),
+            ]));
+            Ok(temp)
+        };
+
+        let mut args = vec![];
+        let mut rest = formals;
+        while let Cell::Pair(variable, cdr) = rest {
+            args.push(bind(variable)?);
+            rest = cdr;
+        }
+        let args = match rest {
+            Cell::Nil => Cell::new_list(args),
+            rest if args.is_empty() => bind(rest)?,
+            rest => Cell::new_improper_list(args, bind(rest)?),
+        };
+        if body.is_empty() {
+            body.push(Cell::Bool(false));
+        }
+
+        // A top level definition of an imported variable shadows the import
+        // before the consumer assigns it.
+        if lambda.is_top_level() {
+            for variable in variables {
+                let sym_ref = self.heap.put_cell(&variable).as_ptr()?;
+                self.globenv.get_definition(sym_ref);
+            }
+        }
+        let consumer = Cell::new_list(
+            vec![Cell::new_symbol("lambda"), args]
+                .into_iter()
+                .chain(body),
+        );
+
+        // Apply the consumer to the values of expression. The consumer is
+        // described by the user's formals, so that an arity error doesn't
+        // refer to the temporaries.
+        self.compile_closure(
+            lambda,
+            &consumer,
+            false,
+            None,
+            Some(formals.strip_aliases()),
+        )?;
+        lambda.emit(OpCode::PushAcc);
+        self.compile_expression(lambda, false, expression)?;
+        lambda.emit(OpCode::PushAcc);
+        lambda.emit(OpCode::PushImmediate);
+        lambda.emit(VCell::ArgumentCount(2));
+        lambda.emit(OpCode::MovImmediate);
+        lambda.emit(self.heap.put(VCell::builtin("define-values", apply_values)));
+        lambda.emit(VCell::Acc);
+        lambda.emit(OpCode::CallAcc);
 
         lambda.emit(OpCode::MovImmediate);
         lambda.emit(VCell::Void);
@@ -326,23 +1105,7 @@ impl Vm {
 
         self.compile_expression(lambda, false, expression)?;
 
-        let sym_ref = self.heap.put_cell(variable);
-        lambda.emit(OpCode::Mov);
-        lambda.emit(VCell::Acc);
-        match lambda.binding_location(&sym_ref) {
-            BindingLocation::Global => {
-                let sym_ref = sym_ref.as_ptr().expect("expected ptr");
-                let env_slot = VCell::env_slot(self.globenv.get_binding(sym_ref));
-                lambda.emit(env_slot);
-            }
-            BindingLocation::Argument(n) => {
-                let arg_offset = 0_i64 - lambda.argc() as i64 + n as i64 + 1;
-                lambda.emit(BasePointerOffset(arg_offset));
-            }
-            BindingLocation::Environment(n) => {
-                lambda.emit(LexicalEnvSlot(n));
-            }
-        }
+        self.compile_store_acc(lambda, variable, false)?;
 
         lambda.emit(OpCode::MovImmediate);
         lambda.emit(VCell::Void);
@@ -358,12 +1121,23 @@ impl Vm {
     /// `lambda` - The lambda to emit bytecode to
     /// `expr` - (define variable expression)    
     pub fn compile_define_syntax(&mut self, lambda: &mut Lambda, expr: &Cell) -> Result<(), Error> {
-        let transform = Transform::try_new(expr)?;
+        let mut transform = Transform::try_new(&expr.strip_aliases())?;
+
+        // An internal define-syntax was bound to its body's syntax
+        // environment when the body was transformed.
+        if !lambda.is_top_level() {
+            lambda.emit(OpCode::MovImmediate);
+            lambda.emit(VCell::void());
+            lambda.emit(VCell::Acc);
+            return Ok(());
+        }
+
         let symbol = transform.keyword().clone();
+        transform.set_environment(&self.globenv.environment());
         let transform = self.heap.put(VCell::Macro(Rc::new(transform)));
 
         let sym_ref = self.heap.put_cell(&symbol).as_ptr()?;
-        let env_slot = VCell::env_slot(self.globenv.get_binding(sym_ref));
+        let env_slot = VCell::env_slot(self.globenv.get_definition(sym_ref));
 
         lambda.emit(OpCode::MovImmediate);
         lambda.emit(transform);
@@ -374,6 +1148,81 @@ impl Vm {
         Ok(())
     }
 
+    /// Compile Define Library
+    ///
+    /// Compile a define-library expression to an application of the
+    /// define-library builtin, which defines the library when the
+    /// expression is evaluated.
+    ///
+    /// Like import, define-library is only allowed at the top level of an
+    /// environment that isn't derived.
+    ///
+    /// `lambda` - The lambda to emit bytecode to
+    /// `expr` - (define-library name declaration ...)
+    pub fn compile_define_library(
+        &mut self,
+        lambda: &mut Lambda,
+        expr: &Cell,
+    ) -> Result<(), Error> {
+        if !lambda.is_top_level() || self.globenv.environment().is_derived() {
+            return Err(InvalidSyntax(format!(
+                "out of context define-library: {:#}",
+                expr
+            )));
+        }
+        let builtin = self
+            .heap
+            .put(VCell::builtin("define-library", define_library));
+        let expr = self.heap.put_cell(&expr.strip_aliases());
+        lambda.emit_call(builtin, &[expr]);
+        Ok(())
+    }
+
+    /// Compile Import
+    ///
+    /// Compile an import expression to an application of the import
+    /// builtin, which binds the imported bindings in the environment the
+    /// expression is compiled against when the expression is evaluated.
+    ///
+    /// An import is only allowed at the top level of the interaction
+    /// environment or a library body. A derived environment, such as one
+    /// made by copy-environment, can't import its way to bindings it was
+    /// made without.
+    ///
+    /// `lambda` - The lambda to emit bytecode to
+    /// `expr` - (import import-set ...)
+    pub fn compile_import(&mut self, lambda: &mut Lambda, expr: &Cell) -> Result<(), Error> {
+        if !lambda.is_top_level() || self.globenv.environment().is_derived() {
+            return Err(InvalidSyntax(format!("out of context import: {:#}", expr)));
+        }
+        let builtin = self.heap.put(VCell::builtin("import", import));
+        let sets = self.heap.put_cell(&cdr!(expr).strip_aliases());
+        let environment = self
+            .heap
+            .put(VCell::Environment(self.globenv.environment()));
+        lambda.emit_call(builtin, &[sets, environment]);
+        Ok(())
+    }
+
+    /// Compile Include
+    ///
+    /// Compile a top level include or include-ci expression to an
+    /// application of the include builtin, which evaluates the expressions
+    /// contained in the files against the environment the expression is
+    /// compiled against.
+    ///
+    /// `lambda` - The lambda to emit bytecode to
+    /// `expr` - (include string ...) or (include-ci string ...)
+    pub fn compile_include(&mut self, lambda: &mut Lambda, expr: &Cell) -> Result<(), Error> {
+        let builtin = self.heap.put(VCell::builtin("include", include));
+        let expr = self.heap.put_cell(&expr.strip_aliases());
+        let environment = self
+            .heap
+            .put(VCell::Environment(self.globenv.environment()));
+        lambda.emit_call(builtin, &[expr, environment]);
+        Ok(())
+    }
+
     /// Compile Lambda
     ///
     /// A lambda should be the following form:
@@ -401,6 +1250,32 @@ impl Vm {
         iof: &mut Lambda,
         expr: &Cell,
         is_define_special: bool,
+    ) -> Result<(), Error> {
+        self.compile_closure(iof, expr, is_define_special, None, None)
+    }
+
+    /// Compile Closure
+    ///
+    /// Compile a lambda as compile_lambda does. If loop_tag is given, the
+    /// lambda is the body of a named let loop, and any application of the tag
+    /// within it is compiled into a jump back to the start of the body. If
+    /// desc is given, it replaces the formal arguments in the lambda's
+    /// description, which is used in error messages.
+    ///
+    /// # Arguments
+    /// `iof` - The immediate outer function in which to inherit an
+    ///         environment from
+    /// `expr` - The full lambda expression
+    /// `is_define_special` - Is this a define special form?
+    /// `loop_tag` - The tag of the named let loop, if any
+    /// `desc` - The formal arguments to describe the lambda with, if any
+    fn compile_closure(
+        &mut self,
+        iof: &mut Lambda,
+        expr: &Cell,
+        is_define_special: bool,
+        loop_tag: Option<Cell>,
+        desc: Option<Cell>,
     ) -> Result<(), Error> {
         let rest = cdr!(expr);
         if rest.is_nil() {
@@ -434,11 +1309,12 @@ impl Vm {
             &free_symbols,
             is_vararg,
         );
-        lambda.set_desc(formal_args_ast.clone());
+        lambda.set_desc(desc.unwrap_or_else(|| formal_args_ast.strip_aliases()));
         if lambda.is_vararg {
             lambda.emit(OpCode::VarArg);
         }
         lambda.emit(OpCode::Enter);
+        lambda.loop_entry = loop_tag.map(|tag| (tag, lambda.bc.len()));
 
         // Compile each body expression in sequence
         let mut body = body;
@@ -460,6 +1336,121 @@ impl Vm {
         Ok(())
     }
 
+    /// Compile Case Lambda
+    ///
+    /// A case-lambda is the following form:
+    ///
+    /// (case-lambda (⟨formals⟩ ⟨body⟩) ...)
+    ///
+    /// Each clause is compiled to a closure as if it were a lambda, and the
+    /// closures are pushed on the stack. CASELAMBDA then pops the closures
+    /// into a single case-lambda procedure. When the procedure is applied,
+    /// the first clause that accepts the number of arguments is applied in
+    /// its place.
+    ///
+    /// # Arguments
+    /// `iof` - The immediate outer function in which to inherit an
+    ///         environment from
+    /// `expr` - The full case-lambda expression
+    pub fn compile_case_lambda(&mut self, iof: &mut Lambda, expr: &Cell) -> Result<(), Error> {
+        let mut n = 0;
+        for clause in cdr!(expr) {
+            let lambda = Cell::new_pair(Cell::new_symbol("lambda"), clause.clone());
+            self.compile_lambda(iof, &lambda, false)?;
+            iof.emit(OpCode::PushAcc);
+            n += 1;
+        }
+        iof.emit(OpCode::CaseLambda);
+        iof.emit(VCell::ArgumentCount(n));
+        Ok(())
+    }
+
+    /// Compile Named Let
+    ///
+    /// A named let that transform_named_let found to be a loop is in the
+    /// following form:
+    ///
+    /// (#named-let tag ((variable init) ...) body ...)
+    ///
+    /// The inits are pushed as arguments, and the body is compiled to a
+    /// closure whose formals are the loop variables, which is then applied.
+    /// Each application of tag within the body is in a tail position, and is
+    /// compiled by compile_loop_jump into a jump back to the start of the
+    /// body instead of a procedure call.
+    ///
+    /// # Arguments
+    /// `iof` - The immediate outer function in which to inherit an
+    ///         environment from
+    /// `tail` - Tail is true if this expression is in a tail position.
+    /// `expr` - The full named-let expression
+    pub fn compile_named_let(
+        &mut self,
+        iof: &mut Lambda,
+        tail: bool,
+        expr: &Cell,
+    ) -> Result<(), Error> {
+        let tag = car!(cdr!(expr));
+        let bindings = car!(cdr!(cdr!(expr)));
+        let body = cdr!(cdr!(cdr!(expr)));
+
+        let mut variables = vec![];
+        for binding in bindings {
+            self.compile_expression(iof, false, car!(cdr!(binding)))?;
+            iof.emit(OpCode::PushAcc);
+            variables.push(car!(binding).clone());
+        }
+        iof.emit(OpCode::PushImmediate);
+        iof.emit(VCell::ArgumentCount(variables.len()));
+
+        let lambda = Cell::new_pair(
+            Cell::new_symbol("lambda"),
+            Cell::new_pair(Cell::new_list(variables), body.clone()),
+        );
+        self.compile_closure(iof, &lambda, false, Some(tag.clone()), None)?;
+        iof.emit(match tail {
+            true => OpCode::TCallAcc,
+            false => OpCode::CallAcc,
+        });
+        Ok(())
+    }
+
+    /// Compile Loop Jump
+    ///
+    /// Compile an application of a named let's tag within its loop body.
+    /// The arguments are evaluated and pushed left-to-right, then popped
+    /// into the loop body's argument slots in reverse. The loop variables are
+    /// then bound to them in a new lexical environment, so that each
+    /// iteration has its own bindings (e.g. for a continuation captured by
+    /// an earlier iteration), and execution jumps back to the start of the
+    /// body.
+    ///
+    /// # Arguments
+    /// `lambda` - The loop body to emit bytecode to
+    /// `expr` - (tag arg ...)
+    fn compile_loop_jump(&mut self, lambda: &mut Lambda, expr: &Cell) -> Result<(), Error> {
+        for arg in cdr!(expr) {
+            self.compile_expression(lambda, false, arg)?;
+            lambda.emit(OpCode::PushAcc);
+        }
+
+        for n in (0..lambda.argc()).rev() {
+            let arg_offset = 0_i64 - lambda.argc() as i64 + n as i64 + 1;
+            lambda.emit(OpCode::PopAcc);
+            lambda.emit(OpCode::Mov);
+            lambda.emit(VCell::Acc);
+            lambda.emit(BasePointerOffset(arg_offset));
+        }
+        lambda.emit(OpCode::Rebind);
+
+        let offset = match &lambda.loop_entry {
+            Some((_, offset)) => *offset,
+            None => return Err(InvalidBytecode),
+        };
+        lambda.emit(OpCode::Jmp);
+        lambda.emit(VCell::ptr(offset));
+        Ok(())
+    }
+
     /// Compile Formal Arguments
     ///
     /// Given a lambda call, extract the formal arguments, place the symbols
@@ -637,7 +1628,7 @@ impl Vm {
     /// `expr` - The expression to quote.
     pub fn compile_quote(&mut self, lambda: &mut Lambda, expr: &Cell) -> Result<(), Error> {
         lambda.emit(OpCode::MovImmediate);
-        lambda.emit(self.heap.maybe_put_cell(expr));
+        lambda.emit(self.heap.maybe_put_cell(&expr.strip_aliases()));
         lambda.emit(VCell::Acc);
         Ok(())
     }
@@ -646,7 +1637,9 @@ impl Vm {
     ///
     /// Unlike quote, quasiquote may contain a mix of quoted and unquoted expressions. Quasiquote
     /// is compiled as a chain of CONS instructions, calling back into compile_expression for any
-    /// expressions that have been unquoted.
+    /// expressions that have been unquoted. Expressions spliced with unquote-splicing are
+    /// joined to the rest of the list with APPEND, and vector templates are compiled as a list
+    /// template that is converted to a new vector with VECTOR.
     ///
     /// # Arguments
     /// `lambda` - The lambda to emit bytecode to
@@ -662,19 +1655,9 @@ impl Vm {
         // Vector
         //
         if expr.is_vector() {
-            let vector = expr.as_vector().unwrap();
-
-            let new_vector = self.heap.put(VCell::vector(vec![]));
-            lambda.emit(OpCode::MovImmediate);
-            lambda.emit(new_vector);
-            lambda.emit(VCell::Acc);
-
-            for it in vector {
-                lambda.emit(OpCode::PushAcc);
-                self.compile_quasiquote(lambda, it, depth)?;
-                lambda.emit(OpCode::VPushAcc);
-            }
-
+            let list = Cell::new_list(expr.as_vector().unwrap().iter().cloned());
+            self.compile_quasiquote_list(lambda, &list, depth, true)?;
+            lambda.emit(OpCode::VectorAcc);
             return Ok(());
         }
 
@@ -688,7 +1671,8 @@ impl Vm {
         //
         // Pair / List
         //
-        if expr.car().unwrap().is_unquote() {
+        let car = expr.car().unwrap();
+        if car.is_unquote() {
             if depth == 0 {
                 return self.compile_expression(lambda, false, car!(cdr!(expr)));
             } else {
@@ -696,29 +1680,364 @@ impl Vm {
             }
         }
 
-        if expr.car().unwrap().is_quasiquote() {
+        if car.is_unquote_splicing() {
+            if depth == 0 {
+                return Err(InvalidSyntax(format!(
+                    "{} is not in a list or vector context",
+                    expr
+                )));
+            } else {
+                depth -= 1;
+            }
+        }
+
+        if car.is_quasiquote() {
             depth += 1;
         }
 
-        let mut count = 0;
+        self.compile_quasiquote_list(lambda, expr, depth, false)
+    }
+
+    /// Compile Quasiquote List
+    ///
+    /// Compile the elements of a quasiquoted list template. Each element is evaluated
+    /// and pushed on the stack in order, followed by the tail of the list. The list is
+    /// then built from right to left, with CONS for regular elements and APPEND for
+    /// elements spliced in by unquote-splicing at depth 0.
+    ///
+    /// An improper tail that is itself an unquote form (e.g. `(1 . ,x)`, which reads as
+    /// `(1 unquote x)`) is compiled as the tail of the list rather than as two elements.
+    ///
+    /// Unless the template is a vector's, an unquote-splicing that is the last element
+    /// is the tail of the list as-is, and so like the last argument to append may be
+    /// any value, e.g. `(1 ,@5) is (1 . 5).
+    ///
+    /// # Arguments
+    /// `lambda` - The lambda to emit bytecode to
+    /// `expr` - The list template
+    /// `depth` - The quasiquote depth
+    /// `vector` - True if the template is a vector's elements
+    fn compile_quasiquote_list(
+        &mut self,
+        lambda: &mut Lambda,
+        expr: &Cell,
+        depth: usize,
+        vector: bool,
+    ) -> Result<(), Error> {
+        let mut splices = vec![];
         let mut rest = expr;
         while rest.is_pair() {
+            if !splices.is_empty() && is_quasiquote_form(rest) {
+                break;
+            }
             let car = rest.car().unwrap();
-            self.compile_quasiquote(lambda, car, depth)?;
+            if depth == 0 && car.is_pair() && car.car().unwrap().is_unquote_splicing() {
+                self.compile_expression(lambda, false, car!(cdr!(car)))?;
+                if !vector && rest.cdr().unwrap().is_nil() {
+                    return self.compile_quasiquote_cons(lambda, &splices);
+                }
+                splices.push(true);
+            } else {
+                self.compile_quasiquote(lambda, car, depth)?;
+                splices.push(false);
+            }
             lambda.emit(OpCode::PushAcc);
             rest = rest.cdr().unwrap();
-            count += 1;
         }
-        lambda.emit(OpCode::PushImmediate);
-        lambda.emit(self.heap.maybe_put_cell(rest));
 
-        for i in 0..count {
-            lambda.emit(OpCode::Cons);
-            if i < count - 1 {
-                lambda.emit(OpCode::PushAcc);
-            }
+        if splices.is_empty() {
+            return self.compile_quote(lambda, rest);
+        } else if rest.is_pair() {
+            self.compile_quasiquote(lambda, rest, depth)?;
+        } else {
+            self.compile_quote(lambda, rest)?;
         }
+        self.compile_quasiquote_cons(lambda, &splices)
+    }
 
+    /// Compile Quasiquote Cons
+    ///
+    /// Build a quasiquoted list from right to left given the tail of the list in %acc,
+    /// preceded on the stack by each element, with CONS for regular elements and APPEND
+    /// for elements spliced in by unquote-splicing.
+    ///
+    /// # Arguments
+    /// `lambda` - The lambda to emit bytecode to
+    /// `splices` - For each element on the stack in order, true if it's spliced
+    fn compile_quasiquote_cons(
+        &mut self,
+        lambda: &mut Lambda,
+        splices: &[bool],
+    ) -> Result<(), Error> {
+        for splice in splices.iter().rev() {
+            lambda.emit(OpCode::PushAcc);
+            lambda.emit(match splice {
+                true => OpCode::Append,
+                false => OpCode::Cons,
+            });
+        }
         Ok(())
     }
 }
+
+/// Is Quasiquote Form
+///
+/// Return true if expr is a two element list starting with quasiquote, unquote
+/// or unquote-splicing, such as the tail of `(1 . ,x)`.
+///
+/// # Arguments
+/// `expr` - The expression to check
+fn is_quasiquote_form(expr: &Cell) -> bool {
+    match (expr.car(), expr.cdr()) {
+        (Some(car), Some(cdr)) => {
+            (car.is_quasiquote() || car.is_unquote() || car.is_unquote_splicing())
+                && cdr.is_pair()
+                && cdr.cdr().unwrap().is_nil()
+        }
+        _ => false,
+    }
+}
+
+/// Sequence Body
+///
+/// Return the body of expr if expr applies a lambda with no formals to no
+/// arguments, such as the expansion of begin, and the body has no internal
+/// definitions. The body may then be evaluated in place of the application.
+///
+/// # Arguments
+/// `expr` - The expression to check
+fn sequence_body(expr: &Cell) -> Option<&Cell> {
+    let lambda = match expr {
+        Cell::Pair(lambda, args) if args.is_nil() => lambda.as_ref(),
+        _ => return None,
+    };
+    let keyword = lambda.car()?;
+    if !keyword.is_lambda() && !keyword.is_symbol_str("λ") {
+        return None;
+    }
+    if !lambda.cdr()?.car()?.is_nil() {
+        return None;
+    }
+    let body = lambda.cdr()?.cdr()?;
+    match body.is_list() && !body.iter().any(is_definition) {
+        true => Some(body),
+        false => None,
+    }
+}
+
+/// Is Definition
+///
+/// Return true if expr is a define, define-values, define-record-type or
+/// define-syntax expression.
+fn is_definition(expr: &Cell) -> bool {
+    match expr.car() {
+        Some(car) => {
+            car.is_define()
+                || car.is_define_values()
+                || car.is_define_record_type()
+                || car.is_define_syntax()
+        }
+        None => false,
+    }
+}
+
+/// Named Let
+///
+/// If expr is the expansion of a named let:
+///
+/// (((lambda (tag) (set! tag (lambda (variable ...) body ...)) tag) #f) init ...)
+///
+/// return its tag, variables, body and inits. The expansion is only
+/// recognized if there are as many inits as variables.
+fn named_let(expr: &Cell) -> Option<(&Cell, &Cell, &Cell, &Cell)> {
+    let is_lambda = |cell: &Cell| cell.is_lambda() || cell.is_symbol_str("λ");
+    let (proc, inits) = (expr.car()?, expr.cdr()?);
+    let (tag, definition) = match proc.collect_vec().as_slice() {
+        [binder, Cell::Bool(false)] if proc.is_list() => match binder.collect_vec().as_slice() {
+            [keyword, formals, definition, tag]
+                if binder.is_list()
+                    && is_lambda(keyword)
+                    && formals.is_list()
+                    && formals.collect_vec().as_slice() == [*tag] =>
+            {
+                (*tag, *definition)
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let lambda = match definition.collect_vec().as_slice() {
+        [set, variable, lambda]
+            if definition.is_list() && set.is_symbol_str("set!") && *variable == tag =>
+        {
+            *lambda
+        }
+        _ => return None,
+    };
+    let keyword = lambda.car()?;
+    let formals = lambda.cdr()?.car()?;
+    let body = lambda.cdr()?.cdr()?;
+    let is_list = |cell: &Cell| cell.is_nil() || cell.is_list();
+    match is_lambda(keyword)
+        && is_list(formals)
+        && is_list(inits)
+        && formals.iter().count() == inits.iter().count()
+    {
+        true => Some((tag, formals, body, inits)),
+        false => None,
+    }
+}
+
+/// Is Loop
+///
+/// Return true if a named let with the given tag, loop variables and
+/// transformed body may be compiled into a loop, which requires that:
+///
+/// * tag is only ever applied in a tail position of the body, to as many
+///   arguments as there are loop variables, and is otherwise unreferenced.
+/// * the body has no internal definitions.
+///
+/// # Arguments
+/// `tag` - The named let's tag
+/// `variables` - The loop variables
+/// `body` - The transformed body
+fn is_loop(tag: &Cell, variables: &[Cell], body: &Cell) -> bool {
+    body.is_list()
+        && !body.iter().any(is_definition)
+        && is_loop_sequence(Some(tag), variables, body, true)
+}
+
+/// Is Loop Sequence
+///
+/// Return true if each expression of body passes is_loop_expression, given
+/// that only the last expression may be in a tail position.
+fn is_loop_sequence(tag: Option<&Cell>, variables: &[Cell], body: &Cell, tail: bool) -> bool {
+    let mut rest = body;
+    while let Cell::Pair(expr, next) = rest {
+        if !is_loop_expression(tag, variables, expr, tail && next.is_nil()) {
+            return false;
+        }
+        rest = next;
+    }
+    true
+}
+
+/// Is Loop Expression
+///
+/// Return true if expr meets the requirements of is_loop. A tag of None
+/// means that the tag has been shadowed, and no longer refers to the loop.
+///
+/// # Arguments
+/// `tag` - The named let's tag, if it's in scope
+/// `variables` - The loop variables
+/// `expr` - The transformed expression to check
+/// `tail` - True if expr is in a tail position of the loop body
+fn is_loop_expression(tag: Option<&Cell>, variables: &[Cell], expr: &Cell, tail: bool) -> bool {
+    let (proc, args) = match expr {
+        Cell::Symbol(_) => return tag != Some(expr),
+        Cell::Pair(proc, args) if args.is_nil() || args.is_list() => (proc.as_ref(), args.as_ref()),
+        Cell::Pair(_, _) => return false,
+        _ => return true,
+    };
+    let mentions_tag = || tag.is_some_and(|tag| contains_symbol(expr, tag));
+
+    if let Some(body) = sequence_body(expr) {
+        return is_loop_sequence(tag, variables, body, tail);
+    }
+    if tag == Some(proc) {
+        return tail
+            && args.iter().count() == variables.len()
+            && is_loop_sequence(tag, variables, args, false);
+    }
+
+    // A lambda that's applied immediately (e.g. the expansion of let) is a
+    // separate procedure that can't jump back to the start of the loop.
+    if proc
+        .car()
+        .is_some_and(|it| it.is_lambda() || it.is_symbol_str("λ"))
+    {
+        let formals = proc.cdr().and_then(|it| it.car()).unwrap_or(&Cell::Nil);
+        let body = proc.cdr().and_then(|it| it.cdr()).unwrap_or(&Cell::Nil);
+        return (formals.is_nil() || formals.is_list())
+            && is_loop_sequence(tag, variables, args, false)
+            && is_loop_body(tag, variables, formals.iter(), body);
+    }
+
+    let keyword = match proc {
+        Cell::Symbol(sym) => unalias(sym),
+        _ => "",
+    };
+    match keyword {
+        "quote" | "define-syntax" => true,
+        "quasiquote" | "define-record-type" | "lambda" | "λ" | "case-lambda" => !mentions_tag(),
+        "define" | "define-values" if args.car().is_some_and(|it| it.is_pair()) => !mentions_tag(),
+        "define" | "define-values" => {
+            !mentions_tag() && is_loop_sequence(tag, variables, cdr_or_nil(args), false)
+        }
+        NAMED_LET => {
+            let bindings = car_or_nil(cdr_or_nil(args));
+            let body = cdr_or_nil(cdr_or_nil(args));
+            let formals = std::iter::once(car_or_nil(args)).chain(bindings.iter().map(car_or_nil));
+            bindings
+                .iter()
+                .all(|it| is_loop_sequence(tag, variables, cdr_or_nil(it), false))
+                && is_loop_body(tag, variables, formals, body)
+        }
+        "if" => {
+            (2..=3).contains(&args.iter().count())
+                && is_loop_expression(tag, variables, car_or_nil(args), false)
+                && cdr_or_nil(args)
+                    .iter()
+                    .all(|it| is_loop_expression(tag, variables, it, tail))
+        }
+        "set!" => tag != args.car() && is_loop_sequence(tag, variables, cdr_or_nil(args), false),
+        _ => {
+            is_loop_expression(tag, variables, proc, false)
+                && is_loop_sequence(tag, variables, args, false)
+        }
+    }
+}
+
+/// Is Loop Body
+///
+/// Return true if the body of a procedure that's applied immediately within
+/// the loop body meets the requirements of is_loop. The procedure can't jump
+/// back to the start of the loop, so no expression in its body is in a tail
+/// position of the loop, and the tag no longer refers to the loop if it's
+/// shadowed by one of the formals.
+///
+/// # Arguments
+/// `tag` - The named let's tag, if it's in scope
+/// `variables` - The loop variables
+/// `formals` - The formals of the procedure
+/// `body` - The body of the procedure
+fn is_loop_body<'a>(
+    tag: Option<&Cell>,
+    variables: &[Cell],
+    mut formals: impl Iterator<Item = &'a Cell>,
+    body: &Cell,
+) -> bool {
+    let tag = match tag {
+        Some(tag) if formals.any(|it| it == tag) => None,
+        tag => tag,
+    };
+    body.is_list() && is_loop_sequence(tag, variables, body, false)
+}
+
+/// Contains Symbol
+///
+/// Return true if sym appears anywhere within expr.
+fn contains_symbol(expr: &Cell, sym: &Cell) -> bool {
+    match expr {
+        Cell::Pair(car, cdr) => contains_symbol(car, sym) || contains_symbol(cdr, sym),
+        _ => expr == sym,
+    }
+}
+
+fn car_or_nil(cell: &Cell) -> &Cell {
+    cell.car().unwrap_or(&Cell::Nil)
+}
+
+fn cdr_or_nil(cell: &Cell) -> &Cell {
+    cell.cdr().unwrap_or(&Cell::Nil)
+}
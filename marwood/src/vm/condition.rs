@@ -0,0 +1,190 @@
+use crate::cell::Cell;
+use crate::error::Error;
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+
+/// Condition Kind
+///
+/// The kind of condition an error object represents, which allows a
+/// handler to tell apart a user raised error from a type error, read
+/// error, etc.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConditionKind {
+    ArityError,
+    Error,
+    FileError,
+    RangeError,
+    ReadError,
+    RuntimeError,
+    SyntaxError,
+    TypeError,
+    UnboundVariable,
+}
+
+impl ConditionKind {
+    /// Symbol
+    ///
+    /// Return the symbol error-object-kind evaluates to for this kind
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ConditionKind::ArityError => "arity-error",
+            ConditionKind::Error => "error",
+            ConditionKind::FileError => "file-error",
+            ConditionKind::RangeError => "range-error",
+            ConditionKind::ReadError => "read-error",
+            ConditionKind::RuntimeError => "runtime-error",
+            ConditionKind::SyntaxError => "syntax-error",
+            ConditionKind::TypeError => "type-error",
+            ConditionKind::UnboundVariable => "unbound-variable",
+        }
+    }
+}
+
+/// Condition
+///
+/// A condition is the error object created by error, or raised on behalf
+/// of a runtime error. It holds the kind, message and irritants visible to
+/// scheme, along with the runtime error that results if the condition is
+/// never handled.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Condition {
+    kind: ConditionKind,
+    message: String,
+    irritants: Vec<VCell>,
+    error: Error,
+}
+
+impl Condition {
+    pub fn new(kind: ConditionKind, message: String, irritants: Vec<VCell>, error: Error) -> Self {
+        Condition {
+            kind,
+            message,
+            irritants,
+            error,
+        }
+    }
+
+    pub fn kind(&self) -> ConditionKind {
+        self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn irritants(&self) -> &[VCell] {
+        &self.irritants
+    }
+
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+}
+
+impl Vm {
+    /// Error To Condition
+    ///
+    /// Map a runtime error to a condition with a kind, message and irritants.
+    /// Any cells held by the error are placed on the heap as irritants.
+    ///
+    /// # Arguments
+    /// `error` - The runtime error to map
+    pub fn error_to_condition(&mut self, error: Error) -> Condition {
+        let (kind, message, irritants) = match &error {
+            Error::ErrorSignal(cells) => match cells.split_first() {
+                Some((Cell::String(message), irritants)) => {
+                    (ConditionKind::Error, message.clone(), irritants.to_vec())
+                }
+                _ => (ConditionKind::Error, String::new(), cells.clone()),
+            },
+            Error::ExpectedType(_, _) | Error::InvalidArgs(_, _, _) => {
+                (ConditionKind::TypeError, error.to_string(), vec![])
+            }
+            Error::ExpectedPairButFound(cell) => (
+                ConditionKind::TypeError,
+                "expected pair".into(),
+                vec![cell.clone()],
+            ),
+            Error::InvalidProcedure(cell) => (
+                ConditionKind::TypeError,
+                "call of non-procedure".into(),
+                vec![cell.clone()],
+            ),
+            Error::InvalidNumArgs(_) => (ConditionKind::ArityError, error.to_string(), vec![]),
+            Error::InvalidVectorIndex(idx, _) => (
+                ConditionKind::RangeError,
+                "vector index out of range".into(),
+                vec![Cell::from(*idx as i64)],
+            ),
+            Error::InvalidBytevectorIndex(idx, _) => (
+                ConditionKind::RangeError,
+                "bytevector index out of range".into(),
+                vec![Cell::from(*idx as i64)],
+            ),
+            Error::InvalidStringIndex(idx, _) => (
+                ConditionKind::RangeError,
+                "string index out of range".into(),
+                vec![Cell::from(*idx as i64)],
+            ),
+            Error::FileError(path, reason) => (
+                ConditionKind::FileError,
+                reason.clone(),
+                vec![Cell::String(path.clone())],
+            ),
+            Error::LoadError(_, inner) => {
+                let kind = self.error_to_condition(inner.as_ref().clone()).kind();
+                (kind, error.to_string(), vec![])
+            }
+            Error::VariableNotBound(sym) => (
+                ConditionKind::UnboundVariable,
+                "variable is not bound".into(),
+                vec![Cell::new_symbol(sym)],
+            ),
+            Error::InvalidUsePrimitive(_)
+            | Error::LambdaMissingExpression
+            | Error::MisplacedMacroKeyword(_)
+            | Error::UnquotedNil => (ConditionKind::SyntaxError, error.to_string(), vec![]),
+            Error::ParseError(_) | Error::LexError(_) => {
+                (ConditionKind::ReadError, error.to_string(), vec![])
+            }
+            Error::UncaughtException(cell) => (
+                ConditionKind::Error,
+                "uncaught exception".into(),
+                vec![cell.clone()],
+            ),
+            Error::InvalidBytecode
+            | Error::InvalidStackIndex(_)
+            | Error::InvalidSyntax(_)
+            | Error::LibraryNotFound(_) => (ConditionKind::RuntimeError, error.to_string(), vec![]),
+        };
+
+        // Irritants that can't be placed back on the heap, such as procedures,
+        // are instead described by the message.
+        if !irritants.iter().all(is_datum) {
+            return Condition::new(kind, error.to_string(), vec![], error);
+        }
+        let irritants = irritants.iter().map(|it| self.heap.put_cell(it)).collect();
+        Condition::new(kind, message, irritants, error)
+    }
+}
+
+/// Is Datum
+///
+/// Return true if the cell is a datum that may be placed on the heap
+/// with put_cell.
+fn is_datum(cell: &Cell) -> bool {
+    match cell {
+        Cell::Pair(car, cdr) => is_datum(car) && is_datum(cdr),
+        Cell::Vector(cells) | Cell::Values(cells) => cells.iter().all(is_datum),
+        Cell::Condition(_)
+        | Cell::Continuation
+        | Cell::Environment
+        | Cell::Macro
+        | Cell::Port
+        | Cell::Promise
+        | Cell::Procedure(_)
+        | Cell::Record(_)
+        | Cell::RecordType(_) => false,
+        _ => true,
+    }
+}
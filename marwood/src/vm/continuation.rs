@@ -1,3 +1,7 @@
+use crate::error::Error;
+use crate::vm::builtin::pop_argc;
+use crate::vm::lambda::Lambda;
+use crate::vm::opcode::OpCode;
 use crate::vm::stack::Stack;
 use crate::vm::vcell::VCell;
 use crate::vm::Vm;
@@ -8,6 +12,8 @@ pub struct Continuation {
     ep: usize,
     ip: (usize, usize),
     bp: usize,
+    wind: VCell,
+    handlers: VCell,
 }
 
 impl Continuation {
@@ -26,6 +32,14 @@ impl Continuation {
     pub fn bp(&self) -> usize {
         self.bp
     }
+
+    pub fn wind(&self) -> &VCell {
+        &self.wind
+    }
+
+    pub fn handlers(&self) -> &VCell {
+        &self.handlers
+    }
 }
 
 impl Vm {
@@ -35,6 +49,8 @@ impl Vm {
             ep: self.ep,
             ip: self.ip,
             bp: self.bp,
+            wind: self.wind.clone(),
+            handlers: self.handlers.clone(),
         }
     }
 
@@ -43,6 +59,180 @@ impl Vm {
         self.ep = cont.ep();
         self.ip = *cont.ip();
         self.bp = cont.bp();
+        self.wind = cont.wind().clone();
+        self.handlers = cont.handlers().clone();
         self.acc = VCell::Undefined;
     }
+
+    /// Build Dynamic Wind
+    ///
+    /// Build a procedure of no arguments that implements dynamic-wind for the
+    /// given before, thunk and after procedures. The procedure applies before,
+    /// pushes a (before . after) frame on to the wind list, applies thunk, pops
+    /// the frame, applies after and finally evaluates to the result of thunk.
+    ///
+    /// # Arguments
+    /// `before` - The procedure applied before entering thunk
+    /// `thunk` - The procedure whose result dynamic-wind evaluates to
+    /// `after` - The procedure applied after leaving thunk
+    pub fn build_dynamic_wind(
+        &mut self,
+        before: VCell,
+        thunk: VCell,
+        after: VCell,
+    ) -> Result<VCell, Error> {
+        let before = self.heap.put(before);
+        let thunk = self.heap.put(thunk);
+        let after = self.heap.put(after);
+        let frame = self
+            .heap
+            .put(VCell::pair(before.as_ptr()?, after.as_ptr()?));
+        let outer = self.wind.clone();
+        let outer_ptr = self.heap.put(outer.clone()).as_ptr()?;
+        let inner = self.heap.put(VCell::pair(frame.as_ptr()?, outer_ptr));
+        let set_wind = self.heap.put(VCell::builtin("dynamic-wind", set_wind));
+        let result = self.heap.put(VCell::builtin("dynamic-wind", result));
+
+        let mut lambda = Lambda::new(vec![]);
+        lambda.emit(OpCode::Enter);
+        lambda.emit_call(before, &[]);
+        lambda.emit_call(set_wind.clone(), &[inner]);
+        lambda.emit_call(thunk, &[]);
+        lambda.emit(OpCode::PushAcc);
+        lambda.emit_call(set_wind, &[outer]);
+        lambda.emit_call(after, &[]);
+        lambda.emit(OpCode::PushImmediate);
+        lambda.emit(VCell::ArgumentCount(1));
+        lambda.emit(OpCode::MovImmediate);
+        lambda.emit(result);
+        lambda.emit(VCell::Acc);
+        lambda.emit(OpCode::CallAcc);
+        lambda.emit(OpCode::Ret);
+        Ok(self.heap.put(lambda))
+    }
+
+    /// Build Rewind
+    ///
+    /// Build a procedure of no arguments that travels from the current wind list
+    /// to the wind list the continuation was captured with, and then applies the
+    /// continuation to value.
+    ///
+    /// The after procedure of each frame being left is applied innermost first,
+    /// followed by the before procedure of each frame being entered outermost
+    /// first. Each is applied with the wind list set to the frames outside of
+    /// it, so that escaping from a before or after procedure does not apply it
+    /// a second time.
+    ///
+    /// # Arguments
+    /// `cont` - A pointer to the continuation being applied
+    /// `target` - The wind list the continuation was captured with
+    /// `value` - The value to apply the continuation to
+    pub fn build_rewind(
+        &mut self,
+        cont: VCell,
+        target: VCell,
+        value: VCell,
+    ) -> Result<VCell, Error> {
+        let common = self.common_wind(&self.wind, &target)?;
+        let set_wind = self.heap.put(VCell::builtin("dynamic-wind", set_wind));
+
+        let mut lambda = Lambda::new(vec![]);
+        lambda.emit(OpCode::Enter);
+
+        let mut rest = self.wind.clone();
+        while rest != common {
+            let (frame, outer) = self.wind_frame(&rest)?;
+            lambda.emit_call(set_wind.clone(), std::slice::from_ref(&outer));
+            lambda.emit_call(frame.1, &[]);
+            rest = outer;
+        }
+
+        let mut entering = vec![];
+        let mut rest = target.clone();
+        while rest != common {
+            let (frame, outer) = self.wind_frame(&rest)?;
+            entering.push((frame, outer.clone()));
+            rest = outer;
+        }
+        for (frame, outer) in entering.into_iter().rev() {
+            lambda.emit_call(set_wind.clone(), &[outer]);
+            lambda.emit_call(frame.0, &[]);
+        }
+
+        lambda.emit_call(set_wind, &[target]);
+        lambda.emit(OpCode::PushImmediate);
+        lambda.emit(value);
+        lambda.emit(OpCode::PushImmediate);
+        lambda.emit(VCell::ArgumentCount(1));
+        lambda.emit(OpCode::MovImmediate);
+        lambda.emit(cont);
+        lambda.emit(VCell::Acc);
+        lambda.emit(OpCode::TCallAcc);
+        Ok(self.heap.put(lambda))
+    }
+
+    /// Wind Frame
+    ///
+    /// Given a non-empty wind list, return the ((before, after), rest) of
+    /// the innermost frame. An empty rest is returned as VCell::Nil so that
+    /// wind lists may be compared by identity.
+    fn wind_frame(&self, wind: &VCell) -> Result<((VCell, VCell), VCell), Error> {
+        let list = self.heap.get(wind);
+        let frame = self.heap.get(&list.as_car()?);
+        let rest = match self.heap.get(&list.as_cdr()?) {
+            VCell::Nil => VCell::Nil,
+            _ => list.as_cdr()?,
+        };
+        Ok(((frame.as_car()?, frame.as_cdr()?), rest))
+    }
+
+    /// Common Wind
+    ///
+    /// Return the longest common tail of the two wind lists. Frames are
+    /// compared by identity.
+    fn common_wind(&self, lhs: &VCell, rhs: &VCell) -> Result<VCell, Error> {
+        let mut lhs = self.wind_tails(lhs)?;
+        let mut rhs = self.wind_tails(rhs)?;
+        let mut common = VCell::Nil;
+        while let (Some(l), Some(r)) = (lhs.pop(), rhs.pop()) {
+            if l != r {
+                break;
+            }
+            common = l;
+        }
+        Ok(common)
+    }
+
+    /// Wind Tails
+    ///
+    /// Return a vector of every non-empty tail of the wind list, starting
+    /// with the list itself.
+    fn wind_tails(&self, wind: &VCell) -> Result<Vec<VCell>, Error> {
+        let mut tails = vec![];
+        let mut rest = wind.clone();
+        while !rest.is_nil() {
+            tails.push(rest.clone());
+            rest = self.wind_frame(&rest)?.1;
+        }
+        Ok(tails)
+    }
+}
+
+/// Set Wind
+///
+/// Set the wind list to the single argument. Procedures built by
+/// dynamic-wind apply this builtin as they enter and leave frames.
+fn set_wind(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "dynamic-wind")?;
+    vm.wind = vm.stack.pop()?.clone();
+    Ok(VCell::Void)
+}
+
+/// Result
+///
+/// Evaluate to the single argument. This is used by dynamic-wind to
+/// restore the result of thunk that was saved on the stack.
+pub fn result(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "dynamic-wind")?;
+    Ok(vm.stack.pop()?.clone())
 }
@@ -1,10 +1,12 @@
-use crate::cell::Cell;
+use crate::cell::{unalias, Cell};
 use crate::error::Error;
 use crate::error::Error::InvalidSyntax;
+use crate::vm::compile::NAMED_LET;
 use crate::vm::lambda::Lambda;
 use crate::vm::vcell::VCell;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::rc::{Rc, Weak};
 
 /// Environment
 ///
@@ -196,39 +198,294 @@ impl LexicalEnvironment {
     }
 }
 
+/// Environment
+///
+/// Environment is a first class environment, a set of deep bindings of
+/// symbol ptr -> slot in the global environment. Expressions are compiled
+/// against a single environment, which is the interaction environment
+/// unless an environment is given to eval.
+///
+/// An environment created with GlobalEnvironment::copy binds each symbol to
+/// a new slot, so that definitions and assignments made in one environment
+/// aren't visible in the other.
+///
+/// A binding imported from a library shares the library's slot. Defining
+/// an imported symbol binds it to a new slot instead.
+///
+/// A derived environment is one made by a procedure such as copy-environment
+/// or environment, rather than the interaction environment or that of a
+/// library. The slots created for a derived environment are reclaimed by the
+/// gc once the environment is dropped and no code refers to them, and
+/// expressions evaluated in a derived environment can't import or define
+/// libraries.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Environment {
+    bindings: RefCell<HashMap<usize, usize>>,
+    imports: RefCell<HashSet<usize>>,
+    derived: bool,
+}
+
+impl Environment {
+    pub fn new() -> Environment {
+        Environment {
+            bindings: RefCell::new(HashMap::new()),
+            imports: RefCell::new(HashSet::new()),
+            derived: false,
+        }
+    }
+
+    /// Derived
+    ///
+    /// Return a new derived environment, which owns the slots created for
+    /// it.
+    pub fn derived() -> Environment {
+        Environment {
+            derived: true,
+            ..Environment::new()
+        }
+    }
+
+    /// Is Derived
+    ///
+    /// Return true if this environment owns the slots created for it.
+    pub fn is_derived(&self) -> bool {
+        self.derived
+    }
+
+    /// Get
+    ///
+    /// Return the slot sym is bound to in this environment, if any.
+    ///
+    /// # Arguments
+    /// `sym` - The symbol to look up
+    pub fn get(&self, sym: usize) -> Option<usize> {
+        self.bindings.borrow().get(&sym).copied()
+    }
+
+    /// Bind
+    ///
+    /// Bind sym to slot in this environment.
+    ///
+    /// # Arguments
+    /// `sym` - The symbol to bind
+    /// `slot` - The global environment slot to bind sym to
+    pub fn bind(&self, sym: usize, slot: usize) {
+        self.bindings.borrow_mut().insert(sym, slot);
+        self.imports.borrow_mut().remove(&sym);
+    }
+
+    /// Import
+    ///
+    /// Bind sym to slot in this environment, where slot is the binding of
+    /// a library.
+    ///
+    /// # Arguments
+    /// `sym` - The symbol to bind
+    /// `slot` - The global environment slot to bind sym to
+    pub fn import(&self, sym: usize, slot: usize) {
+        self.bindings.borrow_mut().insert(sym, slot);
+        self.imports.borrow_mut().insert(sym);
+    }
+
+    /// Is Imported
+    ///
+    /// Return true if sym is bound to a binding imported from a library.
+    ///
+    /// # Arguments
+    /// `sym` - The symbol to look up
+    pub fn is_imported(&self, sym: usize) -> bool {
+        self.imports.borrow().contains(&sym)
+    }
+
+    /// Symbols
+    ///
+    /// Return each symbol bound in this environment.
+    pub fn symbols(&self) -> Vec<usize> {
+        self.bindings.borrow().keys().copied().collect()
+    }
+}
+
+/// The minimum number of slots owned by derived environments that triggers
+/// a gc.
+const OWNED_SLOTS_GC_THRESHOLD: usize = 65536;
+
 /// Global Environment
 ///
 /// GlobalEnvironment represents a binding of a symbol to a value in the heap.
 /// The environment tracks both deep bindings (sym -> slot), and also a
 /// vector of shallow bindings (slot -> vcell).
-#[derive(Debug, PartialEq, Eq)]
+///
+/// The slots are shared by every first class Environment, while the deep
+/// bindings used at compile time are those of the current environment.
+#[derive(Debug)]
 pub struct GlobalEnvironment {
-    /// Deep bindings is a map of symbol ptr -> slot, and
-    /// is used by the compiler to aassociate a symbol at compilation
-    /// time with the environment slot.
-    bindings: HashMap<usize, usize>,
+    /// The environment expressions are compiled against. The compiler uses
+    /// its deep bindings to aassociate a symbol at compilation time with
+    /// the environment slot.
+    environment: Rc<Environment>,
+
+    /// The interaction environment, which is the current environment
+    /// except while compiling an expression given to eval along with
+    /// another environment.
+    interaction: Rc<Environment>,
+
+    /// The bindings of the interaction environment once the builtins and
+    /// prelude are loaded, which scheme-report-environment is copied from.
+    report: Rc<Environment>,
 
     /// Environment slots. The compiler produces shallow bindings as
     /// ptr into this vector at compile time.
     slots: Vec<VCell>,
+
+    /// The symbol each slot was created for, which is used for reverse
+    /// lookups of slot -> symbol.
+    symbols: Vec<usize>,
+
+    /// Symbols bound by an import to a slot created for another symbol,
+    /// such as those renamed or prefixed by the import set.
+    imported_symbols: HashSet<usize>,
+
+    /// The derived environment each of its slots was created for. Once the
+    /// environment is dropped, its slots are released to the gc.
+    owners: HashMap<usize, Weak<Environment>>,
+
+    /// Slots reclaimed by the gc, which are reused by new bindings.
+    free_slots: Vec<usize>,
+
+    /// The number of slots owned by derived environments that triggers a
+    /// gc, regardless of heap utilization.
+    gc_threshold: usize,
 }
 
 impl GlobalEnvironment {
     pub fn new() -> GlobalEnvironment {
+        let interaction = Rc::new(Environment::new());
         GlobalEnvironment {
-            bindings: HashMap::new(),
+            environment: interaction.clone(),
+            interaction,
+            report: Rc::new(Environment::new()),
             slots: vec![],
+            symbols: vec![],
+            imported_symbols: HashSet::new(),
+            owners: HashMap::new(),
+            free_slots: vec![],
+            gc_threshold: OWNED_SLOTS_GC_THRESHOLD,
         }
     }
 
-    pub fn iter_bindings(&self) -> std::collections::hash_map::Keys<usize, usize> {
-        self.bindings.keys()
+    /// Iter Symbols
+    ///
+    /// Iterate over the symbol of each slot along with each imported
+    /// symbol, which includes every symbol bound by any environment.
+    pub fn iter_symbols(&self) -> impl Iterator<Item = &usize> {
+        self.symbols.iter().chain(self.imported_symbols.iter())
     }
 
     pub fn iter_slots(&self) -> std::slice::Iter<VCell> {
         self.slots.iter()
     }
 
+    /// Released Slots
+    ///
+    /// Return the slots created for derived environments that have since
+    /// been dropped. These are only live while code refers to them.
+    pub fn released_slots(&self) -> HashSet<usize> {
+        self.owners
+            .iter()
+            .filter(|(_, owner)| owner.strong_count() == 0)
+            .map(|(slot, _)| *slot)
+            .collect()
+    }
+
+    /// Is GC Pending
+    ///
+    /// Return true if enough slots have been created for derived
+    /// environments since the last gc that it should run to reclaim them.
+    pub fn is_gc_pending(&self) -> bool {
+        self.owners.len() > self.gc_threshold
+    }
+
+    /// Reset GC Threshold
+    ///
+    /// Called after a gc, which sets the threshold relative to the number of
+    /// slots owned by environments that are still alive.
+    pub fn reset_gc_threshold(&mut self) {
+        let owned = self
+            .owners
+            .values()
+            .filter(|owner| owner.strong_count() > 0)
+            .count();
+        self.gc_threshold = OWNED_SLOTS_GC_THRESHOLD.max(2 * owned) + self.owners.len() - owned;
+    }
+
+    /// Free Slot
+    ///
+    /// Reclaim a released slot, so that it may be reused by a new binding.
+    ///
+    /// # Arguments
+    /// `slot` - The slot to free
+    pub fn free_slot(&mut self, slot: usize) {
+        self.slots[slot] = VCell::undefined();
+        self.owners.remove(&slot);
+        self.free_slots.push(slot);
+    }
+
+    /// Interaction Environment
+    ///
+    /// Return the interaction environment.
+    pub fn interaction_environment(&self) -> Rc<Environment> {
+        self.interaction.clone()
+    }
+
+    /// Set Interaction Environment
+    ///
+    /// Replace the interaction environment, which also becomes the
+    /// environment expressions are compiled against.
+    ///
+    /// # Arguments
+    /// `environment` - The new interaction environment
+    pub fn set_interaction_environment(&mut self, environment: Rc<Environment>) {
+        self.environment = environment.clone();
+        self.interaction = environment;
+    }
+
+    /// Environment
+    ///
+    /// Return the environment expressions are currently compiled against.
+    pub fn environment(&self) -> Rc<Environment> {
+        self.environment.clone()
+    }
+
+    /// Report Environment
+    ///
+    /// Return the environment holding the standard bindings.
+    pub fn report_environment(&self) -> Rc<Environment> {
+        self.report.clone()
+    }
+
+    /// Save Report Environment
+    ///
+    /// Save a copy of the interaction environment as the environment
+    /// holding the standard bindings, so that later definitions in the
+    /// interaction environment don't affect it.
+    pub fn save_report_environment(&mut self) {
+        let interaction = self.interaction.clone();
+        let report = Rc::new(Environment::new());
+        self.copy(&interaction, None, &report).unwrap();
+        self.report = report;
+    }
+
+    /// Set Environment
+    ///
+    /// Set the environment that expressions are compiled against, returning
+    /// the previous environment.
+    ///
+    /// # Arguments
+    /// `environment` - The environment to compile against
+    pub fn set_environment(&mut self, environment: Rc<Environment>) -> Rc<Environment> {
+        std::mem::replace(&mut self.environment, environment)
+    }
+
     /// Get binding
     ///
     /// Get binding provides a deep binding lookup of sym -> slot. If the
@@ -239,17 +496,64 @@ impl GlobalEnvironment {
     /// `sym` - The symbol to provide a binding for
     pub fn get_binding<T: Into<usize>>(&mut self, sym: T) -> usize {
         let sym: usize = sym.into();
-        match self.bindings.get(&sym) {
-            Some(slot) => *slot,
+        match self.environment.get(sym) {
+            Some(slot) => slot,
             None => {
-                self.slots.push(VCell::undefined());
-                let slot = self.slots.len() - 1;
-                self.bindings.insert(sym, slot);
+                let environment = self.environment.clone();
+                let slot = self.new_slot(sym, VCell::undefined(), &environment);
+                environment.bind(sym, slot);
                 slot
             }
         }
     }
 
+    /// Get Definition
+    ///
+    /// Get the binding a definition of sym stores to. This is the binding
+    /// returned by get_binding(), unless sym was imported, in which case
+    /// sym is bound to a new slot so that the definition shadows the
+    /// import rather than assigning to the library's binding.
+    ///
+    /// # Arguments
+    /// `sym` - The symbol being defined
+    pub fn get_definition<T: Into<usize>>(&mut self, sym: T) -> usize {
+        let sym: usize = sym.into();
+        if self.environment.is_imported(sym) {
+            let environment = self.environment.clone();
+            let slot = self.new_slot(sym, VCell::undefined(), &environment);
+            environment.bind(sym, slot);
+            return slot;
+        }
+        self.get_binding(sym)
+    }
+
+    /// Is Imported
+    ///
+    /// Return true if sym is bound to a binding imported from a library in
+    /// the current environment.
+    ///
+    /// # Arguments
+    /// `sym` - The symbol to look up
+    pub fn is_imported<T: Into<usize>>(&self, sym: T) -> bool {
+        self.environment.is_imported(sym.into())
+    }
+
+    /// Import
+    ///
+    /// Bind sym to slot in environment, where slot is the binding of a
+    /// library.
+    ///
+    /// # Arguments
+    /// `environment` - The environment to bind sym in
+    /// `sym` - The symbol to bind
+    /// `slot` - The library's binding
+    pub fn import(&mut self, environment: &Environment, sym: usize, slot: usize) {
+        if self.get_symbol(slot) != Some(sym) {
+            self.imported_symbols.insert(sym);
+        }
+        environment.import(sym, slot);
+    }
+
     /// Get
     ///
     /// Given a symbol symbol reference, return the object bound
@@ -260,7 +564,7 @@ impl GlobalEnvironment {
     /// `sym` - The symbol to provide a binding for
     pub fn get<T: Into<usize>>(&mut self, sym: T) -> Option<VCell> {
         let sym: usize = sym.into();
-        self.bindings.get(&sym).map(|slot| self.get_slot(*slot))
+        self.environment.get(sym).map(|slot| self.get_slot(slot))
     }
 
     /// Get Symbol
@@ -271,11 +575,7 @@ impl GlobalEnvironment {
     /// # Arguments
     /// `slot` - The slot to find the symbol for.
     pub fn get_symbol<T: Into<usize>>(&self, slot: T) -> Option<usize> {
-        let slot = slot.into();
-        self.bindings
-            .iter()
-            .find(|it| *(it.1) == slot)
-            .map(|it| *it.0)
+        self.symbols.get(slot.into()).copied()
     }
 
     /// Get slot
@@ -313,6 +613,64 @@ impl GlobalEnvironment {
     pub fn put_slot(&mut self, slot: usize, vcell: VCell) {
         *self.slots.get_mut(slot).expect("invalid environment slot") = vcell;
     }
+
+    /// Copy
+    ///
+    /// Bind each of the given symbols bound in environment to a new slot in
+    /// copy, holding the value currently bound to the symbol. If symbols is
+    /// None, every symbol bound in environment is copied.
+    ///
+    /// A symbol that isn't bound in environment is returned as the error.
+    ///
+    /// # Arguments
+    /// `environment` - The environment to copy bindings from
+    /// `symbols` - The symbols to copy, or None to copy every binding
+    /// `copy` - The environment to bind the copies in
+    pub fn copy(
+        &mut self,
+        environment: &Environment,
+        symbols: Option<&[usize]>,
+        copy: &Rc<Environment>,
+    ) -> Result<(), usize> {
+        let (symbols, required) = match symbols {
+            Some(symbols) => (symbols.to_vec(), true),
+            None => (environment.symbols(), false),
+        };
+        for sym in symbols {
+            match environment.get(sym).map(|slot| self.get_slot(slot)) {
+                Some(vcell) if !vcell.is_undefined() => {
+                    let slot = self.new_slot(sym, vcell, copy);
+                    copy.bind(sym, slot);
+                }
+                _ if required => return Err(sym),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// New Slot
+    ///
+    /// Create a slot for sym in environment holding vcell, reusing a slot
+    /// reclaimed by the gc if there is one.
+    fn new_slot(&mut self, sym: usize, vcell: VCell, environment: &Rc<Environment>) -> usize {
+        let slot = match self.free_slots.pop() {
+            Some(slot) => {
+                self.slots[slot] = vcell;
+                self.symbols[slot] = sym;
+                slot
+            }
+            None => {
+                self.slots.push(vcell);
+                self.symbols.push(sym);
+                self.slots.len() - 1
+            }
+        };
+        if environment.is_derived() {
+            self.owners.insert(slot, Rc::downgrade(environment));
+        }
+        slot
+    }
 }
 
 impl Default for GlobalEnvironment {
@@ -395,7 +753,7 @@ fn find_free_symbols_in_proc<'a>(
     }
 
     let mut rest = match car {
-        Cell::Symbol(sym) => match sym.as_str() {
+        Cell::Symbol(sym) => match unalias(sym) {
             "define" => {
                 let sym_or_args = cdr
                     .car()
@@ -412,6 +770,61 @@ fn find_free_symbols_in_proc<'a>(
                 cdr.cdr()
                     .ok_or_else(|| Error::InvalidNumArgs("define".into()))?
             }
+            "define-values" => cdr
+                .cdr()
+                .ok_or_else(|| Error::InvalidNumArgs("define-values".into()))?,
+            "define-record-type" | "define-syntax" => {
+                return Ok(());
+            }
+            "let-syntax" | "letrec-syntax" => cdr
+                .cdr()
+                .ok_or_else(|| Error::InvalidNumArgs(unalias(sym).into()))?,
+            "case-lambda" => {
+                // Each clause binds its own formals, which must not shadow
+                // the free symbols of the other clauses.
+                for clause in cdr {
+                    let mut env = env.clone();
+                    let mut args = clause
+                        .car()
+                        .ok_or_else(|| Error::InvalidNumArgs("case-lambda".into()))?;
+                    while args.is_pair() {
+                        env.insert(args.car().unwrap());
+                        args = args.cdr().unwrap();
+                    }
+                    if args.is_symbol() {
+                        env.insert(args);
+                    }
+                    for expr in clause.cdr().unwrap() {
+                        find_free_symbols(expr, &mut env, free)?;
+                    }
+                }
+                return Ok(());
+            }
+            NAMED_LET => {
+                // The inits are evaluated outside of the loop, and the body
+                // is evaluated with the tag and loop variables bound.
+                let tag = cdr
+                    .car()
+                    .ok_or_else(|| Error::InvalidNumArgs(NAMED_LET.into()))?;
+                let rest = cdr.cdr().unwrap();
+                let bindings = rest
+                    .car()
+                    .ok_or_else(|| Error::InvalidNumArgs(NAMED_LET.into()))?;
+                let mut body_env = env.clone();
+                body_env.insert(tag);
+                for binding in bindings {
+                    let (var, init) = match binding.collect_vec().as_slice() {
+                        [var, init] => (*var, *init),
+                        _ => return Err(InvalidSyntax(format!("{}: {}", NAMED_LET, binding))),
+                    };
+                    find_free_symbols(init, env, free)?;
+                    body_env.insert(var);
+                }
+                for expr in rest.cdr().unwrap() {
+                    find_free_symbols(expr, &mut body_env, free)?;
+                }
+                return Ok(());
+            }
             "lambda" => {
                 let mut args = cdr
                     .car()
@@ -468,7 +881,50 @@ pub fn internally_defined_symbols(body: &Cell) -> Result<HashSet<&Cell>, Error>
     let mut beginning_of_body = true;
 
     for expr in body {
-        if expr.is_pair() && expr.car().unwrap().is_define() {
+        if expr.is_pair() && expr.car().unwrap().is_define_values() {
+            if !beginning_of_body {
+                return Err(InvalidSyntax(format!("out of context define: {:#}", expr)));
+            }
+            let mut formals = expr.cadr().unwrap_or(&Cell::Nil);
+            while let Cell::Pair(variable, rest) = formals {
+                if variable.is_symbol() {
+                    symbols.insert(variable.as_ref());
+                }
+                formals = rest;
+            }
+            if formals.is_symbol() {
+                symbols.insert(formals);
+            }
+            continue;
+        } else if expr.is_pair() && expr.car().unwrap().is_define_record_type() {
+            if !beginning_of_body {
+                return Err(InvalidSyntax(format!("out of context define: {:#}", expr)));
+            }
+            let mut parts = expr.cdr().unwrap().iter();
+            let type_name = parts.next();
+            let constructor = parts.next().and_then(|it| it.car());
+            let predicate = parts.next();
+            for it in [type_name, constructor, predicate].into_iter().flatten() {
+                if it.is_symbol() {
+                    symbols.insert(it);
+                }
+            }
+            for field in parts {
+                if field.is_pair() {
+                    for it in field.iter().skip(1) {
+                        if it.is_symbol() {
+                            symbols.insert(it);
+                        }
+                    }
+                }
+            }
+            continue;
+        } else if expr.is_pair() && expr.car().unwrap().is_define_syntax() {
+            if !beginning_of_body {
+                return Err(InvalidSyntax(format!("out of context define: {:#}", expr)));
+            }
+            continue;
+        } else if expr.is_pair() && expr.car().unwrap().is_define() {
             if !beginning_of_body {
                 return Err(InvalidSyntax(format!("out of context define: {:#}", expr)));
             }
@@ -508,6 +964,22 @@ mod tests {
         env.put_slot(0, VCell::undefined());
     }
 
+    #[test]
+    fn derived_slots_are_released() {
+        let mut env = GlobalEnvironment::new();
+        let derived = Rc::new(Environment::derived());
+        env.set_environment(derived.clone());
+        let slot = env.get_binding(50_usize);
+        assert!(env.released_slots().is_empty());
+        env.set_environment(Rc::new(Environment::new()));
+        drop(derived);
+        assert_eq!(env.released_slots(), HashSet::from([slot]));
+        env.free_slot(slot);
+        assert_eq!(env.get_binding(100_usize), slot);
+        assert_eq!(env.get_symbol(slot), Some(100_usize));
+        assert!(env.released_slots().is_empty());
+    }
+
     #[test]
     fn primitive_symbol_check() {
         assert!(cell!["lambda"].is_primitive_symbol());
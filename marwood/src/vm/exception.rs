@@ -0,0 +1,179 @@
+use crate::error::Error;
+use crate::error::Error::UncaughtException;
+use crate::vm::builtin::pop_argc;
+use crate::vm::continuation::result;
+use crate::vm::lambda::Lambda;
+use crate::vm::opcode::OpCode;
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+use std::rc::Rc;
+
+impl Vm {
+    /// Build With Exception Handler
+    ///
+    /// Build a procedure of no arguments that implements with-exception-handler
+    /// for the given handler and thunk. The procedure pushes handler on to the
+    /// handler stack, applies thunk, pops the handler and finally evaluates to
+    /// the result of thunk.
+    ///
+    /// # Arguments
+    /// `handler` - The procedure installed as the current exception handler
+    /// `thunk` - The procedure whose result with-exception-handler evaluates to
+    pub fn build_with_exception_handler(
+        &mut self,
+        handler: VCell,
+        thunk: VCell,
+    ) -> Result<VCell, Error> {
+        let handler = self.heap.put(handler);
+        let thunk = self.heap.put(thunk);
+        let outer = self.handlers.clone();
+        let outer_ptr = self.heap.put(outer.clone()).as_ptr()?;
+        let inner = self.heap.put(VCell::pair(handler.as_ptr()?, outer_ptr));
+        let set_handlers = self
+            .heap
+            .put(VCell::builtin("with-exception-handler", set_handlers));
+        let result = self
+            .heap
+            .put(VCell::builtin("with-exception-handler", result));
+
+        let mut lambda = Lambda::new(vec![]);
+        lambda.emit(OpCode::Enter);
+        lambda.emit_call(set_handlers.clone(), &[inner]);
+        lambda.emit_call(thunk, &[]);
+        lambda.emit(OpCode::PushAcc);
+        lambda.emit_call(set_handlers, &[outer]);
+        lambda.emit(OpCode::PushImmediate);
+        lambda.emit(VCell::ArgumentCount(1));
+        lambda.emit(OpCode::MovImmediate);
+        lambda.emit(result);
+        lambda.emit(VCell::Acc);
+        lambda.emit(OpCode::CallAcc);
+        lambda.emit(OpCode::Ret);
+        Ok(self.heap.put(lambda))
+    }
+
+    /// Build Raise
+    ///
+    /// Build a procedure of no arguments that raises obj by applying the
+    /// current exception handler to it. The handler is applied with the
+    /// handler stack set to the handlers outside of it.
+    ///
+    /// If continuable is true, the procedure evaluates to the result of the
+    /// handler after restoring the handler stack. Otherwise obj is raised
+    /// again to the outer handler if the handler returns.
+    ///
+    /// An error is returned if there is no current exception handler.
+    ///
+    /// # Arguments
+    /// `obj` - The object being raised
+    /// `continuable` - True if the handler may return to the raise
+    pub fn build_raise(&mut self, obj: VCell, continuable: bool) -> Result<VCell, Error> {
+        let (handler, outer) = match self.heap.get(&self.handlers) {
+            VCell::Pair(handler, outer) => match self.heap.get(VCell::ptr(outer)) {
+                VCell::Nil => (VCell::ptr(handler), VCell::Nil),
+                _ => (VCell::ptr(handler), VCell::ptr(outer)),
+            },
+            _ => {
+                return Err(self.uncaught_exception(&obj));
+            }
+        };
+        let current = self.handlers.clone();
+        let obj = self.heap.put(obj);
+        let set_handlers = self.heap.put(VCell::builtin("raise", set_handlers));
+
+        let mut lambda = Lambda::new(vec![]);
+        lambda.emit(OpCode::Enter);
+        lambda.emit_call(set_handlers.clone(), &[outer]);
+        lambda.emit_call(handler, std::slice::from_ref(&obj));
+        if continuable {
+            lambda.emit(OpCode::PushAcc);
+            lambda.emit_call(set_handlers, &[current]);
+            lambda.emit(OpCode::PushImmediate);
+            lambda.emit(VCell::ArgumentCount(1));
+            lambda.emit(OpCode::MovImmediate);
+            lambda.emit(self.heap.put(VCell::builtin("raise-continuable", result)));
+            lambda.emit(VCell::Acc);
+            lambda.emit(OpCode::CallAcc);
+        } else {
+            lambda.emit_call(self.heap.put(VCell::builtin("raise", reraise)), &[obj]);
+        }
+        lambda.emit(OpCode::Ret);
+        Ok(self.heap.put(lambda))
+    }
+
+    /// Raise
+    ///
+    /// Raise obj to the current exception handler. The procedure built by
+    /// build_raise is returned to be placed in %acc, and %ip is decremented
+    /// so that the CALL or TCALL that applied the calling builtin next
+    /// applies it.
+    ///
+    /// # Arguments
+    /// `obj` - The object being raised
+    /// `continuable` - True if the handler may return to the raise
+    pub fn raise(&mut self, obj: VCell, continuable: bool) -> Result<VCell, Error> {
+        let proc = self.build_raise(obj, continuable)?;
+        self.stack.push(VCell::ArgumentCount(0));
+        self.ip.1 -= 1;
+        Ok(proc)
+    }
+
+    /// Raise Error
+    ///
+    /// Raise a runtime error as a condition to the current exception handler.
+    /// Because the error may have occurred at any point during an instruction,
+    /// the procedure built by build_raise is applied directly by setting up its
+    /// call frame and jumping to it. The handler must not return, so the
+    /// frame's return address is never used.
+    ///
+    /// The error is returned if there is no current exception handler.
+    ///
+    /// # Arguments
+    /// `error` - The runtime error to raise
+    pub fn raise_error(&mut self, error: Error) -> Result<(), Error> {
+        let condition = VCell::Condition(Rc::new(self.error_to_condition(error)));
+        let proc = self.build_raise(condition, false)?;
+        self.stack.push(VCell::ArgumentCount(0));
+        self.stack.push(VCell::EnvironmentPointer(self.ep));
+        self.stack
+            .push(VCell::InstructionPointer(self.ip.0, self.ip.1));
+        self.ip = (proc.as_ptr()?, 0);
+        self.acc = proc;
+        Ok(())
+    }
+
+    /// Uncaught Exception
+    ///
+    /// Return the error for an object raised without an exception handler.
+    /// A condition results in the runtime error it was raised for.
+    ///
+    /// # Arguments
+    /// `obj` - The object that was raised
+    pub fn uncaught_exception(&self, obj: &VCell) -> Error {
+        match self.heap.get(obj) {
+            VCell::Condition(condition) => condition.error().clone(),
+            obj => UncaughtException(self.heap.get_as_cell(&obj)),
+        }
+    }
+}
+
+/// Set Handlers
+///
+/// Set the exception handler stack to the single argument. Procedures
+/// built by with-exception-handler and raise apply this builtin as they
+/// install and remove handlers.
+fn set_handlers(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "with-exception-handler")?;
+    vm.handlers = vm.stack.pop()?.clone();
+    Ok(VCell::Void)
+}
+
+/// Reraise
+///
+/// Raise the single argument again after the handler it was raised to
+/// returned from a non-continuable raise.
+fn reraise(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "raise")?;
+    let obj = vm.stack.pop()?.clone();
+    vm.raise(obj, false)
+}
@@ -0,0 +1,255 @@
+use crate::error::Error;
+use crate::error::Error::FileError;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::rc::Rc;
+
+/// Filesystem
+///
+/// Filesystem is the capability used by file ports and the file procedures
+/// (e.g. open-input-file, delete-file) to access files. It's supplied to the
+/// VM by the SystemInterface, which allows an embedder to choose between the
+/// real filesystem, an in-memory filesystem, or no filesystem at all.
+pub trait Filesystem: Debug {
+    /// Read the entire contents of the file at path
+    fn read_file(&self, path: &str) -> Result<String, Error>;
+
+    /// Create the file at path, or truncate it if it already exists
+    fn create_file(&self, path: &str) -> Result<(), Error>;
+
+    /// Append text to the end of the file at path
+    fn append_file(&self, path: &str, text: &str) -> Result<(), Error>;
+
+    /// Create the file at path, or truncate it if it already exists, and
+    /// return a handle that writes to it
+    fn open_output_file(&self, path: &str) -> Result<Box<dyn Write>, Error>;
+
+    /// Return true if a file exists at path
+    fn file_exists(&self, path: &str) -> bool;
+
+    /// Delete the file at path
+    fn delete_file(&self, path: &str) -> Result<(), Error>;
+}
+
+/// Real Filesystem
+///
+/// A filesystem backed by the host operating system's filesystem
+#[derive(Debug, Default)]
+pub struct RealFilesystem {}
+
+impl RealFilesystem {
+    pub fn new() -> RealFilesystem {
+        RealFilesystem {}
+    }
+}
+
+/// File Error From IO
+///
+/// Map an io error encountered accessing the file at path to a FileError
+///
+/// # Arguments
+/// `path` - The path of the file being accessed
+/// `error` - The io error
+pub(crate) fn file_error_from_io(path: &str, error: std::io::Error) -> Error {
+    let reason = match error.kind() {
+        ErrorKind::NotFound => "file not found".to_string(),
+        ErrorKind::PermissionDenied => "permission denied".to_string(),
+        _ => error.to_string(),
+    };
+    FileError(path.into(), reason)
+}
+
+impl Filesystem for RealFilesystem {
+    fn read_file(&self, path: &str) -> Result<String, Error> {
+        std::fs::read_to_string(path).map_err(|e| file_error_from_io(path, e))
+    }
+
+    fn create_file(&self, path: &str) -> Result<(), Error> {
+        std::fs::File::create(path)
+            .map(|_| ())
+            .map_err(|e| file_error_from_io(path, e))
+    }
+
+    fn append_file(&self, path: &str, text: &str) -> Result<(), Error> {
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(text.as_bytes()))
+            .map_err(|e| file_error_from_io(path, e))
+    }
+
+    fn open_output_file(&self, path: &str) -> Result<Box<dyn Write>, Error> {
+        match std::fs::File::create(path) {
+            Ok(file) => Ok(Box::new(file)),
+            Err(e) => Err(file_error_from_io(path, e)),
+        }
+    }
+
+    fn file_exists(&self, path: &str) -> bool {
+        std::path::Path::new(path).exists()
+    }
+
+    fn delete_file(&self, path: &str) -> Result<(), Error> {
+        std::fs::remove_file(path).map_err(|e| file_error_from_io(path, e))
+    }
+}
+
+/// Memory Filesystem
+///
+/// A virtual filesystem that holds the contents of each file in memory,
+/// keyed by the file's path.
+#[derive(Debug, Default)]
+pub struct MemoryFilesystem {
+    files: Rc<RefCell<HashMap<String, String>>>,
+}
+
+/// Memory File
+///
+/// A handle that appends to a file of a memory filesystem. Anything written
+/// after the file is deleted is discarded.
+struct MemoryFile {
+    files: Rc<RefCell<HashMap<String, String>>>,
+    path: String,
+}
+
+impl Write for MemoryFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(file) = self.files.borrow_mut().get_mut(&self.path) {
+            file.push_str(&String::from_utf8_lossy(buf));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl MemoryFilesystem {
+    pub fn new() -> MemoryFilesystem {
+        MemoryFilesystem::default()
+    }
+}
+
+impl Filesystem for MemoryFilesystem {
+    fn read_file(&self, path: &str) -> Result<String, Error> {
+        match self.files.borrow().get(path) {
+            Some(text) => Ok(text.clone()),
+            None => Err(FileError(path.into(), "file not found".into())),
+        }
+    }
+
+    fn create_file(&self, path: &str) -> Result<(), Error> {
+        self.files.borrow_mut().insert(path.into(), String::new());
+        Ok(())
+    }
+
+    fn append_file(&self, path: &str, text: &str) -> Result<(), Error> {
+        match self.files.borrow_mut().get_mut(path) {
+            Some(file) => {
+                file.push_str(text);
+                Ok(())
+            }
+            None => Err(FileError(path.into(), "file not found".into())),
+        }
+    }
+
+    fn open_output_file(&self, path: &str) -> Result<Box<dyn Write>, Error> {
+        self.create_file(path)?;
+        Ok(Box::new(MemoryFile {
+            files: self.files.clone(),
+            path: path.into(),
+        }))
+    }
+
+    fn file_exists(&self, path: &str) -> bool {
+        self.files.borrow().contains_key(path)
+    }
+
+    fn delete_file(&self, path: &str) -> Result<(), Error> {
+        match self.files.borrow_mut().remove(path) {
+            Some(_) => Ok(()),
+            None => Err(FileError(path.into(), "file not found".into())),
+        }
+    }
+}
+
+/// Denied Filesystem
+///
+/// A filesystem that denies all access, for embedders that must not allow
+/// scheme programs to access files.
+#[derive(Debug, Default)]
+pub struct DeniedFilesystem {}
+
+impl DeniedFilesystem {
+    pub fn new() -> DeniedFilesystem {
+        DeniedFilesystem {}
+    }
+}
+
+impl Filesystem for DeniedFilesystem {
+    fn read_file(&self, path: &str) -> Result<String, Error> {
+        Err(FileError(path.into(), "file access denied".into()))
+    }
+
+    fn create_file(&self, path: &str) -> Result<(), Error> {
+        Err(FileError(path.into(), "file access denied".into()))
+    }
+
+    fn append_file(&self, path: &str, _: &str) -> Result<(), Error> {
+        Err(FileError(path.into(), "file access denied".into()))
+    }
+
+    fn open_output_file(&self, path: &str) -> Result<Box<dyn Write>, Error> {
+        Err(FileError(path.into(), "file access denied".into()))
+    }
+
+    fn file_exists(&self, _: &str) -> bool {
+        false
+    }
+
+    fn delete_file(&self, path: &str) -> Result<(), Error> {
+        Err(FileError(path.into(), "file access denied".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_filesystem() {
+        let fs = MemoryFilesystem::new();
+        assert!(!fs.file_exists("foo"));
+        assert_eq!(
+            fs.read_file("foo"),
+            Err(FileError("foo".into(), "file not found".into()))
+        );
+        fs.create_file("foo").unwrap();
+        fs.append_file("foo", "hello ").unwrap();
+        fs.append_file("foo", "world").unwrap();
+        assert!(fs.file_exists("foo"));
+        assert_eq!(fs.read_file("foo"), Ok("hello world".into()));
+        fs.create_file("foo").unwrap();
+        assert_eq!(fs.read_file("foo"), Ok("".into()));
+        let mut file = fs.open_output_file("foo").unwrap();
+        file.write_all(b"hello").unwrap();
+        file.write_all(b" again").unwrap();
+        assert_eq!(fs.read_file("foo"), Ok("hello again".into()));
+        fs.delete_file("foo").unwrap();
+        assert!(!fs.file_exists("foo"));
+        assert!(fs.delete_file("foo").is_err());
+    }
+
+    #[test]
+    fn denied_filesystem() {
+        let fs = DeniedFilesystem::new();
+        let denied = Err(FileError("foo".into(), "file access denied".into()));
+        assert_eq!(fs.create_file("foo"), denied);
+        assert_eq!(fs.read_file("foo"), denied.map(|_| String::new()));
+        assert!(!fs.file_exists("foo"));
+    }
+}
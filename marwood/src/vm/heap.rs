@@ -8,7 +8,7 @@ use crate::vm::vcell::VCell;
 use log::trace;
 use num::ToPrimitive;
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 
 pub type HeapRef = usize;
@@ -20,6 +20,10 @@ pub struct Heap {
     heap: Vec<VCell>,
     heap_map: gc::Map,
     symbol_table: HashMap<String, usize>,
+
+    /// The global environment slots referred to by code marked since the
+    /// last sweep.
+    marked_slots: HashSet<usize>,
 }
 
 impl Heap {
@@ -34,6 +38,7 @@ impl Heap {
             free_list: (0..chunk_size).rev().into_iter().collect(),
             heap_map: gc::Map::new(chunk_size),
             symbol_table: HashMap::new(),
+            marked_slots: HashSet::new(),
         }
     }
 
@@ -122,6 +127,7 @@ impl Heap {
             | VCell::Bool(_)
             | VCell::Char(_)
             | VCell::Nil
+            | VCell::Eof
             | VCell::Void
             | VCell::Undefined => vcell,
             VCell::Ptr(_) => vcell,
@@ -174,9 +180,13 @@ impl Heap {
         match *ast {
             cell::Cell::Undefined => VCell::Undefined,
             cell::Cell::Void => VCell::Void,
+            cell::Cell::Eof => VCell::Eof,
             cell::Cell::Nil => VCell::Nil,
             cell::Cell::Number(ref val) => VCell::Number(val.clone()),
             cell::Cell::Bool(val) => VCell::Bool(val),
+            cell::Cell::Bytevector(ref bytevector) => {
+                self.put(VCell::bytevector(bytevector.clone()))
+            }
             cell::Cell::Char(val) => VCell::Char(val),
             cell::Cell::Pair(ref car, ref cdr) => {
                 match (self.put_cell(car.deref()), self.put_cell(cdr.deref())) {
@@ -186,9 +196,21 @@ impl Heap {
             }
             cell::Cell::String(ref s) => self.put(VCell::string(s.clone())),
             cell::Cell::Symbol(ref sym) => self.put(VCell::symbol(sym.clone())),
+            cell::Cell::Condition(_) => panic!("unexpected condition"),
             cell::Cell::Continuation => panic!("unexpected continuation"),
+            cell::Cell::Environment => panic!("unexpected environment"),
             cell::Cell::Macro => panic!("unexpected macro"),
+            cell::Cell::Port => panic!("unexpected port"),
+            cell::Cell::Promise => panic!("unexpected promise"),
             cell::Cell::Procedure(_) => panic!("unexpected lambda"),
+            cell::Cell::Record(_) | cell::Cell::RecordType(_) => panic!("unexpected record"),
+            cell::Cell::Values(ref values) => {
+                let mut outv = Vec::with_capacity(values.len());
+                for it in values {
+                    outv.push(self.put_cell(it))
+                }
+                VCell::values(outv)
+            }
             cell::Cell::Vector(ref vector) => {
                 let mut outv = Vec::with_capacity(vector.len());
                 for it in vector {
@@ -254,6 +276,30 @@ impl Heap {
         }
     }
 
+    /// Case Lambda Desc
+    ///
+    /// Return a description of a case-lambda procedure that names the
+    /// formals of each of its clauses, e.g. (case-lambda (λ (x)) (λ (x y))).
+    ///
+    /// # Arguments
+    /// `clauses` - The closures of each case-lambda clause
+    pub fn case_lambda_desc(&self, clauses: &[HeapRef]) -> String {
+        let clauses = clauses
+            .iter()
+            .map(|clause| match self.get_at_index(*clause) {
+                VCell::Closure(lambda, _) => match self.get_at_index(*lambda).as_lambda() {
+                    Ok(lambda) => lambda.to_string(),
+                    Err(_) => "(λ)".to_string(),
+                },
+                _ => "(λ)".to_string(),
+            })
+            .collect::<Vec<_>>();
+        match clauses.is_empty() {
+            true => "(case-lambda)".to_string(),
+            false => format!("(case-lambda {})", clauses.join(" ")),
+        }
+    }
+
     /// Get As Ast
     ///
     /// Return a Cell representation of the given vcell by copying the recursive
@@ -289,17 +335,29 @@ impl Heap {
             }
             VCell::Ptr(ptr) => self.get_as_cell(self.get_at_index(*ptr)),
             VCell::String(s) => Cell::String(s.borrow().deref().into()),
+            VCell::Bytevector(bytevector) => Cell::Bytevector(bytevector.borrow().clone()),
             VCell::Symbol(s) => Cell::Symbol(s.deref().into()),
             VCell::Undefined => Cell::Undefined,
             VCell::Void => Cell::Void,
+            VCell::Eof => Cell::Eof,
+            VCell::Values(values) => {
+                Cell::Values(values.iter().map(|it| self.get_as_cell(it)).collect())
+            }
+            VCell::Condition(condition) => Cell::Condition(condition.error().to_string()),
+            VCell::Record(record) => Cell::Record(record.record_type().name().into()),
+            VCell::RecordType(record_type) => Cell::RecordType(record_type.name().into()),
             VCell::Continuation(_) => Cell::Continuation,
+            VCell::Environment(_) => Cell::Environment,
             VCell::Closure(ptr, _) => match self.get_at_index(*ptr).as_lambda() {
                 Ok(lambda) => Cell::Procedure(Some(lambda.to_string())),
                 Err(_) => Cell::Procedure(None),
             },
             VCell::Lambda(lambda) => Cell::Procedure(Some(lambda.to_string())),
+            VCell::CaseLambda(clauses) => Cell::Procedure(Some(self.case_lambda_desc(clauses))),
             VCell::BuiltInProc(proc) => Cell::Procedure(Some(proc.desc().to_string())),
             VCell::Macro(_) => Cell::Macro,
+            VCell::Port(_) => Cell::Port,
+            VCell::Promise(_) => Cell::Promise,
             VCell::Vector(vector) => {
                 let mut outv = Vec::with_capacity(vector.len());
                 for idx in 0..vector.len() {
@@ -368,6 +426,11 @@ impl Heap {
                     self.mark(lambda);
                     self.mark(env);
                 }
+                VCell::CaseLambda(clauses) => {
+                    for clause in clauses.iter() {
+                        self.mark(*clause);
+                    }
+                }
                 VCell::LexicalEnv(env) => {
                     let env = env.as_ref();
                     for it in 0..env.slot_len() {
@@ -380,12 +443,35 @@ impl Heap {
                         self.mark_vcell(&vcell);
                     }
                 }
+                VCell::Values(values) => {
+                    for vcell in values.iter() {
+                        self.mark_vcell(vcell);
+                    }
+                }
+                VCell::Condition(condition) => {
+                    for vcell in condition.irritants() {
+                        self.mark_vcell(vcell);
+                    }
+                }
+                VCell::Record(record) => {
+                    for idx in 0..record.len() {
+                        let vcell = record.get(idx).unwrap();
+                        self.mark_vcell(&vcell);
+                    }
+                }
+                // A stream is a chain of promises, which is followed
+                // iteratively like the cdr of a pair
+                VCell::Promise(promise) => match promise.value() {
+                    VCell::Ptr(value) => ptr = value,
+                    value => self.mark_vcell(&value),
+                },
                 VCell::EnvironmentPointer(ptr) => self.mark(ptr),
                 VCell::Acc
                 | VCell::ArgumentCount(_)
                 | VCell::BasePointer(_)
                 | VCell::BasePointerOffset(_)
                 | VCell::Bool(_)
+                | VCell::Bytevector(_)
                 | VCell::Char(_)
                 | VCell::BuiltInProc(_)
                 | VCell::GlobalEnvSlot(_)
@@ -395,9 +481,13 @@ impl Heap {
                 | VCell::Nil
                 | VCell::Number(_)
                 | VCell::OpCode(_)
+                | VCell::Port(_)
+                | VCell::Environment(_)
+                | VCell::Eof
                 | VCell::String(_)
                 | VCell::Symbol(_)
                 | VCell::Macro(_)
+                | VCell::RecordType(_)
                 | VCell::Undefined
                 | VCell::Void => {}
             }
@@ -417,6 +507,11 @@ impl Heap {
                 self.mark(*lambda);
                 self.mark(*env)
             }
+            VCell::CaseLambda(clauses) => {
+                for clause in clauses.iter() {
+                    self.mark(*clause);
+                }
+            }
             VCell::Pair(car, cdr) => {
                 self.mark(*car);
                 self.mark(*cdr);
@@ -433,23 +528,47 @@ impl Heap {
                     self.mark_vcell(&vcell);
                 }
             }
+            VCell::Values(values) => {
+                for vcell in values.iter() {
+                    self.mark_vcell(vcell);
+                }
+            }
+            VCell::Condition(condition) => {
+                for vcell in condition.irritants() {
+                    self.mark_vcell(vcell);
+                }
+            }
+            VCell::Record(record) => {
+                for idx in 0..record.len() {
+                    let vcell = record.get(idx).unwrap();
+                    self.mark_vcell(&vcell);
+                }
+            }
+            VCell::Promise(promise) => self.mark_vcell(&promise.value()),
             VCell::EnvironmentPointer(ep) => self.mark(*ep),
+            VCell::GlobalEnvSlot(slot) => {
+                self.marked_slots.insert(*slot);
+            }
             VCell::Acc
             | VCell::ArgumentCount(_)
             | VCell::BasePointer(_)
             | VCell::BasePointerOffset(_)
             | VCell::Bool(_)
+            | VCell::Bytevector(_)
             | VCell::Char(_)
-            | VCell::GlobalEnvSlot(_)
             | VCell::LexicalEnv(_)
             | VCell::LexicalEnvSlot(_)
             | VCell::Nil
             | VCell::Number(_)
             | VCell::OpCode(_)
+            | VCell::Port(_)
+            | VCell::Environment(_)
+            | VCell::Eof
             | VCell::String(_)
             | VCell::Symbol(_)
             | VCell::BuiltInProc(_)
             | VCell::Macro(_)
+            | VCell::RecordType(_)
             | VCell::Undefined
             | VCell::Void => {}
         }
@@ -464,6 +583,8 @@ impl Heap {
         }
         self.mark(cont.ip().0);
         self.mark(cont.ep());
+        self.mark_vcell(cont.wind());
+        self.mark_vcell(cont.handlers());
     }
 
     /// Mark Lambda
@@ -486,6 +607,14 @@ impl Heap {
         }
     }
 
+    /// Marked Slots
+    ///
+    /// Return the global environment slots referred to by the code marked
+    /// since the last sweep.
+    pub fn marked_slots(&self) -> &HashSet<usize> {
+        &self.marked_slots
+    }
+
     /// Sweep
     ///
     /// Iterate the heap map. Performing the following for each object state:
@@ -495,6 +624,7 @@ impl Heap {
     ///        free list.
     /// * State::Used - Mark the vcell as allocated.
     pub fn sweep(&mut self) {
+        self.marked_slots.clear();
         let before = self.free_list.len();
         for it in 0..self.heap.len() {
             match self.heap_map.get(it) {
@@ -615,6 +745,13 @@ mod tests {
             let vcell = heap.put_cell(&cell!["foo"]);
             assert_eq!(heap.get_as_cell(&vcell), cell!["foo"]);
         }
+        // Bytevector
+        {
+            let mut heap = Heap::new(CHUNK_SIZE);
+            let vcell = heap.put_cell(&Cell::Bytevector(vec![0, 1, 255]));
+            assert!(heap.get(&vcell).is_bytevector());
+            assert_eq!(heap.get_as_cell(&vcell), Cell::Bytevector(vec![0, 1, 255]));
+        }
     }
 
     #[test]
@@ -1,5 +1,6 @@
 use crate::cell::Cell;
 use crate::vm::environment::{BindingLocation, EnvironmentMap};
+use crate::vm::opcode::OpCode;
 use crate::vm::vcell::VCell;
 use std::fmt::{Display, Formatter};
 
@@ -15,6 +16,11 @@ pub struct Lambda {
     pub args: Vec<VCell>,
     pub bc: Vec<VCell>,
     pub desc_args: Option<Cell>,
+
+    /// The tag of the named let this lambda is the loop body of, and the
+    /// offset of the first instruction of the body. A tail call to the tag
+    /// is compiled to a jump to the offset.
+    pub loop_entry: Option<(Cell, usize)>,
 }
 
 impl Lambda {
@@ -34,6 +40,7 @@ impl Lambda {
             args,
             bc: vec![],
             desc_args: None,
+            loop_entry: None,
         }
     }
 
@@ -56,6 +63,7 @@ impl Lambda {
             envmap,
             bc: vec![],
             desc_args: None,
+            loop_entry: None,
         }
     }
 
@@ -86,6 +94,28 @@ impl Lambda {
         self.bc.push(vcell.into());
     }
 
+    /// Emit Call
+    ///
+    /// Emit the byte code to apply proc to args, leaving the result
+    /// in %acc. This is used to build procedures at runtime, where
+    /// proc and args are already known values.
+    ///
+    /// # Arguments
+    /// `proc` - The procedure to apply
+    /// `args` - The arguments to apply proc to
+    pub fn emit_call(&mut self, proc: VCell, args: &[VCell]) {
+        for arg in args {
+            self.emit(OpCode::PushImmediate);
+            self.emit(arg.clone());
+        }
+        self.emit(OpCode::PushImmediate);
+        self.emit(VCell::ArgumentCount(args.len()));
+        self.emit(OpCode::MovImmediate);
+        self.emit(proc);
+        self.emit(VCell::Acc);
+        self.emit(OpCode::CallAcc);
+    }
+
     /// Binding Location
     ///
     /// Return the binding for the given symbol. First check the lexical environment.
@@ -124,6 +154,7 @@ impl From<Vec<VCell>> for Lambda {
             is_vararg: false,
             bc,
             desc_args: None,
+            loop_entry: None,
         }
     }
 }
@@ -0,0 +1,1162 @@
+use crate::cell::Cell;
+use crate::error::Error;
+use crate::error::Error::{InvalidSyntax, LibraryNotFound};
+use crate::parse;
+use crate::vm::builtin::pop_argc;
+use crate::vm::environment::Environment;
+use crate::vm::filesystem::Filesystem;
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// Library
+///
+/// A library defined by define-library, or one of the standard libraries
+/// built into the VM. Each export is a symbol bound to a slot in the global
+/// environment, and importing the library binds the symbol to the same slot
+/// in the importing environment.
+#[derive(Debug)]
+pub struct Library {
+    exports: Vec<(usize, usize)>,
+}
+
+impl Library {
+    /// Iter Symbols
+    ///
+    /// Iterate over the symbol of each export.
+    pub fn iter_symbols(&self) -> impl Iterator<Item = &usize> {
+        self.exports.iter().map(|(sym, _)| sym)
+    }
+}
+
+/// Library Resolver
+///
+/// A library resolver supplies the source of a library that is imported
+/// before it's defined. The source is evaluated, and must define the
+/// library with define-library.
+///
+/// The VM tries each of its resolvers in the order they were added (see
+/// Vm::add_library_resolver), which allows an embedder to supply libraries
+/// from disk, from memory, or from source bundled with the embedder.
+pub trait LibraryResolver: Debug {
+    /// Return the source of the library named name, or None if this
+    /// resolver doesn't supply the library.
+    fn resolve(
+        &self,
+        name: &[String],
+        filesystem: &dyn Filesystem,
+    ) -> Result<Option<String>, Error>;
+}
+
+/// Filesystem Resolver
+///
+/// Resolve the library named (a b c) to the file a/b/c.sld in the first of
+/// a list of directories that contains it, using the VM's filesystem. The
+/// VM's default resolver searches the current directory.
+#[derive(Debug)]
+pub struct FilesystemResolver {
+    directories: Vec<String>,
+}
+
+impl FilesystemResolver {
+    pub fn new(directories: Vec<String>) -> FilesystemResolver {
+        FilesystemResolver { directories }
+    }
+}
+
+impl Default for FilesystemResolver {
+    fn default() -> Self {
+        FilesystemResolver::new(vec![".".into()])
+    }
+}
+
+impl LibraryResolver for FilesystemResolver {
+    fn resolve(
+        &self,
+        name: &[String],
+        filesystem: &dyn Filesystem,
+    ) -> Result<Option<String>, Error> {
+        for directory in &self.directories {
+            let path = format!("{}/{}.sld", directory, name.join("/"));
+            if filesystem.file_exists(&path) {
+                return filesystem.read_file(&path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Memory Resolver
+///
+/// Resolve libraries from source held in memory, such as source bundled
+/// with an embedder by include_str!.
+#[derive(Debug, Default)]
+pub struct MemoryResolver {
+    libraries: HashMap<Vec<String>, String>,
+}
+
+impl MemoryResolver {
+    pub fn new() -> MemoryResolver {
+        MemoryResolver {
+            libraries: HashMap::new(),
+        }
+    }
+
+    /// Insert
+    ///
+    /// Supply the source of the library named name.
+    ///
+    /// # Arguments
+    /// `name` - The name of the library, e.g. ["srfi", "1"] for (srfi 1)
+    /// `source` - The source of the library
+    pub fn insert(&mut self, name: &[&str], source: &str) {
+        let name = name.iter().map(|it| it.to_string()).collect();
+        self.libraries.insert(name, source.into());
+    }
+}
+
+impl LibraryResolver for MemoryResolver {
+    fn resolve(&self, name: &[String], _: &dyn Filesystem) -> Result<Option<String>, Error> {
+        Ok(self.libraries.get(name).cloned())
+    }
+}
+
+/// Standard Libraries
+///
+/// The name and exports of each library built into the VM, which export
+/// the bindings of the builtins and prelude. The interaction environment
+/// imports every library except (marwood internal), which holds the
+/// bindings the builtins and prelude are implemented with.
+///
+/// Syntax such as define, lambda and if is compiled by the VM rather than
+/// bound in an environment, and is available in every environment.
+const STANDARD_LIBRARIES: &[(&[&str], &[&str])] = &[
+    (
+        &["scheme", "base"],
+        &[
+            "*",
+            "+",
+            "-",
+            "/",
+            "<",
+            "<=",
+            "=",
+            ">",
+            ">=",
+            "abs",
+            "and",
+            "append",
+            "apply",
+            "assoc",
+            "assq",
+            "assv",
+            "begin",
+            "boolean?",
+            "bytevector",
+            "bytevector-append",
+            "bytevector-copy",
+            "bytevector-copy!",
+            "bytevector-length",
+            "bytevector-u8-ref",
+            "bytevector-u8-set!",
+            "bytevector?",
+            "caar",
+            "cadr",
+            "call-with-current-continuation",
+            "call-with-port",
+            "call-with-values",
+            "call/cc",
+            "car",
+            "case",
+            "cdar",
+            "cddr",
+            "cdr",
+            "ceiling",
+            "char->integer",
+            "char-ready?",
+            "char<=?",
+            "char<?",
+            "char=?",
+            "char>=?",
+            "char>?",
+            "char?",
+            "close-input-port",
+            "close-output-port",
+            "close-port",
+            "complex?",
+            "cond",
+            "cons",
+            "current-input-port",
+            "current-output-port",
+            "denominator",
+            "do",
+            "dynamic-wind",
+            "eof-object",
+            "eof-object?",
+            "eq?",
+            "equal?",
+            "eqv?",
+            "error",
+            "error-object-irritants",
+            "error-object-message",
+            "error-object?",
+            "even?",
+            "exact",
+            "exact-integer-sqrt",
+            "exact-integer?",
+            "exact?",
+            "expt",
+            "file-error?",
+            "floor",
+            "floor-quotient",
+            "floor-remainder",
+            "floor/",
+            "for-each",
+            "gcd",
+            "get-output-string",
+            "guard",
+            "inexact",
+            "inexact?",
+            "input-port?",
+            "integer->char",
+            "integer?",
+            "lcm",
+            "length",
+            "let",
+            "let*",
+            "let*-values",
+            "let-values",
+            "letrec",
+            "letrec*",
+            "list",
+            "list->string",
+            "list->vector",
+            "list-copy",
+            "list-ref",
+            "list-set!",
+            "list-tail",
+            "list?",
+            "make-bytevector",
+            "make-list",
+            "make-parameter",
+            "make-string",
+            "make-vector",
+            "map",
+            "max",
+            "member",
+            "memq",
+            "memv",
+            "min",
+            "modulo",
+            "negative?",
+            "newline",
+            "not",
+            "null?",
+            "number->string",
+            "number?",
+            "numerator",
+            "odd?",
+            "open-input-string",
+            "open-output-string",
+            "or",
+            "output-port?",
+            "pair?",
+            "parameterize",
+            "peek-char",
+            "port?",
+            "positive?",
+            "procedure?",
+            "quotient",
+            "raise",
+            "raise-continuable",
+            "rational?",
+            "rationalize",
+            "read-char",
+            "read-error?",
+            "read-line",
+            "read-string",
+            "real?",
+            "remainder",
+            "reverse",
+            "round",
+            "set-car!",
+            "set-cdr!",
+            "square",
+            "string",
+            "string->list",
+            "string->number",
+            "string->symbol",
+            "string->utf8",
+            "string->vector",
+            "string-append",
+            "string-copy",
+            "string-copy!",
+            "string-fill!",
+            "string-for-each",
+            "string-length",
+            "string-map",
+            "string-ref",
+            "string-set!",
+            "string<=?",
+            "string<?",
+            "string=?",
+            "string>=?",
+            "string>?",
+            "string?",
+            "substring",
+            "symbol->string",
+            "symbol=?",
+            "symbol?",
+            "textual-port?",
+            "truncate",
+            "truncate-quotient",
+            "truncate-remainder",
+            "truncate/",
+            "unless",
+            "utf8->string",
+            "values",
+            "vector",
+            "vector->list",
+            "vector->string",
+            "vector-append",
+            "vector-copy",
+            "vector-copy!",
+            "vector-fill!",
+            "vector-for-each",
+            "vector-length",
+            "vector-map",
+            "vector-ref",
+            "vector-set!",
+            "vector?",
+            "when",
+            "with-exception-handler",
+            "write-char",
+            "write-string",
+            "zero?",
+        ],
+    ),
+    (&["scheme", "case-lambda"], &[]),
+    (
+        &["scheme", "char"],
+        &[
+            "char-alphabetic?",
+            "char-ci<=?",
+            "char-ci<?",
+            "char-ci=?",
+            "char-ci>=?",
+            "char-ci>?",
+            "char-downcase",
+            "char-foldcase",
+            "char-lower-case?",
+            "char-numeric?",
+            "char-upcase",
+            "char-upper-case?",
+            "char-whitespace?",
+            "digit-value",
+            "string-ci<=?",
+            "string-ci<?",
+            "string-ci=?",
+            "string-ci>=?",
+            "string-ci>?",
+            "string-downcase",
+            "string-foldcase",
+            "string-upcase",
+        ],
+    ),
+    (
+        &["scheme", "complex"],
+        &[
+            "angle",
+            "imag-part",
+            "magnitude",
+            "make-polar",
+            "make-rectangular",
+            "real-part",
+        ],
+    ),
+    (
+        &["scheme", "cxr"],
+        &[
+            "caaaar", "caaadr", "caaar", "caadar", "caaddr", "caadr", "cadaar", "cadadr", "cadar",
+            "caddar", "cadddr", "caddr", "cdaaar", "cdaadr", "cdaar", "cdadar", "cdaddr", "cdadr",
+            "cddaar", "cddadr", "cddar", "cdddar", "cddddr", "cdddr",
+        ],
+    ),
+    (&["scheme", "eval"], &["environment", "eval"]),
+    (
+        &["scheme", "file"],
+        &[
+            "call-with-input-file",
+            "call-with-output-file",
+            "delete-file",
+            "file-exists?",
+            "open-input-file",
+            "open-output-file",
+            "with-input-from-file",
+            "with-output-to-file",
+        ],
+    ),
+    (
+        &["scheme", "inexact"],
+        &[
+            "acos",
+            "asin",
+            "atan",
+            "cos",
+            "exp",
+            "finite?",
+            "infinite?",
+            "log",
+            "nan?",
+            "sin",
+            "sqrt",
+            "tan",
+        ],
+    ),
+    (
+        &["scheme", "lazy"],
+        &["delay", "delay-force", "force", "make-promise", "promise?"],
+    ),
+    (&["scheme", "load"], &["load"]),
+    (
+        &["scheme", "r5rs"],
+        &[
+            "*",
+            "+",
+            "-",
+            "/",
+            "<",
+            "<=",
+            "=",
+            ">",
+            ">=",
+            "abs",
+            "acos",
+            "and",
+            "angle",
+            "append",
+            "apply",
+            "asin",
+            "assoc",
+            "assq",
+            "assv",
+            "atan",
+            "begin",
+            "boolean?",
+            "caaaar",
+            "caaadr",
+            "caaar",
+            "caadar",
+            "caaddr",
+            "caadr",
+            "caar",
+            "cadaar",
+            "cadadr",
+            "cadar",
+            "caddar",
+            "cadddr",
+            "caddr",
+            "cadr",
+            "call-with-current-continuation",
+            "call-with-input-file",
+            "call-with-output-file",
+            "call-with-values",
+            "car",
+            "case",
+            "cdaaar",
+            "cdaadr",
+            "cdaar",
+            "cdadar",
+            "cdaddr",
+            "cdadr",
+            "cdar",
+            "cddaar",
+            "cddadr",
+            "cddar",
+            "cdddar",
+            "cddddr",
+            "cdddr",
+            "cddr",
+            "cdr",
+            "ceiling",
+            "char->integer",
+            "char-alphabetic?",
+            "char-ci<=?",
+            "char-ci<?",
+            "char-ci=?",
+            "char-ci>=?",
+            "char-ci>?",
+            "char-downcase",
+            "char-lower-case?",
+            "char-numeric?",
+            "char-ready?",
+            "char-upcase",
+            "char-upper-case?",
+            "char-whitespace?",
+            "char<=?",
+            "char<?",
+            "char=?",
+            "char>=?",
+            "char>?",
+            "char?",
+            "close-input-port",
+            "close-output-port",
+            "complex?",
+            "cond",
+            "cons",
+            "cos",
+            "current-input-port",
+            "current-output-port",
+            "delay",
+            "denominator",
+            "display",
+            "do",
+            "dynamic-wind",
+            "eof-object?",
+            "eq?",
+            "equal?",
+            "eqv?",
+            "eval",
+            "even?",
+            "exact->inexact",
+            "exact?",
+            "exp",
+            "expt",
+            "floor",
+            "for-each",
+            "force",
+            "gcd",
+            "imag-part",
+            "inexact->exact",
+            "inexact?",
+            "input-port?",
+            "integer->char",
+            "integer?",
+            "interaction-environment",
+            "lcm",
+            "length",
+            "let",
+            "let*",
+            "letrec",
+            "list",
+            "list->string",
+            "list->vector",
+            "list-ref",
+            "list-tail",
+            "list?",
+            "log",
+            "magnitude",
+            "make-polar",
+            "make-rectangular",
+            "make-string",
+            "make-vector",
+            "map",
+            "max",
+            "member",
+            "memq",
+            "memv",
+            "min",
+            "modulo",
+            "negative?",
+            "newline",
+            "not",
+            "null-environment",
+            "null?",
+            "number->string",
+            "number?",
+            "numerator",
+            "odd?",
+            "open-input-file",
+            "open-output-file",
+            "or",
+            "output-port?",
+            "pair?",
+            "peek-char",
+            "positive?",
+            "procedure?",
+            "quotient",
+            "rational?",
+            "rationalize",
+            "read",
+            "read-char",
+            "real-part",
+            "real?",
+            "remainder",
+            "reverse",
+            "round",
+            "scheme-report-environment",
+            "set-car!",
+            "set-cdr!",
+            "sin",
+            "sqrt",
+            "string",
+            "string->list",
+            "string->number",
+            "string->symbol",
+            "string-append",
+            "string-ci<=?",
+            "string-ci<?",
+            "string-ci=?",
+            "string-ci>=?",
+            "string-ci>?",
+            "string-copy",
+            "string-fill!",
+            "string-length",
+            "string-ref",
+            "string-set!",
+            "string<=?",
+            "string<?",
+            "string=?",
+            "string>=?",
+            "string>?",
+            "string?",
+            "substring",
+            "symbol->string",
+            "symbol?",
+            "tan",
+            "truncate",
+            "values",
+            "vector",
+            "vector->list",
+            "vector-fill!",
+            "vector-length",
+            "vector-ref",
+            "vector-set!",
+            "vector?",
+            "with-input-from-file",
+            "with-output-to-file",
+            "write",
+            "write-char",
+            "zero?",
+        ],
+    ),
+    (&["scheme", "read"], &["read"]),
+    (&["scheme", "repl"], &["interaction-environment"]),
+    (&["scheme", "write"], &["display", "write"]),
+    (
+        &["marwood", "base"],
+        &[
+            "%",
+            "add1",
+            "atom?",
+            "call-with-output-string",
+            "copy-environment",
+            "environment?",
+            "error-object-kind",
+            "exact-rational?",
+            "pow",
+            "random-integer",
+            "random-real",
+            "random-signed",
+            "receive",
+            "sort",
+            "sub1",
+            "term-cols",
+            "term-rows",
+            "time-utc",
+            "void",
+        ],
+    ),
+    (
+        &["marwood", "internal"],
+        &[
+            "any?",
+            "do-aux",
+            "guard-aux",
+            "make-parameter-procedure",
+            "map1",
+            "parameter-key",
+            "parameterize-aux",
+            "promise-done?",
+            "promise-new",
+            "promise-update!",
+            "promise-value",
+            "set-current-input-port!",
+            "set-current-output-port!",
+        ],
+    ),
+];
+
+/// The library exporting the bindings the builtins and prelude are
+/// implemented with
+const INTERNAL_LIBRARY: &[&str] = &["marwood", "internal"];
+
+/// The syntax compiled by the VM, which every library implicitly exports
+const PRIMITIVE_SYNTAX: &[&str] = &[
+    "...",
+    "=>",
+    "_",
+    "case-lambda",
+    "define",
+    "define-record-type",
+    "define-syntax",
+    "define-values",
+    "else",
+    "if",
+    "include",
+    "include-ci",
+    "lambda",
+    "let-syntax",
+    "letrec-syntax",
+    "quasiquote",
+    "quote",
+    "set!",
+    "syntax-rules",
+    "unquote",
+    "unquote-splicing",
+];
+
+impl Vm {
+    /// Load Standard Libraries
+    ///
+    /// Define each of the standard libraries from the bindings of the
+    /// builtins and prelude in the current environment, and replace the
+    /// interaction environment with a new environment importing each of
+    /// them except (marwood internal).
+    pub fn load_standard_libraries(&mut self) {
+        let system = self.globenv.environment();
+        let interaction = Rc::new(Environment::new());
+        for (name, exports) in STANDARD_LIBRARIES {
+            let exports = exports
+                .iter()
+                .map(|sym| {
+                    let sym = self.heap.put(VCell::symbol(*sym)).as_ptr().unwrap();
+                    let slot = system.get(sym).expect("invalid standard library export");
+                    (sym, slot)
+                })
+                .collect::<Vec<_>>();
+            if *name != INTERNAL_LIBRARY {
+                for (sym, slot) in &exports {
+                    self.globenv.import(&interaction, *sym, *slot);
+                }
+            }
+            let name = name.iter().map(|it| it.to_string()).collect();
+            self.libraries.insert(name, Library { exports });
+        }
+
+        debug_assert!(
+            {
+                let exported = self
+                    .libraries
+                    .values()
+                    .flat_map(|it| it.iter_symbols())
+                    .collect::<HashSet<_>>();
+                system.symbols().iter().all(|it| exported.contains(it))
+            },
+            "every builtin and prelude binding must be exported by a standard library"
+        );
+        self.globenv.set_interaction_environment(interaction);
+    }
+
+    /// Add Library Resolver
+    ///
+    /// Add a resolver that supplies the source of libraries, which is tried
+    /// after the resolvers already added.
+    ///
+    /// # Arguments
+    /// `resolver` - The resolver to add
+    pub fn add_library_resolver(&mut self, resolver: Box<dyn LibraryResolver>) {
+        self.library_resolvers.push(resolver);
+    }
+
+    /// Clear Library Resolvers
+    ///
+    /// Remove every library resolver, including the default resolver that
+    /// searches the current directory.
+    pub fn clear_library_resolvers(&mut self) {
+        self.library_resolvers.clear();
+    }
+
+    /// Import Sets
+    ///
+    /// Bind the bindings named by each import set in the list sets in
+    /// environment, and return result. This is applied by a builtin, and
+    /// if any library isn't yet defined its source is first loaded from
+    /// the library resolvers and evaluated (see Vm::eval_sequence).
+    ///
+    /// # Arguments
+    /// `sets` - The list of import sets
+    /// `environment` - The environment to bind the imports in
+    /// `result` - The value to return once the sets are imported
+    pub fn import_sets(
+        &mut self,
+        sets: VCell,
+        environment: Rc<Environment>,
+        result: VCell,
+    ) -> Result<VCell, Error> {
+        let cell = self.heap.get_as_cell(&sets);
+        for set in &cell {
+            let name = import_set_library(set)?;
+            if !self.libraries.contains_key(&name) {
+                return self.load_library(name, sets, environment, result);
+            }
+        }
+
+        let mut bindings = vec![];
+        for set in &cell {
+            bindings.extend(self.resolve_import_set(set)?);
+        }
+        for (sym, slot) in bindings {
+            let sym = self.heap.put(VCell::symbol(&sym)).as_ptr()?;
+            self.globenv.import(&environment, sym, slot);
+        }
+        Ok(result)
+    }
+
+    /// Load Library
+    ///
+    /// Evaluate the source of the library named name, and then resume
+    /// importing the import sets.
+    ///
+    /// # Arguments
+    /// `name` - The name of the library to load
+    /// `sets` - The list of import sets being imported
+    /// `environment` - The environment to bind the imports in
+    /// `result` - The value to return once the sets are imported
+    fn load_library(
+        &mut self,
+        name: Vec<String>,
+        sets: VCell,
+        environment: Rc<Environment>,
+        result: VCell,
+    ) -> Result<VCell, Error> {
+        if self.loading_libraries.contains(&name) {
+            return Err(InvalidSyntax(format!(
+                "library {} imports itself",
+                library_name_text(&name)
+            )));
+        }
+        let source = self
+            .library_source(&name)?
+            .ok_or_else(|| LibraryNotFound(library_name_text(&name)))?;
+        let exprs = parse::parse_all(&source)?;
+        let exprs = self.heap.put_cell(&Cell::new_list(exprs));
+        let name_cell = Cell::new_list(name.iter().map(|it| Cell::new_symbol(it)));
+        let state = VCell::vector(vec![
+            sets,
+            VCell::Environment(environment),
+            result,
+            self.heap.put_cell(&name_cell),
+        ]);
+        self.loading_libraries.insert(name);
+        let environment = Rc::new(Environment::new());
+        self.eval_sequence(exprs, environment, None, "import", import_resume, state)
+    }
+
+    /// Library Source
+    ///
+    /// Return the source of the library named name supplied by the first
+    /// resolver that supplies it, if any.
+    ///
+    /// # Arguments
+    /// `name` - The name of the library
+    fn library_source(&self, name: &[String]) -> Result<Option<String>, Error> {
+        for resolver in &self.library_resolvers {
+            if let Some(source) = resolver.resolve(name, self.filesystem())? {
+                return Ok(Some(source));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Resolve Import Set
+    ///
+    /// Return the name and slot of each binding named by an import set,
+    /// whose library must be defined.
+    ///
+    /// # Arguments
+    /// `set` - The import set
+    fn resolve_import_set(&self, set: &Cell) -> Result<Vec<(String, usize)>, Error> {
+        let (modifier, inner, args) = match import_modifier(set) {
+            Some(modifier) => modifier,
+            None => {
+                let name = library_name(set)?;
+                let library = self
+                    .libraries
+                    .get(&name)
+                    .ok_or_else(|| LibraryNotFound(library_name_text(&name)))?;
+                return Ok(library
+                    .exports
+                    .iter()
+                    .map(|(sym, slot)| {
+                        let sym = self.heap.get_at_index(*sym).as_symbol().unwrap();
+                        (sym.to_string(), *slot)
+                    })
+                    .collect());
+            }
+        };
+
+        let mut bindings = self.resolve_import_set(inner)?;
+        let not_exported =
+            |id: &str| InvalidSyntax(format!("{} is not exported by {:#}", id, inner));
+        let invalid = || InvalidSyntax(format!("invalid import set {:#}", set));
+        match modifier {
+            "only" => {
+                let mut only = vec![];
+                for id in args {
+                    let id = import_identifier(id)?;
+                    match bindings.iter().find(|it| it.0 == id) {
+                        Some(binding) => only.push(binding.clone()),
+                        None if is_primitive_syntax(id) => {}
+                        None => return Err(not_exported(id)),
+                    }
+                }
+                Ok(only)
+            }
+            "except" => {
+                for id in args {
+                    let id = import_identifier(id)?;
+                    match bindings.iter().position(|it| it.0 == id) {
+                        Some(idx) => {
+                            bindings.remove(idx);
+                        }
+                        None if is_primitive_syntax(id) => {}
+                        None => return Err(not_exported(id)),
+                    }
+                }
+                Ok(bindings)
+            }
+            "prefix" => {
+                let prefix = match args.collect_vec().as_slice() {
+                    [prefix] => import_identifier(prefix)?,
+                    _ => return Err(invalid()),
+                };
+                Ok(bindings
+                    .into_iter()
+                    .map(|(sym, slot)| (format!("{}{}", prefix, sym), slot))
+                    .collect())
+            }
+            _ => {
+                for rename in args {
+                    let (from, to) = match rename.collect_vec().as_slice() {
+                        [from, to] if rename.is_list() => {
+                            (import_identifier(from)?, import_identifier(to)?)
+                        }
+                        _ => return Err(invalid()),
+                    };
+                    match bindings.iter_mut().find(|it| it.0 == from) {
+                        Some(binding) => binding.0 = to.into(),
+                        None if is_primitive_syntax(from) => {}
+                        None => return Err(not_exported(from)),
+                    }
+                }
+                Ok(bindings)
+            }
+        }
+    }
+}
+
+/// Define Library
+///
+/// The builtin a define-library expression is compiled to, which is
+/// applied to the expression. Each import and include declaration and the
+/// body of each begin declaration is evaluated in order against a new
+/// environment, and then the library is defined with the bindings named by
+/// its export declarations.
+pub fn define_library(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "define-library")?;
+    let expr = vm.stack.pop()?.clone();
+    let definition = LibraryDefinition::try_new(&vm.heap.get_as_cell(&expr))?;
+    let environment = Rc::new(Environment::new());
+    let body = vm.heap.put_cell(&Cell::new_list(definition.body));
+    let state = VCell::vector(vec![expr, VCell::Environment(environment.clone())]);
+    vm.eval_sequence(
+        body,
+        environment,
+        None,
+        "define-library",
+        define_library_resume,
+        state,
+    )
+}
+
+/// Define Library Resume
+///
+/// Define the library once its body has been evaluated. The state is a
+/// vector of the define-library expression and the library's environment.
+fn define_library_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "define-library")?;
+    vm.stack.pop()?;
+    let state = vm.heap.get(vm.stack.pop()?);
+    let state = state.as_vector()?;
+    let definition = LibraryDefinition::try_new(&vm.heap.get_as_cell(&state.get(0).unwrap()))?;
+    let environment = vm.heap.get(state.get(1).unwrap()).as_environment()?;
+
+    let mut exports = vec![];
+    for (internal, external) in definition.exports {
+        if is_primitive_syntax(&internal) {
+            continue;
+        }
+        let slot = vm
+            .heap
+            .get_sym_ref(&Cell::new_symbol(&internal))
+            .and_then(|sym| environment.get(sym.as_ptr().ok()?))
+            .filter(|slot| !vm.globenv.get_slot(*slot).is_undefined())
+            .ok_or_else(|| {
+                InvalidSyntax(format!(
+                    "library {} exports {}, which is not defined",
+                    library_name_text(&definition.name),
+                    internal
+                ))
+            })?;
+        let sym = vm.heap.put(VCell::symbol(&external)).as_ptr()?;
+        exports.push((sym, slot));
+    }
+    vm.libraries.insert(definition.name, Library { exports });
+    Ok(VCell::Void)
+}
+
+/// Import
+///
+/// The builtin an import expression is compiled to, which is applied to
+/// the list of import sets and the environment the expression was compiled
+/// against.
+pub fn import(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "import")?;
+    let environment = vm.heap.get(vm.stack.pop()?).as_environment()?;
+    let sets = vm.stack.pop()?.clone();
+    vm.import_sets(sets, environment, VCell::Void)
+}
+
+/// Import Resume
+///
+/// Resume importing once the source of a library has been evaluated. The
+/// state is a vector of the import sets, the environment to bind them in,
+/// the result to return, and the name of the library that was loaded.
+fn import_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "import")?;
+    vm.stack.pop()?;
+    let state = vm.heap.get(vm.stack.pop()?);
+    let state = state.as_vector()?;
+    let field = |idx| state.get(idx).unwrap();
+
+    let name = library_name(&vm.heap.get_as_cell(&field(3)))?;
+    vm.loading_libraries.remove(&name);
+    if !vm.libraries.contains_key(&name) {
+        return Err(InvalidSyntax(format!(
+            "the source of library {} doesn't define it",
+            library_name_text(&name)
+        )));
+    }
+
+    let environment = vm.heap.get(field(1)).as_environment()?;
+    vm.import_sets(field(0), environment, field(2))
+}
+
+/// Library Definition
+///
+/// The parts of a define-library expression
+struct LibraryDefinition {
+    /// The name of the library
+    name: Vec<String>,
+    /// The internal and external symbol of each export
+    exports: Vec<(String, String)>,
+    /// The import and include declarations and the bodies of the begin
+    /// declarations, in order
+    body: Vec<Cell>,
+}
+
+impl LibraryDefinition {
+    fn try_new(expr: &Cell) -> Result<LibraryDefinition, Error> {
+        let name = library_name(expr.cdr().and_then(|it| it.car()).unwrap_or(&Cell::Nil))?;
+        let mut exports = vec![];
+        let mut body = vec![];
+        for declaration in expr.cdr().unwrap().cdr().unwrap() {
+            let invalid =
+                || InvalidSyntax(format!("invalid library declaration {:#}", declaration));
+            let keyword = declaration.car().and_then(|it| it.as_symbol());
+            match keyword.ok_or_else(invalid)? {
+                "export" => {
+                    for spec in declaration.cdr().unwrap() {
+                        exports.push(match spec {
+                            Cell::Symbol(sym) => (sym.clone(), sym.clone()),
+                            _ => match spec.collect_vec().as_slice() {
+                                [Cell::Symbol(rename), Cell::Symbol(from), Cell::Symbol(to)]
+                                    if rename == "rename" && spec.is_list() =>
+                                {
+                                    (from.clone(), to.clone())
+                                }
+                                _ => return Err(invalid()),
+                            },
+                        });
+                    }
+                }
+                "import" | "include" | "include-ci" => body.push(declaration.clone()),
+                "begin" => body.extend(declaration.cdr().unwrap().iter().cloned()),
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(LibraryDefinition {
+            name,
+            exports,
+            body,
+        })
+    }
+}
+
+/// Library Name
+///
+/// Return the name of a library given as a list of identifiers and exact
+/// non-negative integers, e.g. (scheme base) or (srfi 1).
+///
+/// # Arguments
+/// `cell` - The library name
+fn library_name(cell: &Cell) -> Result<Vec<String>, Error> {
+    let invalid = || InvalidSyntax(format!("invalid library name {:#}", cell));
+    if !cell.is_pair() || !cell.is_list() {
+        return Err(invalid());
+    }
+    cell.iter()
+        .map(|it| match it {
+            Cell::Symbol(sym) => Ok(sym.clone()),
+            Cell::Number(num) if num.is_exact() && num.to_usize().is_some() => Ok(num.to_string()),
+            _ => Err(invalid()),
+        })
+        .collect()
+}
+
+fn library_name_text(name: &[String]) -> String {
+    format!("({})", name.join(" "))
+}
+
+/// Import Modifier
+///
+/// If set is an only, except, prefix or rename import set, return the
+/// modifier, the import set it modifies and the modifier's arguments.
+///
+/// # Arguments
+/// `set` - The import set
+fn import_modifier(set: &Cell) -> Option<(&str, &Cell, &Cell)> {
+    let modifier = set.car()?.as_symbol()?;
+    let inner = set.cdr()?.car()?;
+    match modifier {
+        "only" | "except" | "prefix" | "rename" if inner.is_pair() => {
+            Some((modifier, inner, set.cdr()?.cdr()?))
+        }
+        _ => None,
+    }
+}
+
+/// Import Set Library
+///
+/// Return the name of the library an import set imports from.
+///
+/// # Arguments
+/// `set` - The import set
+fn import_set_library(set: &Cell) -> Result<Vec<String>, Error> {
+    match import_modifier(set) {
+        Some((_, inner, _)) => import_set_library(inner),
+        None => library_name(set),
+    }
+}
+
+fn import_identifier(cell: &Cell) -> Result<&str, Error> {
+    cell.as_symbol()
+        .ok_or_else(|| InvalidSyntax(format!("expected identifier, but got {:#}", cell)))
+}
+
+fn is_primitive_syntax(sym: &str) -> bool {
+    PRIMITIVE_SYNTAX.contains(&sym)
+}
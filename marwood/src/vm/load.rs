@@ -0,0 +1,254 @@
+use crate::cell::{unalias, Cell};
+use crate::error::Error;
+use crate::error::Error::{InvalidSyntax, LoadError};
+use crate::parse;
+use crate::vm::builtin::pop_argc;
+use crate::vm::lambda::Lambda;
+use crate::vm::opcode::OpCode;
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+
+impl Vm {
+    /// Load Source
+    ///
+    /// Parse and eval each expression in text in order, returning the
+    /// result of the last expression. Any error is reported as a LoadError
+    /// naming the source.
+    ///
+    /// # Arguments
+    /// `name` - The name of the source, e.g. its path
+    /// `text` - The text to eval
+    pub fn load_source(&mut self, name: &str, text: &str) -> Result<Cell, Error> {
+        let load_error = |error| LoadError(name.into(), Box::new(error));
+        let exprs = parse::parse_all(text).map_err(|error| load_error(error.into()))?;
+        let mut result = Cell::Void;
+        for expr in exprs {
+            result = self.eval(&expr).map_err(load_error)?;
+        }
+        Ok(result)
+    }
+
+    /// Eval File
+    ///
+    /// Read the file at path using the system interface's filesystem, and
+    /// eval each expression it contains (see Vm::load_source).
+    ///
+    /// # Arguments
+    /// `path` - The path of the file to eval
+    pub fn eval_file(&mut self, path: &str) -> Result<Cell, Error> {
+        let text = self.filesystem().read_file(path)?;
+        self.load_source(path, &text)
+    }
+
+    /// Read Source
+    ///
+    /// Read and parse each expression in the file at path, as load and
+    /// include do. An error parsing the file is reported as a LoadError
+    /// naming the file.
+    ///
+    /// # Arguments
+    /// `path` - The path of the file to read
+    /// `fold_case` - If true, fold the case of identifiers as include-ci does
+    pub fn read_source(&self, path: &str, fold_case: bool) -> Result<Vec<Cell>, Error> {
+        let text = self.filesystem().read_file(path)?;
+        let exprs = parse::parse_all(&text)
+            .map_err(|error| LoadError(path.into(), Box::new(error.into())))?;
+        match fold_case {
+            true => Ok(exprs.iter().map(fold_identifiers).collect()),
+            false => Ok(exprs),
+        }
+    }
+
+    /// Load Thunk
+    ///
+    /// Return thunk, an expression read from the file at path, such that an
+    /// error it raises is reported as a LoadError naming the file like
+    /// Vm::load_source does. This is only done if there is no exception
+    /// handler: a handler is applied to the error as it was raised.
+    ///
+    /// # Arguments
+    /// `path` - The path of the file the expression was read from
+    /// `thunk` - The compiled expression
+    pub fn load_thunk(&mut self, path: &str, thunk: VCell) -> Result<VCell, Error> {
+        if !self.handlers.is_nil() {
+            return Ok(thunk);
+        }
+        let path = self.heap.put(VCell::string(path));
+        let obj = self.heap.put(VCell::symbol("obj"));
+        let load_error = self.heap.put(VCell::builtin("load", load_error));
+
+        let mut handler = Lambda::new(vec![obj]);
+        handler.emit(OpCode::Enter);
+        handler.emit(OpCode::PushImmediate);
+        handler.emit(path);
+        handler.emit(OpCode::Push);
+        handler.emit(VCell::BasePointerOffset(0));
+        handler.emit(OpCode::PushImmediate);
+        handler.emit(VCell::ArgumentCount(2));
+        handler.emit(OpCode::MovImmediate);
+        handler.emit(load_error);
+        handler.emit(VCell::Acc);
+        handler.emit(OpCode::CallAcc);
+        handler.emit(OpCode::Ret);
+        let handler = self.heap.put(handler);
+        self.build_with_exception_handler(handler, thunk)
+    }
+
+    /// Read Include
+    ///
+    /// Read each file named by an include or include-ci expression,
+    /// returning every expression they contain in order.
+    ///
+    /// # Arguments
+    /// `expr` - (include string ...) or (include-ci string ...)
+    pub fn read_include(&self, expr: &Cell) -> Result<Vec<Cell>, Error> {
+        let (paths, fold_case) = include_paths(expr)?;
+        let mut exprs = vec![];
+        for path in paths {
+            exprs.extend(self.read_source(&path, fold_case)?);
+        }
+        Ok(exprs)
+    }
+}
+
+/// Load Error
+///
+/// The builtin applied by the exception handler built by Vm::load_thunk to
+/// the path of the file and the object raised, which reports the object as
+/// a LoadError.
+fn load_error(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "load")?;
+    let obj = vm.stack.pop()?.clone();
+    let path = vm.heap.get(vm.stack.pop()?);
+    let path = path.as_string()?.borrow().clone();
+    Err(LoadError(path, Box::new(vm.uncaught_exception(&obj))))
+}
+
+/// Include
+///
+/// The builtin a top level include or include-ci expression is compiled
+/// to, which is applied to the expression and the environment it was
+/// compiled against. The expressions contained in the files are evaluated
+/// in order, so that their definitions and macros are top level
+/// definitions in the environment.
+///
+/// Each file is read and evaluated in turn, so that an error is reported
+/// as a LoadError naming the file it occurred in.
+pub fn include(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "include")?;
+    let environment = vm.heap.get(vm.stack.pop()?).as_environment()?;
+    let expr = vm.stack.pop()?.clone();
+    let expr = vm.heap.get_as_cell(&expr);
+    include_paths(&expr)?;
+    let state = VCell::vector(vec![
+        vm.heap.put_cell(&expr),
+        VCell::Environment(environment),
+        VCell::number(0),
+    ]);
+    include_file(vm, state, VCell::Void)
+}
+
+/// Include File
+///
+/// Evaluate the next file named by an include expression, or return the
+/// value of the last expression included if there are none left. The state
+/// is a vector of the include expression, the environment and the index of
+/// the next file.
+///
+/// # Arguments
+/// `state` - The state of the include
+/// `value` - The value of the last expression included
+fn include_file(vm: &mut Vm, state: VCell, value: VCell) -> Result<VCell, Error> {
+    let include = state.as_vector()?;
+    let expr = vm.heap.get_as_cell(&include.get(0).unwrap());
+    let environment = vm.heap.get(include.get(1).unwrap()).as_environment()?;
+    let index = vm.heap.get(include.get(2).unwrap()).as_number()?.to_usize();
+    let index = index.unwrap();
+
+    let (paths, fold_case) = include_paths(&expr)?;
+    let path = match paths.get(index) {
+        Some(path) => path,
+        None => return Ok(value),
+    };
+    let exprs = vm.read_source(path, fold_case)?;
+    let exprs = vm.heap.put_cell(&Cell::new_list(exprs));
+    let state = VCell::vector(vec![
+        include.get(0).unwrap(),
+        include.get(1).unwrap(),
+        VCell::number(index as u64 + 1),
+    ]);
+    vm.eval_sequence(
+        exprs,
+        environment,
+        Some(path),
+        "include",
+        include_resume,
+        state,
+    )
+}
+
+/// Include Resume
+///
+/// Resume an include once a file has been evaluated, with the value of the
+/// last expression it contained.
+fn include_resume(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "include")?;
+    let value = vm.stack.pop()?.clone();
+    let state = vm.heap.get(vm.stack.pop()?);
+    include_file(vm, state, value)
+}
+
+/// Is Include
+///
+/// Return true if expr is an include or include-ci expression.
+///
+/// # Arguments
+/// `expr` - The expression to check
+pub fn is_include(expr: &Cell) -> bool {
+    matches!(
+        expr.car().and_then(|it| it.as_symbol()).map(unalias),
+        Some("include" | "include-ci")
+    )
+}
+
+/// Include Paths
+///
+/// Return the paths named by an include or include-ci expression, and
+/// whether the case of identifiers is folded.
+///
+/// # Arguments
+/// `expr` - (include string ...) or (include-ci string ...)
+fn include_paths(expr: &Cell) -> Result<(Vec<String>, bool), Error> {
+    let invalid = || InvalidSyntax(format!("invalid include {:#}", expr));
+    let fold_case = expr.car().and_then(|it| it.as_symbol()).map(unalias) == Some("include-ci");
+    let paths = expr.cdr().ok_or_else(invalid)?;
+    if !paths.is_pair() || !paths.is_list() {
+        return Err(invalid());
+    }
+    let paths = paths
+        .iter()
+        .map(|it| match it {
+            Cell::String(path) => Ok(path.clone()),
+            _ => Err(invalid()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((paths, fold_case))
+}
+
+/// Fold Identifiers
+///
+/// Return expr with each identifier folded to lower case.
+///
+/// # Arguments
+/// `expr` - The expression to fold
+fn fold_identifiers(expr: &Cell) -> Cell {
+    match expr {
+        Cell::Symbol(sym) => Cell::new_symbol(&sym.to_lowercase()),
+        Cell::Pair(car, cdr) => Cell::Pair(
+            Box::new(fold_identifiers(car)),
+            Box::new(fold_identifiers(cdr)),
+        ),
+        Cell::Vector(cells) => Cell::Vector(cells.iter().map(fold_identifiers).collect()),
+        _ => expr.clone(),
+    }
+}
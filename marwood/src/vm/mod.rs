@@ -1,28 +1,43 @@
 use crate::cell::Cell;
 use crate::error::Error;
-use crate::lex;
 use crate::parse;
-use crate::vm::environment::GlobalEnvironment;
+use crate::vm::environment::{Environment, GlobalEnvironment};
+use crate::vm::filesystem::{DeniedFilesystem, Filesystem, MemoryFilesystem};
 use crate::vm::heap::{Heap, HeapRef};
+use crate::vm::library::{FilesystemResolver, Library, LibraryResolver};
+use crate::vm::port::Port;
 use crate::vm::stack::Stack;
 use crate::vm::trace::StackTrace;
+use crate::vm::transform::SyntaxEnvironment;
 use crate::vm::vcell::VCell;
 use log::trace;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::rc::Rc;
 
 pub mod builtin;
+pub mod callback;
 pub mod compare;
 pub mod compile;
+pub mod condition;
 pub mod continuation;
 pub mod environment;
+pub mod exception;
+pub mod filesystem;
 pub mod gc;
 pub mod heap;
 pub mod lambda;
+pub mod library;
+pub mod load;
 pub mod opcode;
+pub mod port;
+pub mod promise;
+pub mod record;
 pub mod run;
 pub mod stack;
 pub mod trace;
 pub mod transform;
+pub mod values;
 pub mod vcell;
 pub mod vector;
 
@@ -43,6 +58,33 @@ pub struct Vm {
     ip: (HeapRef, usize),
     bp: usize,
 
+    /// The dynamic-wind list, a list of (before . after) frames with the
+    /// innermost frame first
+    wind: VCell,
+
+    /// The exception handler stack, a list of handler procedures with the
+    /// innermost handler first
+    handlers: VCell,
+
+    /// The number of macro expansions, which gives the aliases introduced
+    /// by each expansion a unique name
+    expansions: usize,
+
+    /// The macros bound by let-syntax, letrec-syntax and internal
+    /// define-syntax while an expression is being transformed
+    syntax_env: SyntaxEnvironment,
+
+    /// The libraries defined by define-library or built into the VM, the
+    /// resolvers that supply the source of libraries that aren't yet
+    /// defined, and the libraries whose source is being evaluated
+    libraries: HashMap<Vec<String>, Library>,
+    library_resolvers: Vec<Box<dyn LibraryResolver>>,
+    loading_libraries: HashSet<Vec<String>>,
+
+    /// The ports used by input and output procedures when no port is given
+    input_port: VCell,
+    output_port: VCell,
+
     /// System Interface (display, write, etc).
     sys: Box<dyn SystemInterface>,
 
@@ -63,11 +105,25 @@ impl Vm {
             ep: usize::MAX,
             acc: VCell::undefined(),
             bp: 0,
-            sys: Box::new(StubInterface {}),
+            wind: VCell::Nil,
+            handlers: VCell::Nil,
+            expansions: 0,
+            syntax_env: SyntaxEnvironment::default(),
+            libraries: HashMap::new(),
+            library_resolvers: vec![Box::new(FilesystemResolver::default())],
+            loading_libraries: HashSet::new(),
+            input_port: VCell::Nil,
+            output_port: VCell::Nil,
+            sys: Box::new(StubInterface::default()),
             last_stacktrace: None,
         };
+        let console = vm.heap.put(VCell::Port(Rc::new(Port::Console)));
+        vm.input_port = console.clone();
+        vm.output_port = console;
         vm.load_builtins();
         vm.load_prelude();
+        vm.load_standard_libraries();
+        vm.globenv.save_report_environment();
         vm
     }
 
@@ -76,12 +132,8 @@ impl Vm {
     /// Read and compile prelude.scm
     pub fn load_prelude(&mut self) {
         let prelude_text = include_str!("../../prelude.scm");
-        let prelude_tokens = lex::scan(prelude_text).expect("invalid prelude");
-        let mut it = prelude_tokens.iter().peekable();
-        while it.peek().is_some() {
-            let ast = parse::parse(prelude_text, &mut it).expect("invalid prelude");
-            self.eval(&ast).expect("invalid prelude");
-        }
+        self.load_source("prelude.scm", prelude_text)
+            .expect("invalid prelude");
     }
 
     /// Eval
@@ -96,12 +148,117 @@ impl Vm {
         self.run()
     }
 
+    /// Eval In
+    ///
+    /// Compile the expression contained within cell against the given
+    /// environment, eval, and return the result.
+    ///
+    /// # Arguments
+    /// `cell` - An expression to evaluate
+    /// `environment` - The environment to evaluate the expression in
+    pub fn eval_in(&mut self, cell: &Cell, environment: &Rc<Environment>) -> Result<Cell, Error> {
+        self.with_environment(environment.clone(), |vm| vm.prepare_eval(cell))?;
+        self.run()
+    }
+
+    /// With Environment
+    ///
+    /// Apply f with expressions compiled against the given environment,
+    /// restoring the previous environment before returning.
+    ///
+    /// # Arguments
+    /// `environment` - The environment to compile against
+    /// `f` - The function to apply
+    pub fn with_environment<T>(
+        &mut self,
+        environment: Rc<Environment>,
+        f: impl FnOnce(&mut Vm) -> T,
+    ) -> T {
+        let previous = self.globenv.set_environment(environment);
+        let result = f(self);
+        self.globenv.set_environment(previous);
+        result
+    }
+
+    /// Interaction Environment
+    ///
+    /// Return the environment expressions are evaluated in by default.
+    pub fn interaction_environment(&self) -> Rc<Environment> {
+        self.globenv.interaction_environment()
+    }
+
+    /// Make Environment
+    ///
+    /// Return a new environment containing the standard syntax and only
+    /// the given bindings of the interaction environment.
+    ///
+    /// # Arguments
+    /// `symbols` - The symbols to copy from the interaction environment
+    pub fn make_environment(&mut self, symbols: &[&str]) -> Result<Rc<Environment>, Error> {
+        let symbols = symbols
+            .iter()
+            .map(|sym| self.heap.put(VCell::symbol(*sym)).as_ptr())
+            .collect::<Result<Vec<_>, _>>()?;
+        let interaction = self.globenv.interaction_environment();
+        self.copy_environment(&interaction, Some(&symbols))
+    }
+
+    /// Copy Environment
+    ///
+    /// Return a new environment containing the bindings of environment,
+    /// or the standard syntax and only the given bindings of environment.
+    /// The new environment is isolated: definitions and assignments made
+    /// in either environment are not visible in the other.
+    ///
+    /// # Arguments
+    /// `environment` - The environment to copy
+    /// `symbols` - The symbols to copy, or None to copy every binding
+    pub fn copy_environment(
+        &mut self,
+        environment: &Environment,
+        symbols: Option<&[usize]>,
+    ) -> Result<Rc<Environment>, Error> {
+        let copy = match symbols {
+            Some(_) => self.null_environment(),
+            None => Rc::new(Environment::derived()),
+        };
+        match self.globenv.copy(environment, symbols, &copy) {
+            Ok(()) => Ok(copy),
+            Err(sym) => Err(Error::VariableNotBound(
+                self.heap.get_at_index(sym).as_symbol()?.into(),
+            )),
+        }
+    }
+
+    /// Null Environment
+    ///
+    /// Return a new environment containing only the standard syntax.
+    pub fn null_environment(&mut self) -> Rc<Environment> {
+        let report = self.globenv.report_environment();
+        let symbols = report
+            .symbols()
+            .into_iter()
+            .filter(|sym| {
+                let slot = report.get(*sym).unwrap();
+                self.heap.get(self.globenv.get_slot(slot)).is_macro()
+            })
+            .collect::<Vec<_>>();
+        let environment = Rc::new(Environment::derived());
+        self.globenv
+            .copy(&report, Some(&symbols), &environment)
+            .unwrap();
+        environment
+    }
+
     pub fn prepare_eval(&mut self, cell: &Cell) -> Result<(), Error> {
         let lambda = self.compile_runnable(cell)?;
         trace!("entry: \n{}", self.decompile_text(&lambda));
         let lambda = self.heap.put(lambda);
         self.ip.0 = lambda.as_ptr().unwrap();
         self.ip.1 = 0;
+        self.wind = VCell::Nil;
+        self.handlers = VCell::Nil;
+        self.loading_libraries.clear();
         Ok(())
     }
 
@@ -142,10 +299,16 @@ impl Vm {
         self.sys.time_utc()
     }
 
+    pub fn filesystem(&self) -> &dyn Filesystem {
+        self.sys.filesystem()
+    }
+
     pub fn global_symbols(&self) -> Vec<&str> {
         self.globenv
-            .iter_bindings()
-            .map(|sym| self.heap.get_at_index(*sym).as_symbol().unwrap())
+            .interaction_environment()
+            .symbols()
+            .into_iter()
+            .map(|sym| self.heap.get_at_index(sym).as_symbol().unwrap())
             .collect()
     }
 
@@ -167,10 +330,21 @@ pub trait SystemInterface: Debug {
     fn write(&self, cell: &Cell);
     fn terminal_dimensions(&self) -> (usize, usize);
     fn time_utc(&self) -> u64;
+
+    /// The filesystem used by file ports and the file procedures, which
+    /// denies all access unless the embedder provides one.
+    fn filesystem(&self) -> &dyn Filesystem {
+        static DENIED: DeniedFilesystem = DeniedFilesystem {};
+        &DENIED
+    }
 }
 
-#[derive(Debug)]
-struct StubInterface {}
+/// The SystemInterface used until one is set by the embedder, which
+/// discards output and provides an in-memory filesystem.
+#[derive(Debug, Default)]
+struct StubInterface {
+    filesystem: MemoryFilesystem,
+}
 impl SystemInterface for StubInterface {
     fn display(&self, _: &Cell) {}
     fn write(&self, _: &Cell) {}
@@ -180,4 +354,7 @@ impl SystemInterface for StubInterface {
     fn time_utc(&self) -> u64 {
         0
     }
+    fn filesystem(&self) -> &dyn Filesystem {
+        &self.filesystem
+    }
 }
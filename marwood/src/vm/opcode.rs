@@ -12,21 +12,25 @@ use std::iter::Peekable;
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub enum OpCode {
     // VM Primitives
+    Append,
     Cons,
     Jmp,
     Jnt,
     Mov,
     MovImmediate,
+    PopAcc,
     Push,
     PushAcc,
     PushImmediate,
     Halt,
-    VPushAcc,
+    VectorAcc,
 
     // Procedure Application
     CallAcc,
+    CaseLambda,
     ClosureAcc,
     Enter,
+    Rebind,
     Ret,
     TCallAcc,
     VarArg,
@@ -94,7 +98,9 @@ impl Schema {
 fn schema() -> &'static HashMap<OpCode, Schema> {
     lazy_static! {
         static ref SCHEMA: HashMap<OpCode, Schema> = HashMap::from([
+            (OpCode::Append, Schema::new("APPEND", vec![])),
             (OpCode::CallAcc, Schema::new("CALL", vec![Operand::Acc])),
+            (OpCode::CaseLambda, Schema::new("CASELAMBDA", vec![Operand::Immediate])),
             (OpCode::ClosureAcc, Schema::new("CLOSURE", vec![Operand::Acc])),
             (OpCode::Cons, Schema::new("CONS", vec![])),
             (OpCode::Enter, Schema::new("ENTER", vec![])),
@@ -103,12 +109,14 @@ fn schema() -> &'static HashMap<OpCode, Schema> {
             (OpCode::Jnt, Schema::new("JNT", vec![Operand::Immediate])),
             (OpCode::Mov, Schema::new("MOV", vec![Operand::LoadReference, Operand::StoreReference])),
             (OpCode::MovImmediate, Schema::new("MOV", vec![Operand::Immediate, Operand::StoreReference])),
+            (OpCode::PopAcc, Schema::new("POP", vec![Operand::Acc])),
             (OpCode::Push, Schema::new("PUSH", vec![Operand::LoadReference])),
             (OpCode::PushImmediate, Schema::new("PUSH", vec![Operand::Immediate])),
             (OpCode::PushAcc, Schema::new("PUSH", vec![Operand::Acc])),
+            (OpCode::Rebind, Schema::new("REBIND", vec![])),
             (OpCode::Ret, Schema::new("RET", vec![])),
             (OpCode::TCallAcc, Schema::new("TCALL", vec![Operand::Acc])),
-            (OpCode::VPushAcc, Schema::new("VPUSH", vec![])),
+            (OpCode::VectorAcc, Schema::new("VECTOR", vec![Operand::Acc])),
             (OpCode::VarArg, Schema::new("VARARG", vec![]))
         ]);
     }
@@ -0,0 +1,299 @@
+use crate::cell::Cell;
+use crate::error::Error;
+use crate::error::Error::InvalidSyntax;
+use crate::lex;
+use crate::parse;
+use crate::vm::filesystem::file_error_from_io;
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+use std::io::Write;
+
+/// Port
+///
+/// A port is a source of characters for input procedures such as
+/// read-char, or a sink of characters for output procedures such as
+/// display.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Port {
+    /// The console, which is written to through the SystemInterface. The
+    /// console has no input, and reading from it results in eof.
+    Console,
+
+    /// A string input port, which reads from its text starting at the
+    /// byte offset of the next character to be read. File input ports are
+    /// string input ports over the contents of the file. Closing the port
+    /// moves it to the end of its text, so that reading from it results in
+    /// eof.
+    StringInput(String, RefCell<usize>),
+
+    /// A string output port, which accumulates the characters written to it
+    StringOutput(RefCell<String>),
+
+    /// A file output port, which writes the characters written to it to
+    /// the file at its path through a handle opened by the filesystem
+    FileOutput(String, OutputHandle),
+}
+
+/// Output Handle
+///
+/// The handle a file output port writes to, which is released when the
+/// port is closed.
+pub struct OutputHandle(RefCell<Option<Box<dyn Write>>>);
+
+impl OutputHandle {
+    /// Write
+    ///
+    /// Write text to the handle, returning an error if the port has been
+    /// closed.
+    ///
+    /// # Arguments
+    /// `path` - The path of the file, used in error messages
+    /// `text` - The text to write
+    fn write(&self, path: &str, text: &str) -> Result<(), Error> {
+        match self.0.borrow_mut().as_mut() {
+            Some(handle) => handle
+                .write_all(text.as_bytes())
+                .map_err(|e| file_error_from_io(path, e)),
+            None => Err(InvalidSyntax("port is closed".into())),
+        }
+    }
+
+    /// Close
+    ///
+    /// Flush and release the handle. Closing a closed handle has no effect.
+    ///
+    /// # Arguments
+    /// `path` - The path of the file, used in error messages
+    fn close(&self, path: &str) -> Result<(), Error> {
+        match self.0.borrow_mut().take() {
+            Some(mut handle) => handle.flush().map_err(|e| file_error_from_io(path, e)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Debug for OutputHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0.borrow().is_some() {
+            true => write!(f, "OutputHandle"),
+            false => write!(f, "OutputHandle(closed)"),
+        }
+    }
+}
+
+impl PartialEq for OutputHandle {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Eq for OutputHandle {}
+
+impl Port {
+    pub fn new_input_string<T: Into<String>>(text: T) -> Port {
+        Port::StringInput(text.into(), RefCell::new(0))
+    }
+
+    pub fn new_output_string() -> Port {
+        Port::StringOutput(RefCell::new(String::new()))
+    }
+
+    pub fn new_output_file<T: Into<String>>(path: T, handle: Box<dyn Write>) -> Port {
+        Port::FileOutput(path.into(), OutputHandle(RefCell::new(Some(handle))))
+    }
+
+    pub fn is_input(&self) -> bool {
+        matches!(self, Port::Console | Port::StringInput(_, _))
+    }
+
+    pub fn is_output(&self) -> bool {
+        matches!(
+            self,
+            Port::Console | Port::StringOutput(_) | Port::FileOutput(_, _)
+        )
+    }
+
+    /// Close
+    ///
+    /// Close the port, releasing the file handle of a file output port.
+    /// Closing the console or a string output port has no effect, and
+    /// closing a closed port has no effect.
+    pub fn close(&self) -> Result<(), Error> {
+        match self {
+            Port::StringInput(text, pos) => {
+                *pos.borrow_mut() = text.len();
+                Ok(())
+            }
+            Port::FileOutput(path, handle) => handle.close(path),
+            Port::Console | Port::StringOutput(_) => Ok(()),
+        }
+    }
+
+    /// Remaining
+    ///
+    /// Return the text that has yet to be read from an input port
+    fn remaining(&self) -> &str {
+        match self {
+            Port::StringInput(text, pos) => &text[*pos.borrow()..],
+            _ => "",
+        }
+    }
+
+    /// Advance
+    ///
+    /// Advance an input port past len bytes of its remaining text
+    fn advance(&self, len: usize) {
+        if let Port::StringInput(_, pos) = self {
+            *pos.borrow_mut() += len;
+        }
+    }
+
+    /// Peek Char
+    ///
+    /// Return the next character available from the port without
+    /// consuming it, or None if the port is at eof.
+    pub fn peek_char(&self) -> Option<char> {
+        self.remaining().chars().next()
+    }
+
+    /// Read Char
+    ///
+    /// Consume and return the next character available from the port, or
+    /// None if the port is at eof.
+    pub fn read_char(&self) -> Option<char> {
+        let c = self.peek_char()?;
+        self.advance(c.len_utf8());
+        Some(c)
+    }
+
+    /// Read Line
+    ///
+    /// Consume and return the text up to the next end of line, which is
+    /// consumed but not returned. None is returned if the port is at eof.
+    pub fn read_line(&self) -> Option<String> {
+        let remaining = self.remaining();
+        if remaining.is_empty() {
+            return None;
+        }
+        let line = match remaining.find('\n') {
+            Some(end) => {
+                self.advance(end + 1);
+                &remaining[..end]
+            }
+            None => {
+                self.advance(remaining.len());
+                remaining
+            }
+        };
+        Some(line.strip_suffix('\r').unwrap_or(line).to_string())
+    }
+
+    /// Read String
+    ///
+    /// Consume and return up to k characters from the port. None is
+    /// returned if the port is at eof.
+    pub fn read_string(&self, k: usize) -> Option<String> {
+        let remaining = self.remaining();
+        if remaining.is_empty() && k > 0 {
+            return None;
+        }
+        let end = remaining
+            .char_indices()
+            .nth(k)
+            .map(|it| it.0)
+            .unwrap_or(remaining.len());
+        self.advance(end);
+        Some(remaining[..end].to_string())
+    }
+
+    /// Read
+    ///
+    /// Consume and return the next datum available from the port, or None
+    /// if the port is at eof. Only the text of the next datum is scanned,
+    /// so errors in text following it are reported by a later read. If the
+    /// datum can't be parsed the remaining text is discarded, so that a
+    /// subsequent read results in eof instead of the same error.
+    pub fn read(&self) -> Result<Option<Cell>, Error> {
+        let remaining = self.remaining();
+        let tokens = match lex::scan_datum(remaining) {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                self.advance(remaining.len());
+                return Err(e.into());
+            }
+        };
+        if tokens.is_empty() {
+            self.advance(remaining.len());
+            return Ok(None);
+        }
+        let mut cur = tokens.iter().peekable();
+        match parse::parse(remaining, &mut cur) {
+            Ok(cell) => {
+                let unread = cur.count();
+                self.advance(tokens[tokens.len() - unread - 1].span.1);
+                Ok(Some(cell))
+            }
+            Err(e) => {
+                self.advance(remaining.len());
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Output String
+    ///
+    /// Return the characters accumulated by a string output port
+    pub fn output_string(&self) -> Option<String> {
+        match self {
+            Port::StringOutput(output) => Some(output.borrow().clone()),
+            _ => None,
+        }
+    }
+}
+
+impl Vm {
+    /// Write Port
+    ///
+    /// Write the cell to the output port. The cell is written in its
+    /// external representation if write is true, or displayed otherwise.
+    ///
+    /// # Arguments
+    /// `port` - The output port to write to
+    /// `cell` - The cell to write
+    /// `write` - True to write the cell, false to display it
+    pub fn write_port(&self, port: &Port, cell: &Cell, write: bool) -> Result<(), Error> {
+        match port {
+            Port::Console if write => self.write(cell),
+            Port::Console => self.display(cell),
+            Port::StringOutput(output) if write => {
+                output.borrow_mut().push_str(&format!("{:#}", cell))
+            }
+            Port::StringOutput(output) => output.borrow_mut().push_str(&format!("{}", cell)),
+            Port::FileOutput(path, handle) if write => {
+                handle.write(path, &format!("{:#}", cell))?
+            }
+            Port::FileOutput(path, handle) => handle.write(path, &format!("{}", cell))?,
+            Port::StringInput(_, _) => {
+                return Err(InvalidSyntax("port is not an output port".into()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Current Output Port
+    ///
+    /// Return the port written to by output procedures when no port is
+    /// given.
+    pub fn current_output_port(&self) -> VCell {
+        self.output_port.clone()
+    }
+
+    /// Current Input Port
+    ///
+    /// Return the port read from by input procedures when no port is given.
+    pub fn current_input_port(&self) -> VCell {
+        self.input_port.clone()
+    }
+}
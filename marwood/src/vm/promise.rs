@@ -0,0 +1,92 @@
+use crate::vm::vcell::VCell;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Promise
+///
+/// A promise is created by delay, delay-force or make-promise. The state of
+/// a promise is held in a box that may be shared by more than one promise:
+/// when forcing a delay-force promise results in another promise, the
+/// promise adopts the other promise's state, and the other promise then
+/// shares its box. This allows force to iterate through a chain of
+/// delay-force promises in constant space, as required by R7RS.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Promise {
+    state: RefCell<Rc<RefCell<PromiseState>>>,
+}
+
+/// Promise State
+///
+/// A forced promise holds its value, and an unforced promise holds the
+/// procedure that computes it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct PromiseState {
+    done: bool,
+    value: VCell,
+}
+
+impl Promise {
+    /// New
+    ///
+    /// Create a new promise
+    ///
+    /// # Arguments
+    /// `done` - True if value is the result of the promise, or false if value is
+    ///          the procedure that computes it
+    /// `value` - The value or procedure
+    pub fn new(done: bool, value: VCell) -> Promise {
+        Promise {
+            state: RefCell::new(Rc::new(RefCell::new(PromiseState { done, value }))),
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.state.borrow().borrow().done
+    }
+
+    /// Value
+    ///
+    /// Return the value of a forced promise, or the procedure that computes
+    /// the value of an unforced promise.
+    pub fn value(&self) -> VCell {
+        self.state.borrow().borrow().value.clone()
+    }
+
+    /// Update
+    ///
+    /// Replace the state of this promise with the state of other, and then
+    /// share this promise's state with other.
+    ///
+    /// # Arguments
+    /// `other` - The promise to adopt the state of
+    pub fn update(&self, other: &Promise) {
+        let state = other.state.borrow().borrow().clone();
+        *self.state.borrow().borrow_mut() = state;
+        let shared = self.state.borrow().clone();
+        *other.state.borrow_mut() = shared;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_shares_state() {
+        let promise = Promise::new(false, VCell::ptr(1));
+        let other = Promise::new(false, VCell::ptr(2));
+        promise.update(&other);
+        assert!(!promise.is_done());
+        assert_eq!(promise.value(), VCell::ptr(2));
+
+        *other.state.borrow().borrow_mut() = PromiseState {
+            done: true,
+            value: VCell::ptr(3),
+        };
+        assert!(promise.is_done());
+        assert_eq!(promise.value(), VCell::ptr(3));
+
+        promise.update(&promise);
+        assert_eq!(promise.value(), VCell::ptr(3));
+    }
+}
@@ -0,0 +1,339 @@
+use crate::cell::Cell;
+use crate::error::Error;
+use crate::error::Error::{InvalidArgs, InvalidSyntax};
+use crate::vm::builtin::pop_argc;
+use crate::vm::lambda::Lambda;
+use crate::vm::opcode::OpCode;
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Record Type
+///
+/// A record type is the record-type descriptor created by define-record-type.
+/// It holds the name of the type and the names of its fields, in the order
+/// they were defined.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordType {
+    name: String,
+    fields: Vec<String>,
+}
+
+impl RecordType {
+    pub fn new(name: String, fields: Vec<String>) -> RecordType {
+        RecordType { name, fields }
+    }
+
+    /// Name
+    ///
+    /// Return the name of the type, without any surrounding angle brackets,
+    /// such that <point> is named point.
+    pub fn name(&self) -> &str {
+        self.name
+            .strip_prefix('<')
+            .and_then(|it| it.strip_suffix('>'))
+            .unwrap_or(&self.name)
+    }
+
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+
+    pub fn field_index(&self, field: &str) -> Option<usize> {
+        self.fields.iter().position(|it| it == field)
+    }
+}
+
+/// Record
+///
+/// A record is an instance of a record type, holding a value for each of
+/// the type's fields.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Record {
+    record_type: Rc<RecordType>,
+    fields: RefCell<Vec<VCell>>,
+}
+
+impl Record {
+    pub fn new(record_type: Rc<RecordType>, fields: Vec<VCell>) -> Record {
+        Record {
+            record_type,
+            fields: RefCell::new(fields),
+        }
+    }
+
+    pub fn record_type(&self) -> &Rc<RecordType> {
+        &self.record_type
+    }
+
+    /// Is Instance
+    ///
+    /// Return true if this record is an instance of the record type
+    ///
+    /// # Arguments
+    /// `record_type` - The record type to compare against
+    pub fn is_instance(&self, record_type: &Rc<RecordType>) -> bool {
+        Rc::ptr_eq(&self.record_type, record_type)
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<VCell> {
+        self.fields.borrow().get(index).cloned()
+    }
+
+    pub fn put(&self, index: usize, value: VCell) {
+        if let Some(field) = self.fields.borrow_mut().get_mut(index) {
+            *field = value;
+        }
+    }
+}
+
+impl Vm {
+    /// Build Record Procedure
+    ///
+    /// Build a procedure that applies builtin to the given immediate values
+    /// followed by each of the procedure's own arguments. This is used to
+    /// build the constructor, predicate, accessors and modifiers of a record
+    /// type.
+    ///
+    /// # Arguments
+    /// `args` - The names of the procedure's arguments
+    /// `immediates` - The values applied to builtin before the arguments
+    /// `builtin` - The builtin the procedure applies
+    fn build_record_procedure(
+        &mut self,
+        args: &[String],
+        immediates: &[VCell],
+        builtin: VCell,
+    ) -> Result<VCell, Error> {
+        let mut lambda = Lambda::new(
+            args.iter()
+                .map(|it| self.heap.put(VCell::symbol(it.as_str())))
+                .collect(),
+        );
+        lambda.set_desc(Cell::new_list(args.iter().map(|it| Cell::new_symbol(it))));
+        lambda.emit(OpCode::Enter);
+        for immediate in immediates {
+            lambda.emit(OpCode::PushImmediate);
+            lambda.emit(immediate.clone());
+        }
+        for n in 0..args.len() {
+            lambda.emit(OpCode::Push);
+            lambda.emit(VCell::BasePointerOffset(n as i64 + 1 - args.len() as i64));
+        }
+        lambda.emit(OpCode::PushImmediate);
+        lambda.emit(VCell::ArgumentCount(immediates.len() + args.len()));
+        lambda.emit(OpCode::MovImmediate);
+        lambda.emit(self.heap.put(builtin));
+        lambda.emit(VCell::Acc);
+        lambda.emit(OpCode::CallAcc);
+        lambda.emit(OpCode::Ret);
+        Ok(self.heap.put(lambda))
+    }
+}
+
+/// Pop Record Type
+///
+/// Pop a record-type descriptor off the stack, returning both the
+/// reference to it on the heap and the record type.
+fn pop_record_type(vm: &mut Vm) -> Result<(VCell, Rc<RecordType>), Error> {
+    let rtd = vm.heap.put(vm.stack.pop()?.clone());
+    match vm.heap.get(&rtd) {
+        VCell::RecordType(record_type) => Ok((rtd, record_type)),
+        vcell => Err(InvalidSyntax(format!(
+            "{:#} is not a record type",
+            vm.heap.get_as_cell(&vcell)
+        ))),
+    }
+}
+
+/// Pop Symbols
+///
+/// Pop a list of symbols off the stack
+fn pop_symbols(vm: &mut Vm) -> Result<Vec<String>, Error> {
+    let mut symbols = vec![];
+    let mut list = vm.heap.get(vm.stack.pop()?);
+    while let VCell::Pair(car, cdr) = list {
+        symbols.push(vm.heap.get_at_index(car).as_symbol()?.to_string());
+        list = vm.heap.get_at_index(cdr).clone();
+    }
+    Ok(symbols)
+}
+
+/// Field Index
+///
+/// Return the index of field in record_type as a number
+///
+/// # Arguments
+/// `record_type` - The record type the field belongs to
+/// `field` - The name of the field
+fn field_index(record_type: &RecordType, field: &str) -> Result<VCell, Error> {
+    match record_type.field_index(field) {
+        Some(index) => Ok(VCell::number(index as i64)),
+        None => Err(InvalidSyntax(format!(
+            "{} is not a field of record type {}",
+            field,
+            record_type.name()
+        ))),
+    }
+}
+
+/// Make Record Type
+///
+/// Create a record-type descriptor given the type name and the list of
+/// field names on the stack.
+pub fn make_record_type(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "define-record-type")?;
+    let fields = pop_symbols(vm)?;
+    let name = vm.heap.get(vm.stack.pop()?).as_symbol()?.to_string();
+    Ok(vm
+        .heap
+        .put(VCell::RecordType(Rc::new(RecordType::new(name, fields)))))
+}
+
+/// Record Constructor
+///
+/// Build the constructor for the record type and list of fields on the
+/// stack. The constructor's arguments initialize the listed fields, and any
+/// other field is initialized to #f.
+pub fn record_constructor(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "define-record-type")?;
+    let args = pop_symbols(vm)?;
+    let (rtd, record_type) = pop_record_type(vm)?;
+    let mut immediates = vec![rtd];
+    for arg in &args {
+        immediates.push(field_index(&record_type, arg)?);
+    }
+    let builtin = VCell::builtin("record", make_record);
+    vm.build_record_procedure(&args, &immediates, builtin)
+}
+
+/// Record Predicate
+///
+/// Build the predicate for the record type on the stack
+pub fn record_predicate(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 1, Some(1), "define-record-type")?;
+    let (rtd, _) = pop_record_type(vm)?;
+    let builtin = VCell::builtin("record?", is_record);
+    vm.build_record_procedure(&["obj".into()], &[rtd], builtin)
+}
+
+/// Record Accessor
+///
+/// Build the accessor for the record type, field and accessor name on the
+/// stack.
+pub fn record_accessor(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 3, Some(3), "define-record-type")?;
+    let name = vm.heap.put(vm.stack.pop()?.clone());
+    let field = vm.heap.get(vm.stack.pop()?).as_symbol()?.to_string();
+    let (rtd, record_type) = pop_record_type(vm)?;
+    let index = field_index(&record_type, &field)?;
+    let builtin = VCell::builtin("record-ref", record_ref);
+    vm.build_record_procedure(&["record".into()], &[name, rtd, index], builtin)
+}
+
+/// Record Modifier
+///
+/// Build the modifier for the record type, field and modifier name on the
+/// stack.
+pub fn record_modifier(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 3, Some(3), "define-record-type")?;
+    let name = vm.heap.put(vm.stack.pop()?.clone());
+    let field = vm.heap.get(vm.stack.pop()?).as_symbol()?.to_string();
+    let (rtd, record_type) = pop_record_type(vm)?;
+    let index = field_index(&record_type, &field)?;
+    let builtin = VCell::builtin("record-set!", record_set);
+    let args = ["record".into(), "value".into()];
+    vm.build_record_procedure(&args, &[name, rtd, index], builtin)
+}
+
+/// Make Record
+///
+/// Create a record given its record type, the index of each field being
+/// initialized, and the value of each of those fields on the stack.
+fn make_record(vm: &mut Vm) -> Result<VCell, Error> {
+    let argc = pop_argc(vm, 1, None, "record")?;
+    let n = (argc - 1) / 2;
+    let mut values = vec![];
+    for _ in 0..n {
+        values.push(vm.heap.put(vm.stack.pop()?.clone()));
+    }
+    let mut indices = vec![];
+    for _ in 0..n {
+        indices.push(vm.stack.pop()?.as_number()?.to_usize().unwrap_or(0));
+    }
+    let (_, record_type) = pop_record_type(vm)?;
+
+    let mut fields = vec![VCell::Bool(false); record_type.fields().len()];
+    for (index, value) in indices.into_iter().zip(values) {
+        if let Some(field) = fields.get_mut(index) {
+            *field = value;
+        }
+    }
+    Ok(vm
+        .heap
+        .put(VCell::Record(Rc::new(Record::new(record_type, fields)))))
+}
+
+/// Is Record
+///
+/// Return true if the object on the stack is a record of the record type
+/// beneath it.
+fn is_record(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "record?")?;
+    let obj = vm.heap.get(vm.stack.pop()?);
+    let (_, record_type) = pop_record_type(vm)?;
+    Ok(match obj {
+        VCell::Record(record) => record.is_instance(&record_type),
+        _ => false,
+    }
+    .into())
+}
+
+/// Pop Record
+///
+/// Pop the record, field index, record type and procedure name applied by
+/// an accessor or modifier off the stack. An error naming the procedure is
+/// returned if the record is not an instance of the record type.
+fn pop_record(vm: &mut Vm) -> Result<(Rc<Record>, usize), Error> {
+    let record = vm.heap.get(vm.stack.pop()?);
+    let index = vm.stack.pop()?.as_number()?.to_usize().unwrap_or(0);
+    let (_, record_type) = pop_record_type(vm)?;
+    let proc = vm.heap.get(vm.stack.pop()?);
+    match record {
+        VCell::Record(record) if record.is_instance(&record_type) => Ok((record, index)),
+        vcell => Err(InvalidArgs(
+            proc.as_symbol()?.into(),
+            record_type.name().into(),
+            format!("{:#}", vm.heap.get_as_cell(&vcell)),
+        )),
+    }
+}
+
+/// Record Ref
+///
+/// Return the value of a record's field
+fn record_ref(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 4, Some(4), "record-ref")?;
+    let (record, index) = pop_record(vm)?;
+    Ok(record.get(index).unwrap_or(VCell::Void))
+}
+
+/// Record Set
+///
+/// Set the value of a record's field
+fn record_set(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 5, Some(5), "record-set!")?;
+    let value = vm.heap.put(vm.stack.pop()?.clone());
+    let (record, index) = pop_record(vm)?;
+    record.put(index, value);
+    Ok(VCell::Void)
+}
@@ -1,8 +1,10 @@
 use crate::cell::Cell;
 use crate::error::Error;
 use crate::error::Error::{
-    InvalidBytecode, InvalidNumArgs, InvalidProcedure, InvalidSyntax, VariableNotBound,
+    ExpectedPairButFound, InvalidBytecode, InvalidNumArgs, InvalidProcedure, InvalidSyntax,
+    VariableNotBound,
 };
+use crate::vm::continuation::Continuation;
 use crate::vm::environment::{BindingSource, EnvironmentMap, LexicalEnvironment};
 use crate::vm::lambda::Lambda;
 use crate::vm::opcode::OpCode;
@@ -11,6 +13,7 @@ use crate::vm::vcell::VCell;
 use crate::vm::vcell::VCell::LexicalEnvPtr;
 use crate::vm::Vm;
 use log::trace;
+use std::collections::HashSet;
 use std::rc::Rc;
 
 impl Vm {
@@ -38,6 +41,15 @@ impl Vm {
                 Ok(true) => break,
                 Ok(false) => continue,
                 Err(e) => {
+                    // Runtime errors are raised as conditions to the current
+                    // exception handler, if there is one.
+                    let e = match self.handlers.is_nil() {
+                        true => e,
+                        false => match self.raise_error(e) {
+                            Ok(()) => continue,
+                            Err(e) => e,
+                        },
+                    };
                     self.last_stacktrace = Some(StackTrace::new(
                         &self.stack,
                         &self.heap,
@@ -94,6 +106,9 @@ impl Vm {
                 let vcell = self.read_operand()?;
                 self.stack.push(vcell);
             }
+            OpCode::PopAcc => {
+                self.acc = self.stack.pop()?.clone();
+            }
             OpCode::PushAcc => {
                 self.stack.push(self.acc.clone());
             }
@@ -109,13 +124,44 @@ impl Vm {
                 self.acc = self.heap.put(VCell::pair(car.as_ptr()?, cdr.as_ptr()?));
             }
 
-            // The VPushAcc opcode represents a primitive instruction for pushing an an element in
-            // %acc on to the vector at the top of the stack.
-            OpCode::VPushAcc => {
-                let vector_ptr = self.heap.get(self.stack.pop()?);
-                let vector = vector_ptr.as_vector()?;
-                vector.push(self.acc.clone());
-                self.acc = vector_ptr;
+            // The APPEND opcode represents a primitive version of the append procedure
+            // with two arguments, used by quasiquote to splice the list on the stack
+            // in front of the tail at the top of the stack.
+            OpCode::Append => {
+                let tail = self.heap.put(self.stack.pop()?.clone());
+                let list = self.stack.pop()?.clone();
+                let mut cars = vec![];
+                let mut rest = self.heap.get(&list);
+                while let VCell::Pair(car, cdr) = rest {
+                    cars.push(car);
+                    rest = self.heap.get(VCell::ptr(cdr));
+                }
+                if !rest.is_nil() {
+                    return Err(match cars.is_empty() {
+                        true => ExpectedPairButFound(self.heap.get_as_cell(&list)),
+                        false => InvalidSyntax(format!(
+                            "{:#} is an improper list",
+                            self.heap.get_as_cell(&list)
+                        )),
+                    });
+                }
+                let mut pair = tail.as_ptr()?;
+                for car in cars.into_iter().rev() {
+                    pair = self.heap.put(VCell::pair(car, pair)).as_ptr()?;
+                }
+                self.acc = VCell::ptr(pair);
+            }
+
+            // The VECTOR opcode represents a primitive version of list->vector, replacing
+            // the list in %acc with a new vector of its elements.
+            OpCode::VectorAcc => {
+                let mut outv = vec![];
+                let mut rest = self.heap.get(&self.acc);
+                while let VCell::Pair(car, cdr) = rest {
+                    outv.push(VCell::ptr(car));
+                    rest = self.heap.get(VCell::ptr(cdr));
+                }
+                self.acc = self.heap.put(VCell::vector(outv));
             }
 
             // Procedure Application
@@ -142,7 +188,16 @@ impl Vm {
                 let closure_ptr = self.heap.put(VCell::Closure(lambda_ptr, lexical_env_ptr));
                 self.acc = closure_ptr;
             }
+            OpCode::CaseLambda => {
+                let n = self.read_operand()?.as_argc()?;
+                let mut clauses = vec![0; n];
+                for it in (0..n).rev() {
+                    clauses[it] = self.stack.pop()?.as_ptr()?;
+                }
+                self.acc = self.heap.put(VCell::CaseLambda(Rc::new(clauses)));
+            }
             OpCode::CallAcc => {
+                self.select_case_lambda_clause()?;
                 let lambda = match self.heap.get(&self.acc) {
                     VCell::Closure(lambda, _) => lambda,
                     VCell::Lambda(_) => self.acc.as_ptr()?,
@@ -155,13 +210,7 @@ impl Vm {
                         return Ok(false);
                     }
                     VCell::Continuation(cont) => {
-                        let cont = &*cont;
-                        if self.stack.pop()?.as_argc()? == 0 {
-                            return Err(InvalidSyntax("expected value".into()));
-                        }
-                        let result = self.stack.pop()?.clone();
-                        self.restore_continuation(cont);
-                        self.acc = result;
+                        self.call_continuation(&cont)?;
                         return Ok(false);
                     }
                     other => {
@@ -175,6 +224,7 @@ impl Vm {
                 self.ip.1 = 0;
             }
             OpCode::TCallAcc => {
+                self.select_case_lambda_clause()?;
                 let lambda = match self.heap.get(&self.acc) {
                     VCell::Closure(lambda, _) => lambda,
                     VCell::Lambda(_) => self.acc.as_ptr()?,
@@ -187,13 +237,7 @@ impl Vm {
                         return Ok(false);
                     }
                     VCell::Continuation(cont) => {
-                        let cont = &*cont;
-                        if self.stack.pop()?.as_argc()? == 0 {
-                            return Err(InvalidSyntax("expected value".into()));
-                        }
-                        let result = self.stack.pop()?.clone();
-                        self.restore_continuation(cont);
-                        self.acc = result;
+                        self.call_continuation(&cont)?;
                         return Ok(false);
                     }
                     other => {
@@ -271,6 +315,18 @@ impl Vm {
                 self.ip = self.stack.get(self.bp + 3)?.as_ip()?;
                 self.bp = self.stack.get(self.bp + 4)?.as_bp()?;
             }
+            OpCode::Rebind => {
+                // REBIND gives the procedure being executed a new lexical environment,
+                // which is a copy of its current one with each argument bound to the
+                // value in its argument slot on the stack. This is used by a named let
+                // loop to begin each iteration with fresh bindings of its variables.
+                let env = self.heap.get_at_index(self.ep).as_lexical_env()?;
+                let lexical_env = self.build_lexical_environment(self.lambda(), self.ep, env)?;
+                self.ep = self
+                    .heap
+                    .put(VCell::LexicalEnv(Rc::new(lexical_env)))
+                    .as_ptr()?;
+            }
             OpCode::VarArg => {
                 // VARARG converts the optional arguments of a vararg procedure into a list.
                 // This requires popping off every optional argument, forming a list on the heap,
@@ -315,6 +371,68 @@ impl Vm {
         Ok(false)
     }
 
+    /// Call Continuation
+    ///
+    /// Apply the continuation in %acc to the arguments on the stack. A single
+    /// argument is delivered as is, and any other number of arguments are
+    /// delivered as multiple values. If the continuation was captured with a
+    /// different wind list, then instead apply a procedure that runs the before
+    /// and after procedures of each dynamic-wind frame crossed before applying
+    /// the continuation.
+    ///
+    /// # Arguments
+    /// `cont` - The continuation in %acc
+    fn call_continuation(&mut self, cont: &Continuation) -> Result<(), Error> {
+        let argc = self.stack.pop()?.as_argc()?;
+        let result = match argc {
+            1 => self.stack.pop()?.clone(),
+            _ => {
+                let mut values = vec![VCell::Void; argc];
+                for it in 0..argc {
+                    *values.get_mut(argc - it - 1).unwrap() = self.stack.pop()?.clone();
+                }
+                self.heap.put(VCell::values(values))
+            }
+        };
+        if self.wind == *cont.wind() {
+            self.restore_continuation(cont);
+            self.acc = result;
+        } else {
+            self.acc = self.build_rewind(self.acc.clone(), cont.wind().clone(), result)?;
+            self.stack.push(VCell::ArgumentCount(0));
+            self.ip.1 -= 1;
+        }
+        Ok(())
+    }
+
+    /// Select Case Lambda Clause
+    ///
+    /// If the procedure being applied in %acc is a case-lambda, replace it
+    /// with the closure of the first clause whose formals accept the number
+    /// of arguments on the stack.
+    fn select_case_lambda_clause(&mut self) -> Result<(), Error> {
+        let clauses = match self.heap.get(&self.acc) {
+            VCell::CaseLambda(clauses) => clauses,
+            _ => return Ok(()),
+        };
+        let argc = self.stack.get_offset(0)?.as_argc()?;
+        for clause in clauses.iter() {
+            let lambda = match self.heap.get_at_index(*clause) {
+                VCell::Closure(lambda, _) => self.heap.get_at_index(*lambda).as_lambda()?,
+                _ => return Err(InvalidBytecode),
+            };
+            let accepts = match lambda.is_vararg {
+                true => argc + 1 >= lambda.argc(),
+                false => argc == lambda.argc(),
+            };
+            if accepts {
+                self.acc = VCell::ptr(*clause);
+                return Ok(());
+            }
+        }
+        Err(InvalidNumArgs(self.heap.case_lambda_desc(&clauses)))
+    }
+
     /// Get Symbol Bound To
     ///
     /// Given either an environment slot, or a symbol reference, return the
@@ -416,7 +534,7 @@ impl Vm {
                 *self.heap.get_at_index_mut(ptr) = vcell;
             }
             VCell::BasePointerOffset(offset) => {
-                *self.stack.get_offset_mut((self.bp as i64) + offset)? = vcell;
+                *self.stack.get_mut((self.bp as i64 + offset) as usize)? = vcell;
             }
             VCell::GlobalEnvSlot(slot) => {
                 self.globenv.put_slot(slot, vcell);
@@ -472,25 +590,40 @@ impl Vm {
     ///
     /// Run GC performs two steps in order:
     ///
-    /// 1. Check if heap utilization is > 75%, aborting gc is not.
+    /// 1. Check if heap utilization is > 75%, or if many slots have been
+    ///    created for derived environments, aborting gc if not.
     ///
     /// 2. It performs a mark on all roots:
     ///    * The global environment
     ///    * Any data referecned by the running program & stack
     ///
     /// 3. A sweep, freeing any vcells not marked as used in step #1.
+    ///
+    /// The slots of a dropped derived environment aren't roots. They're
+    /// marked only while marked code refers to them, and are otherwise
+    /// reclaimed along with the vcells.
     pub fn run_gc(&mut self) {
-        if (self.heap.used_size() as f64 / self.heap.capacity() as f64) < 0.75_f64 {
+        if (self.heap.used_size() as f64 / self.heap.capacity() as f64) < 0.75_f64
+            && !self.globenv.is_gc_pending()
+        {
             return;
         }
 
         self.globenv
-            .iter_bindings()
+            .iter_symbols()
             .for_each(|it| self.heap.mark(*it));
 
+        self.libraries
+            .values()
+            .flat_map(|it| it.iter_symbols())
+            .for_each(|it| self.heap.mark(*it));
+
+        let released = self.globenv.released_slots();
         self.globenv
             .iter_slots()
-            .filter_map(|it| it.as_ptr().ok())
+            .enumerate()
+            .filter(|(slot, _)| !released.contains(slot))
+            .filter_map(|(_, it)| it.as_ptr().ok())
             .for_each(|it| self.heap.mark(it));
 
         self.stack
@@ -499,7 +632,37 @@ impl Vm {
         self.heap.mark_vcell(&self.acc);
         self.heap.mark(self.ip.0);
         self.heap.mark(self.ep);
+        self.heap.mark_vcell(&self.wind);
+        self.heap.mark_vcell(&self.handlers);
+        self.heap.mark_vcell(&self.input_port);
+        self.heap.mark_vcell(&self.output_port);
+
+        // Marking the value of a released slot that code refers to may mark
+        // code referring to further released slots.
+        let mut live = HashSet::new();
+        loop {
+            let referred = self
+                .heap
+                .marked_slots()
+                .iter()
+                .filter(|it| released.contains(it) && !live.contains(*it))
+                .copied()
+                .collect::<Vec<_>>();
+            if referred.is_empty() {
+                break;
+            }
+            for slot in referred {
+                live.insert(slot);
+                if let Ok(ptr) = self.globenv.get_slot(slot).as_ptr() {
+                    self.heap.mark(ptr);
+                }
+            }
+        }
+        released
+            .difference(&live)
+            .for_each(|slot| self.globenv.free_slot(*slot));
         self.heap.sweep();
+        self.globenv.reset_gc_threshold();
 
         // If after GC the heap utilization is still high, grow the heap.
         if (self.heap.used_size() as f64 / self.heap.capacity() as f64) > 0.75_f64 {
@@ -1,7 +1,10 @@
 use crate::cell;
-use crate::cell::Cell;
+use crate::cell::{split_alias, Cell};
 use crate::error::Error;
 use crate::error::Error::InvalidSyntax;
+use crate::vm::environment::Environment;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
 
 macro_rules! car {
     ($cell:expr) => {{
@@ -161,8 +164,44 @@ pub struct Transform {
     ellipsis: Cell,
     syntax_rules: Vec<(Pattern, Cell)>,
     literals: Vec<Cell>,
+
+    /// The environment a top level macro was defined in, which free
+    /// identifiers in its templates refer to
+    environment: Option<MacroEnvironment>,
+}
+
+/// Macro Environment
+///
+/// The environment a macro was defined in. A derived environment is held
+/// weakly, because the macro is bound in one of its slots and a strong
+/// reference would keep the environment and its slots alive forever.
+#[derive(Debug)]
+enum MacroEnvironment {
+    Strong(Rc<Environment>),
+    Weak(Weak<Environment>),
+}
+
+impl MacroEnvironment {
+    fn get(&self) -> Option<Rc<Environment>> {
+        match self {
+            MacroEnvironment::Strong(environment) => Some(environment.clone()),
+            MacroEnvironment::Weak(environment) => environment.upgrade(),
+        }
+    }
 }
 
+impl PartialEq for MacroEnvironment {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MacroEnvironment::Strong(lhs), MacroEnvironment::Strong(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (MacroEnvironment::Weak(lhs), MacroEnvironment::Weak(rhs)) => Weak::ptr_eq(lhs, rhs),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for MacroEnvironment {}
+
 impl Transform {
     /// Try New
     ///
@@ -184,7 +223,7 @@ impl Transform {
         }
 
         // Skip past "syntax-rules"
-        if car!(syntax_rules) != &cell!["syntax-rules"] {
+        if !car!(syntax_rules).is_symbol_str("syntax-rules") {
             return Err(InvalidSyntax("expected syntax-rules".into()));
         }
         syntax_rules = cdr!(syntax_rules);
@@ -226,6 +265,7 @@ impl Transform {
             ellipsis,
             syntax_rules: syntax_rules_vec,
             literals,
+            environment: None,
         })
     }
 
@@ -240,6 +280,23 @@ impl Transform {
         &self.keyword
     }
 
+    pub fn environment(&self) -> Option<Rc<Environment>> {
+        self.environment.as_ref().and_then(|it| it.get())
+    }
+
+    /// Set Environment
+    ///
+    /// Set the environment a top level macro was defined in.
+    ///
+    /// # Arguments
+    /// `environment` - The environment the macro was defined in
+    pub fn set_environment(&mut self, environment: &Rc<Environment>) {
+        self.environment = Some(match environment.is_derived() {
+            true => MacroEnvironment::Weak(Rc::downgrade(environment)),
+            false => MacroEnvironment::Strong(environment.clone()),
+        });
+    }
+
     /// Check Template Syntax
     ///
     /// * Any symbol preceding an ellipsis must be a pattern variable
@@ -288,9 +345,31 @@ impl Transform {
     /// this transformer. An error is returned if none of the input expressions
     /// match the patterns specified in the syntax-rules of this transformer.
     ///
+    /// Symbols introduced by the template are copied to the expansion as is.
+    ///
     /// # Arguments
     /// `expr` - The expression to transform
     pub fn transform(&self, expr: &Cell) -> Result<Cell, Error> {
+        self.transform_expansion(expr, None)
+    }
+
+    /// Transform Hygienic
+    ///
+    /// Transform the input expression like transform, except that every
+    /// symbol introduced by the template is renamed to an alias unique to
+    /// this expansion. An introduced binding may then never capture a
+    /// variable of the input expression, and an introduced reference that
+    /// the expansion doesn't bind refers to the symbol in the environment
+    /// the macro was defined in.
+    ///
+    /// # Arguments
+    /// `expr` - The expression to transform
+    /// `expansion` - A number unique to this expansion
+    pub fn transform_hygienic(&self, expr: &Cell, expansion: usize) -> Result<Cell, Error> {
+        self.transform_expansion(expr, Some(expansion))
+    }
+
+    fn transform_expansion(&self, expr: &Cell, expansion: Option<usize>) -> Result<Cell, Error> {
         let invalid_syntax = || Err(InvalidSyntax(format!("{:#}", self.keyword)));
         if !expr.is_pair() {
             invalid_syntax()?;
@@ -299,8 +378,12 @@ impl Transform {
         for rule in &self.syntax_rules {
             let mut env = PatternEnvironment::new(&rule.0);
             if self.pattern_match(cdr!(&rule.0.expr), cdr!(expr), &mut env) {
+                let template = match expansion {
+                    Some(expansion) => self.rename(&rule.1, &rule.0, expansion),
+                    None => rule.1.clone(),
+                };
                 return self
-                    .expand(&rule.1, &rule.0, &mut env)
+                    .expand(&template, &rule.0, &mut env)
                     .ok_or_else(|| InvalidSyntax(format!("{:#}", self.keyword)));
             }
         }
@@ -397,7 +480,9 @@ impl Transform {
             match pattern {
                 Cell::Symbol(_) => {
                     if self.is_literal(pattern) {
-                        if pattern != expr {
+                        // A literal introduced by another macro's expansion is
+                        // an alias, which still matches the literal
+                        if *pattern != expr.unaliased() {
                             return false;
                         }
                     } else if pattern != &cell!["_"] {
@@ -418,6 +503,38 @@ impl Transform {
         }
     }
 
+    /// Rename
+    ///
+    /// Return a copy of template where every symbol other than a pattern
+    /// variable or the ellipsis is replaced by its alias for expansion.
+    ///
+    /// # Arguments
+    /// `template` - The template to rename
+    /// `pattern` - The pattern associated with the template
+    /// `expansion` - The number of the expansion the aliases belong to
+    fn rename(&self, template: &Cell, pattern: &Pattern, expansion: usize) -> Cell {
+        match template {
+            Cell::Symbol(sym) => {
+                if pattern.is_variable(template) || *template == self.ellipsis {
+                    template.clone()
+                } else {
+                    Cell::new_alias(sym, expansion)
+                }
+            }
+            Cell::Pair(car, cdr) => Cell::new_pair(
+                self.rename(car, pattern, expansion),
+                self.rename(cdr, pattern, expansion),
+            ),
+            Cell::Vector(vector) => Cell::Vector(
+                vector
+                    .iter()
+                    .map(|it| self.rename(it, pattern, expansion))
+                    .collect(),
+            ),
+            cell => cell.clone(),
+        }
+    }
+
     /// Expand
     ///
     /// Given a list of bindings created from a pattern match, and a template, expand
@@ -477,6 +594,151 @@ impl Transform {
     }
 }
 
+/// Syntax Binding
+///
+/// The binding of an identifier in a syntax environment scope
+#[derive(Debug, Clone)]
+pub enum SyntaxBinding {
+    /// A macro, along with the depth of the scope that free identifiers
+    /// in its templates refer to
+    Macro(Rc<Transform>, usize),
+
+    /// A variable bound by a lambda or internal definition, along with the
+    /// identifier it's compiled as
+    Variable(Cell),
+}
+
+/// Syntax Environment
+///
+/// Syntax environment holds the macros bound by let-syntax, letrec-syntax
+/// and internal define-syntax while an expression is being expanded, along
+/// with the variables that may shadow them. Each scope is a list of
+/// identifiers and their bindings, with the innermost scope last.
+///
+/// Any identifier not bound in a scope refers to the global environment.
+#[derive(Debug, Default)]
+pub struct SyntaxEnvironment {
+    scopes: Vec<Vec<(Cell, SyntaxBinding)>>,
+
+    /// The depth of the definition of the macro that performed each
+    /// expansion, by expansion number
+    expansions: HashMap<usize, usize>,
+
+    /// The environment of the top level macro that performed each
+    /// expansion, by expansion number
+    environments: HashMap<usize, Rc<Environment>>,
+}
+
+impl SyntaxEnvironment {
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(vec![]);
+    }
+
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Bind
+    ///
+    /// Bind identifier in the innermost scope. Identifiers bound at the top
+    /// level are left to the global environment.
+    ///
+    /// # Arguments
+    /// `identifier` - The identifier to bind
+    /// `binding` - The macro or variable identifier is bound to
+    pub fn bind(&mut self, identifier: Cell, binding: SyntaxBinding) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push((identifier, binding));
+        }
+    }
+
+    /// Bind Variable
+    ///
+    /// Bind variable in the innermost scope, and return the identifier it's
+    /// compiled as. A variable that shadows a binding of an outer scope is
+    /// renamed to a unique alias, so that an identifier introduced by a local
+    /// macro that refers to the outer binding isn't captured by it.
+    ///
+    /// # Arguments
+    /// `variable` - The variable to bind
+    /// `expansions` - The expansion counter that numbers the alias
+    pub fn bind_variable(&mut self, variable: Cell, expansions: &mut usize) -> Cell {
+        let depth = self.depth();
+        let bound = self
+            .scopes
+            .last()
+            .and_then(|scope| scope.iter().rev().find(|it| it.0 == variable));
+        let renamed = match (bound, variable.as_symbol()) {
+            (Some((_, SyntaxBinding::Variable(renamed))), _) => renamed.clone(),
+            (_, Some(sym)) if self.get(&variable, depth.saturating_sub(1)).is_some() => {
+                *expansions += 1;
+                Cell::new_alias(sym, *expansions)
+            }
+            _ => variable.clone(),
+        };
+        self.bind(variable, SyntaxBinding::Variable(renamed.clone()));
+        renamed
+    }
+
+    /// Add Expansion
+    ///
+    /// Record that expansion was performed by a macro defined at depth
+    pub fn add_expansion(&mut self, expansion: usize, depth: usize) {
+        self.expansions.insert(expansion, depth);
+    }
+
+    /// Add Expansion Environment
+    ///
+    /// Record that expansion was performed by a top level macro defined in
+    /// environment
+    pub fn add_expansion_environment(&mut self, expansion: usize, environment: Rc<Environment>) {
+        self.environments.insert(expansion, environment);
+    }
+
+    /// Get Expansion Environment
+    ///
+    /// Return the environment of the top level macro that performed
+    /// expansion, if any
+    pub fn get_expansion_environment(&self, expansion: usize) -> Option<&Rc<Environment>> {
+        self.environments.get(&expansion)
+    }
+
+    /// Get
+    ///
+    /// Return the identifier that binds identifier in the scopes up to
+    /// depth, along with its binding. An alias introduced by a macro refers
+    /// to the binding of the identifier in the scope that macro was defined
+    /// in.
+    ///
+    /// None is returned if the identifier isn't bound in any scope.
+    ///
+    /// # Arguments
+    /// `identifier` - The identifier to look up
+    /// `depth` - The depth of the innermost scope to search
+    pub fn get(&self, identifier: &Cell, depth: usize) -> Option<(Cell, SyntaxBinding)> {
+        let depth = depth.min(self.scopes.len());
+        for scope in self.scopes[..depth].iter().rev() {
+            if let Some(binding) = scope.iter().rev().find(|it| it.0 == *identifier) {
+                return Some(binding.clone());
+            }
+        }
+        match identifier.as_symbol().and_then(split_alias) {
+            Some((sym, expansion)) => {
+                let depth = match self.expansions.get(&expansion) {
+                    Some(expansion_depth) => depth.min(*expansion_depth),
+                    None => depth,
+                };
+                self.get(&Cell::new_symbol(sym), depth)
+            }
+            None => None,
+        }
+    }
+}
+
 /// Pattern Environment
 ///
 /// Pattern environment is the result of a successful pattern,
@@ -1110,6 +1372,82 @@ mod tests {
         assert!(transform.is_ok());
     }
 
+    #[test]
+    fn hygienic_expansion() {
+        let transform = Transform::try_new(&parse!(
+            r#"
+        (define-syntax my-or
+            (syntax-rules ()
+            [(my-or e1 e2) (let ((temp e1)) (if temp temp e2))]))
+        "#
+        ))
+        .unwrap();
+        assert_eq!(
+            transform.transform_hygienic(&parse!("(my-or #f temp)"), 3),
+            Ok(Cell::new_list(vec![
+                Cell::new_alias("let", 3),
+                Cell::new_list(vec![Cell::new_list(vec![
+                    Cell::new_alias("temp", 3),
+                    cell![false]
+                ])]),
+                Cell::new_list(vec![
+                    Cell::new_alias("if", 3),
+                    Cell::new_alias("temp", 3),
+                    Cell::new_alias("temp", 3),
+                    cell!["temp"]
+                ])
+            ]))
+        );
+        assert_eq!(
+            transform.transform(&parse!("(my-or #f temp)")),
+            Ok(parse!("(let ((temp #f)) (if temp temp temp))"))
+        );
+    }
+
+    #[test]
+    fn syntax_environment() {
+        let transform = Rc::new(
+            Transform::try_new(&parse!("(define-syntax m (syntax-rules () ((_) x)))")).unwrap(),
+        );
+        let mut env = SyntaxEnvironment::default();
+        let mut expansions = 1;
+        assert_eq!(env.bind_variable(cell!["x"], &mut expansions), cell!["x"]);
+        assert!(env.get(&cell!["x"], env.depth()).is_none());
+
+        env.push_scope();
+        assert_eq!(env.bind_variable(cell!["x"], &mut expansions), cell!["x"]);
+        env.bind(cell!["m"], SyntaxBinding::Macro(transform, 1));
+        env.push_scope();
+        assert_eq!(
+            env.bind_variable(cell!["x"], &mut expansions),
+            Cell::new_alias("x", 2)
+        );
+        assert_eq!(
+            env.bind_variable(cell!["x"], &mut expansions),
+            Cell::new_alias("x", 2)
+        );
+        env.add_expansion(1, 1);
+
+        assert!(matches!(
+            env.get(&cell!["m"], env.depth()),
+            Some((_, SyntaxBinding::Macro(_, 1)))
+        ));
+        assert!(matches!(
+            env.get(&Cell::new_alias("x", 1), env.depth()),
+            Some((_, SyntaxBinding::Variable(x))) if x == cell!["x"]
+        ));
+        assert!(matches!(
+            env.get(&cell!["x"], env.depth()),
+            Some((_, SyntaxBinding::Variable(x))) if x == Cell::new_alias("x", 2)
+        ));
+        assert!(env.get(&Cell::new_alias("x", 1), 0).is_none());
+        assert!(env.get(&Cell::new_alias("y", 1), env.depth()).is_none());
+
+        env.pop_scope();
+        env.pop_scope();
+        assert!(env.get(&cell!["m"], env.depth()).is_none());
+    }
+
     #[test]
     fn trivial_let_macro() {
         let transform = Transform::try_new(&parse!(
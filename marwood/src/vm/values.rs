@@ -0,0 +1,92 @@
+use crate::error::Error;
+use crate::error::Error::InvalidSyntax;
+use crate::vm::builtin::pop_argc;
+use crate::vm::lambda::Lambda;
+use crate::vm::opcode::OpCode;
+use crate::vm::vcell::VCell;
+use crate::vm::Vm;
+
+impl Vm {
+    /// Build Call With Values
+    ///
+    /// Build a procedure of no arguments that implements call-with-values
+    /// for the given producer and consumer. The procedure applies producer,
+    /// and then tail calls consumer with the values producer returned as its
+    /// arguments.
+    ///
+    /// # Arguments
+    /// `producer` - The procedure of no arguments producing the values
+    /// `consumer` - The procedure the values are applied to
+    pub fn build_call_with_values(
+        &mut self,
+        producer: VCell,
+        consumer: VCell,
+    ) -> Result<VCell, Error> {
+        let producer = self.heap.put(producer);
+        let consumer = self.heap.put(consumer);
+        let apply_values = self
+            .heap
+            .put(VCell::builtin("call-with-values", apply_values));
+
+        let mut lambda = Lambda::new(vec![]);
+        lambda.emit(OpCode::Enter);
+        lambda.emit(OpCode::PushImmediate);
+        lambda.emit(consumer);
+        lambda.emit_call(producer, &[]);
+        lambda.emit(OpCode::PushAcc);
+        lambda.emit(OpCode::PushImmediate);
+        lambda.emit(VCell::ArgumentCount(2));
+        lambda.emit(OpCode::MovImmediate);
+        lambda.emit(apply_values);
+        lambda.emit(VCell::Acc);
+        lambda.emit(OpCode::TCallAcc);
+        lambda.emit(OpCode::Ret);
+        Ok(self.heap.put(lambda))
+    }
+
+    /// Values To Vec
+    ///
+    /// Return the individual values held by vcell. A VCell::Values is
+    /// expanded into each of its values, and any other value is returned
+    /// as a single value.
+    ///
+    /// # Arguments
+    /// `vcell` - The value or values to expand
+    pub fn values_to_vec(&self, vcell: &VCell) -> Vec<VCell> {
+        match self.heap.get(vcell) {
+            VCell::Values(values) => values.as_ref().clone(),
+            _ => vec![vcell.clone()],
+        }
+    }
+}
+
+/// Apply Values
+///
+/// Apply the consumer in the first argument to the values in the second
+/// argument. This is used by call-with-values and define-values to spread
+/// multiple values on to the stack as arguments to the consumer.
+///
+/// Like apply, the consumer is returned to be placed in %acc, and %ip is
+/// decremented so that the CALL or TCALL that applied this builtin next
+/// applies the consumer.
+pub fn apply_values(vm: &mut Vm) -> Result<VCell, Error> {
+    pop_argc(vm, 2, Some(2), "call-with-values")?;
+    let values = vm.stack.pop()?.clone();
+    let consumer = match vm.stack.pop()?.clone() {
+        proc if vm.heap.get(&proc).is_procedure() => proc,
+        proc => {
+            return Err(InvalidSyntax(format!(
+                "bad argument to call-with-values: {:#} is not a procedure",
+                vm.heap.get_as_cell(&proc)
+            )));
+        }
+    };
+    let values = vm.values_to_vec(&values);
+    let argc = values.len();
+    for it in values {
+        vm.stack.push(it);
+    }
+    vm.stack.push(VCell::ArgumentCount(argc));
+    vm.ip.1 -= 1;
+    Ok(consumer)
+}
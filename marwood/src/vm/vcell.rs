@@ -2,11 +2,15 @@ use crate::char::write_escaped_char;
 use crate::error::Error;
 use crate::error::Error::ExpectedType;
 use crate::number::Number;
+use crate::vm::condition::Condition;
 use crate::vm::continuation::Continuation;
-use crate::vm::environment::LexicalEnvironment;
+use crate::vm::environment::{Environment, LexicalEnvironment};
 use crate::vm::heap::HeapRef;
 use crate::vm::lambda::Lambda;
 use crate::vm::opcode::OpCode;
+use crate::vm::port::Port;
+use crate::vm::promise::Promise;
+use crate::vm::record::{Record, RecordType};
 use crate::vm::transform::Transform;
 use crate::vm::vector::Vector;
 use crate::vm::Vm;
@@ -32,6 +36,7 @@ use std::rc::Rc;
 pub enum VCell {
     // Scheme primitive types
     Bool(bool),
+    Bytevector(Rc<RefCell<Vec<u8>>>),
     Char(char),
     Nil,
     Number(Number),
@@ -41,10 +46,19 @@ pub enum VCell {
     Vector(Rc<Vector>),
 
     // other scheme values
+    Condition(Rc<Condition>),
+    Environment(Rc<Environment>),
+    Eof,
+    Port(Rc<Port>),
+    Promise(Rc<Promise>),
+    Record(Rc<Record>),
+    RecordType(Rc<RecordType>),
     Undefined,
+    Values(Rc<Vec<VCell>>),
     Void,
 
     // lambda, closure and lexical environments
+    CaseLambda(Rc<Vec<HeapRef>>),
     Continuation(Rc<Continuation>),
     Closure(HeapRef, HeapRef),
     Lambda(Rc<Lambda>),
@@ -104,10 +118,15 @@ pub const ARGUMENT_COUNT_TYPE_TEXT: &str = "#<argument-count>";
 pub const BASE_POINTER_TYPE_TEXT: &str = "#<base-pointer>";
 pub const BASE_POINTER_OFFSET_TYPE_TEXT: &str = "#<base-pointer-offset>";
 pub const BOOL_TYPE_TEXT: &str = "#<bool>";
+pub const BYTEVECTOR_TYPE_TEXT: &str = "#<bytevector>";
+pub const CASE_LAMBDA_TYPE_TEXT: &str = "#<case-lambda>";
 pub const CHAR_TYPE_TEXT: &str = "#<char>";
 pub const CLOSURE_TYPE_TEXT: &str = "#<closure>";
+pub const CONDITION_TYPE_TEXT: &str = "#<condition>";
 pub const CONTINUATION_TYPE_TEXT: &str = "#<continuation>";
 pub const GLOBAL_ENV_SLOT_TYPE_TEXT: &str = "#<global-environment-slot>";
+pub const EOF_TYPE_TEXT: &str = "#<eof>";
+pub const ENVIRONMENT_TYPE_TEXT: &str = "#<environment>";
 pub const ENVIRONMENT_POINTER_TYPE_TEXT: &str = "#<environment-pointer>";
 pub const MACRO_TYPE_TEXT: &str = "#<macro>";
 pub const LEXICAL_ENV_TYPE_TEXT: &str = "#<lexical-environment>";
@@ -120,11 +139,16 @@ pub const LAMBDA_TYPE_TEXT: &str = "#<lambda>";
 pub const NIL_TYPE_TEXT: &str = "#<nil>";
 pub const OPCODE_TYPE_TEXT: &str = "#<opcode>";
 pub const PAIR_TYPE_TEXT: &str = "#<pair>";
+pub const PORT_TYPE_TEXT: &str = "#<port>";
+pub const PROMISE_TYPE_TEXT: &str = "#<promise>";
 pub const PTR_TYPE_TEXT: &str = "#<ptr>";
+pub const RECORD_TYPE_TEXT: &str = "#<record>";
+pub const RECORD_TYPE_TYPE_TEXT: &str = "#<record-type>";
 pub const STRING_TYPE_TEXT: &str = "#<string>";
 pub const SYMBOL_TYPE_TEXT: &str = "#<symbol>";
 pub const SYSCALL_TYPE_TEXT: &str = "#<syscall>";
 pub const UNDEFINED_TYPE_TEXT: &str = "#<undefined>";
+pub const VALUES_TYPE_TEXT: &str = "#<values>";
 pub const VECTOR_TYPE_TEXT: &str = "#<vector>";
 pub const VOID_TYPE_TEXT: &str = "#<void>";
 
@@ -140,10 +164,15 @@ impl VCell {
             VCell::BasePointer(_) => BASE_POINTER_TYPE_TEXT,
             VCell::BasePointerOffset(_) => BASE_POINTER_OFFSET_TYPE_TEXT,
             VCell::Bool(_) => BOOL_TYPE_TEXT,
+            VCell::Bytevector(_) => BYTEVECTOR_TYPE_TEXT,
+            VCell::CaseLambda(_) => CASE_LAMBDA_TYPE_TEXT,
             VCell::Char(_) => CHAR_TYPE_TEXT,
+            VCell::Condition(_) => CONDITION_TYPE_TEXT,
             VCell::Continuation(_) => CONTINUATION_TYPE_TEXT,
             VCell::Closure(_, _) => CLOSURE_TYPE_TEXT,
             VCell::EnvironmentPointer(_) => ENVIRONMENT_POINTER_TYPE_TEXT,
+            VCell::Environment(_) => ENVIRONMENT_TYPE_TEXT,
+            VCell::Eof => EOF_TYPE_TEXT,
             VCell::GlobalEnvSlot(_) => GLOBAL_ENV_SLOT_TYPE_TEXT,
             VCell::LexicalEnv(_) => LEXICAL_ENV_TYPE_TEXT,
             VCell::LexicalEnvSlot(_) => LEXICAL_ENV_TYPE_SLOT,
@@ -154,12 +183,17 @@ impl VCell {
             VCell::Number(_) => NUMBER_TYPE_TEXT,
             VCell::OpCode(_) => OPCODE_TYPE_TEXT,
             VCell::Pair(_, _) => PAIR_TYPE_TEXT,
+            VCell::Port(_) => PORT_TYPE_TEXT,
+            VCell::Promise(_) => PROMISE_TYPE_TEXT,
             VCell::Ptr(_) => PTR_TYPE_TEXT,
+            VCell::Record(_) => RECORD_TYPE_TEXT,
+            VCell::RecordType(_) => RECORD_TYPE_TYPE_TEXT,
             VCell::String(_) => STRING_TYPE_TEXT,
             VCell::Symbol(_) => SYMBOL_TYPE_TEXT,
             VCell::BuiltInProc(_) => SYSCALL_TYPE_TEXT,
             VCell::Macro(_) => MACRO_TYPE_TEXT,
             VCell::Undefined => UNDEFINED_TYPE_TEXT,
+            VCell::Values(_) => VALUES_TYPE_TEXT,
             VCell::Vector(_) => VECTOR_TYPE_TEXT,
             VCell::Void => VOID_TYPE_TEXT,
         }
@@ -205,6 +239,14 @@ impl VCell {
         VCell::Vector(Rc::new(Vector::new(vector.into())))
     }
 
+    pub fn bytevector<T: Into<Vec<u8>>>(bytevector: T) -> VCell {
+        VCell::Bytevector(Rc::new(RefCell::new(bytevector.into())))
+    }
+
+    pub fn values<T: Into<Vec<VCell>>>(values: T) -> VCell {
+        VCell::Values(Rc::new(values.into()))
+    }
+
     pub fn lambda<T: Into<Lambda>>(lambda: T) -> VCell {
         VCell::Lambda(Rc::new(lambda.into()))
     }
@@ -269,16 +311,28 @@ impl VCell {
         matches!(self, VCell::Closure(_, _))
     }
 
+    pub fn is_case_lambda(&self) -> bool {
+        matches!(self, VCell::CaseLambda(_))
+    }
+
     pub fn is_continuation(&self) -> bool {
         matches!(self, VCell::Continuation(_))
     }
 
+    pub fn is_condition(&self) -> bool {
+        matches!(self, VCell::Condition(_))
+    }
+
     pub fn is_builtin_proc(&self) -> bool {
         matches!(self, VCell::BuiltInProc(_))
     }
 
     pub fn is_procedure(&self) -> bool {
-        self.is_lambda() || self.is_closure() || self.is_builtin_proc() || self.is_continuation()
+        self.is_lambda()
+            || self.is_closure()
+            || self.is_case_lambda()
+            || self.is_builtin_proc()
+            || self.is_continuation()
     }
 
     pub fn is_lexical_env(&self) -> bool {
@@ -293,6 +347,30 @@ impl VCell {
         matches!(self, VCell::Vector(_))
     }
 
+    pub fn is_bytevector(&self) -> bool {
+        matches!(self, VCell::Bytevector(_))
+    }
+
+    pub fn is_eof(&self) -> bool {
+        matches!(self, VCell::Eof)
+    }
+
+    pub fn is_environment(&self) -> bool {
+        matches!(self, VCell::Environment(_))
+    }
+
+    pub fn is_port(&self) -> bool {
+        matches!(self, VCell::Port(_))
+    }
+
+    pub fn is_promise(&self) -> bool {
+        matches!(self, VCell::Promise(_))
+    }
+
+    pub fn is_record(&self) -> bool {
+        matches!(self, VCell::Record(_))
+    }
+
     pub fn as_opcode(&self) -> Result<OpCode, Error> {
         match self {
             VCell::OpCode(op) => Ok(op.clone()),
@@ -377,6 +455,13 @@ impl VCell {
         }
     }
 
+    pub fn as_environment(&self) -> Result<Rc<Environment>, Error> {
+        match self {
+            VCell::Environment(environment) => Ok(environment.clone()),
+            _ => Err(ExpectedType(ENVIRONMENT_TYPE_TEXT, self.type_text())),
+        }
+    }
+
     pub fn as_argc(&self) -> Result<usize, Error> {
         match self {
             VCell::ArgumentCount(bp) => Ok(*bp),
@@ -492,10 +577,15 @@ impl fmt::Display for VCell {
             VCell::BasePointerOffset(offset) => write!(f, "%bp[{:+}]", *offset),
             VCell::Bool(true) => write!(f, "#t"),
             VCell::Bool(false) => write!(f, "#f"),
+            VCell::Bytevector(_) => write!(f, "#<bytevector>"),
+            VCell::CaseLambda(_) => write!(f, "#<case-lambda>"),
             VCell::Char(c) => write_escaped_char(*c, f),
             VCell::Closure(_, _) => write!(f, "#<closure>"),
+            VCell::Condition(condition) => write!(f, "#<condition: {}>", condition.error()),
             VCell::Continuation(_) => write!(f, "#<continuation>"),
             VCell::EnvironmentPointer(ep) => write!(f, "%ep[${:02x}]", ep),
+            VCell::Environment(_) => write!(f, "#<environment>"),
+            VCell::Eof => write!(f, "#<eof>"),
             VCell::GlobalEnvSlot(slot) => write!(f, "genv[${:02x}]", slot),
             VCell::InstructionPointer(lambda, ip) => {
                 write!(f, "%ip[${:02x}][${:02x}]", *lambda, *ip)
@@ -509,11 +599,16 @@ impl fmt::Display for VCell {
             VCell::Number(number) => write!(f, "{:?}", number),
             VCell::OpCode(val) => write!(f, "{:?}", val),
             VCell::Pair(car, cdr) => write!(f, "(${:02x} . ${:02x})", car, cdr),
+            VCell::Port(_) => write!(f, "#<port>"),
+            VCell::Promise(_) => write!(f, "#<promise>"),
             VCell::Ptr(ptr) => write!(f, "${:02x}", ptr),
+            VCell::Record(record) => write!(f, "#<record {}>", record.record_type().name()),
+            VCell::RecordType(record_type) => write!(f, "#<record-type {}>", record_type.name()),
             VCell::String(s) => write!(f, "\"{}\"", (**s).borrow().deref()),
             VCell::Symbol(s) => write!(f, "{}", *s),
             VCell::BuiltInProc(proc) => write!(f, "#<builtin:{}>", proc.desc()),
             VCell::Undefined => write!(f, "undefined"),
+            VCell::Values(_) => write!(f, "#<values>"),
             VCell::Vector(_) => write!(f, "#<vector>"),
             VCell::Void => write!(f, "#<void>"),
         }
@@ -0,0 +1,105 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::Vm;
+
+use marwood::error::Error::{InvalidBytevectorIndex, InvalidSyntax};
+
+#[test]
+fn bytevector_literals() {
+    evals![
+        "#u8()" => "#u8()",
+        "#u8(1 2 255)" => "#u8(1 2 255)",
+        "'#u8(0 #xff)" => "#u8(0 255)",
+        "`(1 #u8(2))" => "(1 #u8(2))",
+        "(bytevector? #u8(1))" => "#t",
+        "(bytevector? #(1))" => "#f",
+        "(vector? #u8(1))" => "#f"
+    ];
+}
+
+#[test]
+fn bytevector_and_make_bytevector() {
+    evals![
+        "(bytevector)" => "#u8()",
+        "(bytevector 1 (+ 1 1) 3)" => "#u8(1 2 3)",
+        "(make-bytevector 0)" => "#u8()",
+        "(make-bytevector 3)" => "#u8(0 0 0)",
+        "(make-bytevector 2 12)" => "#u8(12 12)"
+    ];
+    fails![
+        "(bytevector 256)" => InvalidSyntax("bad argument to bytevector: 256 is not a byte".into()),
+        "(make-bytevector 2 -1)" => InvalidSyntax("bad argument to make-bytevector: -1 is not a byte".into())
+    ];
+}
+
+#[test]
+fn bytevector_ref_and_set() {
+    evals![
+        "(define bv (bytevector 1 2 3))" => "#<void>",
+        "(bytevector-length bv)" => "3",
+        "(bytevector-u8-ref bv 1)" => "2",
+        "(bytevector-u8-set! bv 1 255)" => "#<void>",
+        "bv" => "#u8(1 255 3)"
+    ];
+    fails![
+        "(bytevector-u8-ref #u8(1 2 3) 3)" => InvalidBytevectorIndex(3, 3),
+        "(bytevector-u8-set! (bytevector 1) 1 0)" => InvalidBytevectorIndex(1, 1),
+        "(bytevector-u8-set! (bytevector 1) 0 300)" => InvalidSyntax("bad argument to bytevector-u8-set!: 300 is not a byte".into()),
+        "(bytevector-length #(1 2))" => InvalidSyntax("bad argument to bytevector-length: #(1 2) is not a bytevector".into())
+    ];
+}
+
+#[test]
+fn bytevector_copy_and_append() {
+    evals![
+        "(bytevector-copy #u8(1 2 3 4 5))" => "#u8(1 2 3 4 5)",
+        "(bytevector-copy #u8(1 2 3 4 5) 2)" => "#u8(3 4 5)",
+        "(bytevector-copy #u8(1 2 3 4 5) 2 4)" => "#u8(3 4)",
+        "(bytevector-copy #u8(1 2 3) 3)" => "#u8()",
+        "(bytevector-append)" => "#u8()",
+        "(bytevector-append #u8(0 1) #u8() #u8(2 3))" => "#u8(0 1 2 3)"
+    ];
+    evals![
+        "(define a (bytevector 1 2 3 4 5))" => "#<void>",
+        "(define b (bytevector 10 20 30 40 50))" => "#<void>",
+        "(bytevector-copy! b 1 a 0 2)" => "#<void>",
+        "b" => "#u8(10 1 2 40 50)",
+        "(bytevector-copy! a 1 a 0 3)" => "#<void>",
+        "a" => "#u8(1 1 2 3 5)"
+    ];
+    fails![
+        "(bytevector-copy #u8(1 2 3) 1 4)" => InvalidBytevectorIndex(4, 3),
+        "(bytevector-copy #u8(1 2 3) 2 1)" => InvalidBytevectorIndex(2, 3),
+        "(bytevector-copy! (bytevector 1 2) 1 #u8(1 2))" => InvalidSyntax("bytevector-copy!: to bytevector is too small".into())
+    ];
+}
+
+#[test]
+fn utf8() {
+    evals![
+        "(string->utf8 \"abc\")" => "#u8(97 98 99)",
+        "(string->utf8 \"λx\")" => "#u8(206 187 120)",
+        "(string->utf8 \"aλx\" 1 2)" => "#u8(206 187)",
+        "(utf8->string #u8(206 187 120))" => "\"λx\"",
+        "(utf8->string #u8(97 98 99) 1)" => "\"bc\"",
+        "(utf8->string (string->utf8 \"hello\"))" => "\"hello\""
+    ];
+    fails![
+        "(utf8->string #u8(255))" => InvalidSyntax("bad argument to utf8->string: bytevector is not valid utf-8".into())
+    ];
+}
+
+#[test]
+fn bytevector_equality() {
+    evals![
+        "(define bv #u8(1 2 3))" => "#<void>",
+        "(eq? bv bv)" => "#t",
+        "(eqv? bv (bytevector 1 2 3))" => "#f",
+        "(equal? bv (bytevector 1 2 3))" => "#t",
+        "(equal? bv (bytevector 1 2))" => "#f",
+        "(equal? '(#u8(1)) (list (bytevector 1)))" => "#t"
+    ];
+}
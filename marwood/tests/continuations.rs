@@ -1,6 +1,7 @@
 #[macro_use]
 mod common;
 use marwood::cell::Cell;
+use marwood::error::Error::InvalidSyntax;
 use marwood::lex;
 use marwood::parse;
 use marwood::vm::Vm;
@@ -68,3 +69,109 @@ fn factorial_cps() {
             "(factorial 10)" 
             => "3628800"];
 }
+
+#[test]
+fn dynamic_wind() {
+    evals![
+        "(define trail '())" => "#<void>",
+        "(define (note x) (set! trail (cons x trail)))" => "#<void>",
+        "(dynamic-wind (lambda () (note 'before)) (lambda () (note 'during) 42) (lambda () (note 'after)))" => "42",
+        "(reverse trail)" => "(before during after)"
+    ];
+
+    // Escaping from the thunk runs after
+    evals![
+        "(define trail '())" => "#<void>",
+        "(define (note x) (set! trail (cons x trail)))" => "#<void>",
+        "(+ 1 (call/cc (lambda (k)
+            (dynamic-wind
+              (lambda () (note 'before))
+              (lambda () (k 10) (note 'unreachable))
+              (lambda () (note 'after))))))" => "11",
+        "(reverse trail)" => "(before after)"
+    ];
+
+    // Re-entering the thunk runs before again
+    evals![
+        "(define trail '())" => "#<void>",
+        "(define (note x) (set! trail (cons x trail)))" => "#<void>",
+        "(define k #f)" => "#<void>",
+        "(define n 0)" => "#<void>",
+        "(dynamic-wind
+           (lambda () (note 'before))
+           (lambda () (call/cc (lambda (c) (set! k c))) (set! n (+ n 1)))
+           (lambda () (note 'after)))" => "#<void>",
+        "(if (< n 3) (k 'again) n)" => "#<void>",
+        "(if (< n 3) (k 'again) n)" => "#<void>",
+        "(if (< n 3) (k 'again) n)" => "3",
+        "(reverse trail)" => "(before after before after before after)"
+    ];
+
+    // Nested frames unwind innermost first and rewind outermost first
+    evals![
+        "(define trail '())" => "#<void>",
+        "(define (note x) (set! trail (cons x trail)))" => "#<void>",
+        "(define k #f)" => "#<void>",
+        "(define (wind name thunk)
+           (dynamic-wind (lambda () (note (list 'in name))) thunk (lambda () (note (list 'out name)))))" => "#<void>",
+        "(wind 'a (lambda () (wind 'b (lambda () (call/cc (lambda (c) (set! k c)))))))" => "#<void>",
+        "(set! trail '())" => "#<void>",
+        "(let ((c k)) (set! k #f) (c 'done))" => "done",
+        "(reverse trail)" => "((in a) (in b) (out b) (out a))"
+    ];
+
+    // Jumping between sibling frames only crosses the frames that differ
+    evals![
+        "(define trail '())" => "#<void>",
+        "(define (note x) (set! trail (cons x trail)))" => "#<void>",
+        "(define (wind name thunk)
+           (dynamic-wind (lambda () (note (list 'in name))) thunk (lambda () (note (list 'out name)))))" => "#<void>",
+        "(wind 'outer (lambda ()
+           (let ((k (wind 'a (lambda () (call/cc (lambda (c) c))))))
+             (when (procedure? k)
+               (wind 'b (lambda () (k 'jump)))))))" => "#<void>",
+        "(reverse trail)" => "((in outer) (in a) (out a) (in b) (out b) (in a) (out a) (out outer))"
+    ];
+
+    // Wind frames survive garbage collection
+    evals![
+        "(define (loop n acc)
+           (if (= n 0)
+               acc
+               (loop (- n 1)
+                     (dynamic-wind (lambda () #f) (lambda () (+ acc 1)) (lambda () #f)))))" => "#<void>",
+        "(loop 20000 0)" => "20000"
+    ];
+
+    fails![
+        "(dynamic-wind 1 (lambda () 1) (lambda () 2))" =>
+            InvalidSyntax("bad argument to dynamic-wind: 1 is not a procedure".into())
+    ];
+}
+
+#[test]
+fn continuations_in_higher_order_builtins() {
+    // Escaping from a procedure applied by a builtin
+    evals![
+        "(call/cc (lambda (k) (vector-map (lambda (x) (if (= x 2) (k 'escaped) x)) #(1 2 3))))" => "escaped"
+    ];
+
+    // Re-entering a procedure applied by a builtin resumes with the
+    // builtin's state at the time the continuation was captured
+    evals![
+        "(define k2 #f)" => "#<void>",
+        "(define results '())" => "#<void>",
+        "(let ((v (vector-map (lambda (x) (call/cc (lambda (k) (when (= x 2) (set! k2 k)) x))) #(1 2 3))))
+           (set! results (cons v results))
+           (when (= (length results) 1) (k2 20)))" => "#<void>",
+        "results" => "(#(1 20 3) #(1 2 3))"
+    ];
+    evals![
+        "(define k #f)" => "#<void>",
+        "(define results '())" => "#<void>",
+        "(let ((sorted (sort '(2 1) (lambda (a b) (call/cc (lambda (c) (set! k c) (< a b)))))))
+           (set! results (cons sorted results))
+           (when (= (length results) 1) (k #f)))" => "#<void>",
+        "results" => "((2 1) (1 2))"
+    ];
+}
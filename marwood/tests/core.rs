@@ -4,7 +4,8 @@ mod common;
 use marwood::cell;
 use marwood::cell::Cell;
 use marwood::error::Error::{
-    InvalidProcedure, InvalidSyntax, InvalidUsePrimitive, UnquotedNil, VariableNotBound,
+    ExpectedPairButFound, InvalidNumArgs, InvalidProcedure, InvalidSyntax, InvalidUsePrimitive,
+    UnquotedNil, VariableNotBound,
 };
 use marwood::lex;
 use marwood::parse;
@@ -53,6 +54,50 @@ fn quasiquote() {
         "``(x ,x ,,x)" => "(quasiquote (x (unquote x) (unquote 42)))",
         "``#(x ,x ,,x)" => "(quasiquote #(x (unquote x) (unquote 42)))"
     ];
+    evals![
+        "(define (f x) `#(,x))" => "#<void>",
+        "(f 1)" => "#(1)",
+        "(f 2)" => "#(2)",
+        "`(1 . ,(+ 1 1))" => "(1 . 2)",
+        "`(1 2 . ,(list 3 4))" => "(1 2 3 4)",
+        "``(1 . ,(+ 1 1))" => "(quasiquote (1 unquote (+ 1 1)))"
+    ];
+}
+
+#[test]
+fn unquote_splicing() {
+    evals![
+        "(define xs '(2 3))" => "#<void>",
+        "`(1 ,@xs 4)" => "(1 2 3 4)",
+        "`(,@xs)" => "(2 3)",
+        "`(,@xs ,@xs)" => "(2 3 2 3)",
+        "`(1 ,@'() 2)" => "(1 2)",
+        "`(1 ,@xs . 4)" => "(1 2 3 . 4)",
+        "`(1 ,@xs . ,(+ 2 2))" => "(1 2 3 . 4)",
+        "`(1 ,@5)" => "(1 . 5)",
+        "`(,@5)" => "5",
+        "`(1 ,@xs ,@'(4 . 5))" => "(1 2 3 4 . 5)",
+        "`#(1 ,@xs 4)" => "#(1 2 3 4)",
+        "`#(,@xs)" => "#(2 3)",
+        "`#(,@'())" => "#()",
+        "`((a ,@xs) #(b ,@xs))" => "((a 2 3) #(b 2 3))",
+        "(eq? xs (cdr `(1 ,@xs)))" => "#t",
+        "(eq? xs (cdr `(1 ,@xs 4)))" => "#f"
+    ];
+    evals![
+        "(define x '(1 2))" => "#<void>",
+        "``(a ,@x)" => "(quasiquote (a (unquote-splicing x)))",
+        "``(a ,,@x)" => "(quasiquote (a (unquote 1 2)))",
+        "``(a ,@,@x)" => "(quasiquote (a (unquote-splicing 1 2)))",
+        "`(a `(b ,(c ,@x)))" => "(a (quasiquote (b (unquote (c 1 2)))))"
+    ];
+    fails![
+        "`,@'(1 2)" => InvalidSyntax("(unquote-splicing '(1 2)) is not in a list or vector context".into()),
+        "`(1 ,@2 3)" => ExpectedPairButFound(cell![2]),
+        "`#(1 ,@2)" => ExpectedPairButFound(cell![2]),
+        "`(1 ,@'(2 . 3) 4)" => InvalidSyntax("(2 . 3) is an improper list".into()),
+        "`(1 . ,@'(2))" => InvalidSyntax("(unquote-splicing '(2)) is not in a list or vector context".into())
+    ];
 }
 
 #[test]
@@ -216,6 +261,48 @@ fn vararg() {
     ];
 }
 
+#[test]
+fn case_lambda() {
+    evals![
+        "(define plus
+           (case-lambda
+             (() 0)
+             ((x) x)
+             ((x y) (+ x y))
+             ((x y . rest) (apply plus (+ x y) rest))))" => "#<void>",
+        "(plus)" => "0",
+        "(plus 1)" => "1",
+        "(plus 1 2)" => "3",
+        "(plus 1 2 3 4)" => "10",
+        "(apply plus '(1 2 3))" => "6",
+        "(procedure? plus)" => "#t",
+        "(map plus '(1 2) '(10 20))" => "(11 22)"
+    ];
+    evals![
+        "(define (make-counter n)
+           (case-lambda
+             (() n)
+             ((step) (set! n (+ n step)) n)))" => "#<void>",
+        "(define c (make-counter 10))" => "#<void>",
+        "(c 5)" => "15",
+        "(c)" => "15",
+        "((case-lambda ((x) (list 'one x)) (args (cons 'many args))) 1)" => "(one 1)",
+        "((case-lambda ((x) (list 'one x)) (args (cons 'many args))))" => "(many)",
+        "(let ((x 'outer)) ((case-lambda ((x) x) (() x))))" => "outer",
+        "(define (loop n) (if (= n 0) 'done ((case-lambda ((n) (loop (- n 1)))) n)))" => "#<void>",
+        "(loop 10000)" => "done"
+    ];
+    prints![
+        "(case-lambda ((x) x) ((x y) y))" => "#<procedure:(case-lambda (λ (x)) (λ (x y)))>"
+    ];
+    fails![
+        "((case-lambda ((x) x) ((x y) y)))" => InvalidNumArgs("(case-lambda (λ (x)) (λ (x y)))".into()),
+        "((case-lambda))" => InvalidNumArgs("(case-lambda)".into()),
+        "(case-lambda x)" => InvalidSyntax("expected case-lambda clause, but got x".into()),
+        "(define case-lambda 1)" => InvalidUsePrimitive("case-lambda".into())
+    ];
+}
+
 #[test]
 fn tail_recursive() {
     evals![r#"(define (nth l n)
@@ -252,6 +339,8 @@ fn and() {
 #[test]
 fn begin() {
     evals!["(begin (+ 10 10) (+ 20 20) (+ 5 5))" => "10"];
+    evals!["(define (f x) (begin (set! x (+ x 1)) (begin x)))" => "#<void>",
+           "(f 1)" => "2"];
 }
 
 #[test]
@@ -271,6 +360,83 @@ fn let_star() {
     evals!["(let* ([x 10] [y (* x x)]) (+ x y))" => "110"]
 }
 
+#[test]
+fn named_let() {
+    evals!["(let loop ((i 0) (acc '())) (if (= i 3) acc (loop (+ i 1) (cons i acc))))" => "(2 1 0)"];
+    evals!["(let loop ((i 0)) (set! i (+ i 10)) (if (< i 35) (loop i) i))" => "40"];
+    evals!["(let loop ((i 0)) (let ((j (* i 2))) (if (< i 3) (loop (+ i 1)) j)))" => "6"];
+    evals!["(let loop ((i 0)) (when (< i 3) (set! i i) (loop (+ i 1))))" => "#<void>"];
+    evals!["(let loop ((loop 5)) loop)" => "5"];
+    evals![
+        "(let outer ((i 0) (acc '()))
+           (if (= i 2)
+               acc
+               (outer (+ i 1)
+                      (let inner ((j 0) (acc acc))
+                        (if (= j 2) acc (inner (+ j 1) (cons (list i j) acc)))))))"
+            => "((1 1) (1 0) (0 1) (0 0))"
+    ];
+    evals!["(let loop ((i 0) (fs '()))
+              (if (= i 3) (map (lambda (f) (f)) fs) (loop (+ i 1) (cons (lambda () i) fs))))"
+            => "(2 1 0)"];
+
+    // Each iteration has its own bindings, which a continuation captured by
+    // an earlier iteration sees when it's re-entered
+    evals![
+        "(define k #f)" => "#<void>",
+        "(define out '())" => "#<void>",
+        "(let loop ((i 0))
+           (if (< i 3)
+               (begin
+                 (if (= i 0) (call/cc (lambda (c) (set! k c))))
+                 (set! out (cons i out))
+                 (loop (+ i 1)))))" => "#<void>",
+        "out" => "(2 1 0)",
+        "(k #f)" => "#<void>",
+        "out" => "(2 1 0 2 1 0)"
+    ];
+
+    // Loops that can't be compiled into jumps
+    evals!["(let loop ((l '(1 2 3))) (if (null? l) '() (cons (* 2 (car l)) (loop (cdr l)))))" => "(2 4 6)"];
+    evals!["(let loop ((i 0)) (define x 10) (if (< i 3) (loop (+ i 1)) (+ i x)))" => "13"];
+    prints!["((let loop ((i 0)) (if (< i 3) (loop (+ i 1)) loop)) 10)" => "#<procedure:(λ (i))>"];
+
+    fails!["(let loop ((i 0)) (if (< i 1) (loop) i))" => InvalidNumArgs("(λ (i))".into())];
+    fails!["(let 1 ((x 1)) 1)" => InvalidSyntax("expected variable, but got 1".into())];
+
+    // The loop keyword isn't reserved
+    evals![
+        "(define named-let 1)" => "#<void>",
+        "(let loop ((i named-let)) (if (< i 3) (loop (+ i 1)) i))" => "3"
+    ];
+}
+
+#[test]
+fn do_loop() {
+    evals!["(do ((vec (make-vector 5)) (i 0 (+ i 1))) ((= i 5) vec) (vector-set! vec i i))" => "#(0 1 2 3 4)"];
+    evals!["(let ((x '(1 3 5 7 9))) (do ((x x (cdr x)) (sum 0 (+ sum (car x)))) ((null? x) sum)))" => "25"];
+    evals!["(do ((i 0 (+ i 1))) ((= i 3)))" => "#<void>"];
+    evals!["(do ((i 0 (+ i 1))) (#t 'a 'b))" => "b"];
+    evals!["(do ((i 0 (+ i 1)) (acc '())) ((= i 3) acc) (set! acc (cons i acc)))" => "(2 1 0)"];
+    evals![
+        "(let ((acc '()))
+           (do ((i 0 (+ i 1))) ((= i 2) acc)
+             (do ((j 0 (+ j 1))) ((= j 2))
+               (set! acc (cons (list i j) acc)))))"
+            => "((1 1) (1 0) (0 1) (0 0))"
+    ];
+    evals!["(do ((i 0 (+ i 1))) ((= i 100000) i))" => "100000"];
+    evals![
+        "(define k #f)" => "#<void>",
+        "(define out '())" => "#<void>",
+        "(do ((i 0 (+ i 1))) ((= i 3))
+           (if (= i 0) (call/cc (lambda (c) (set! k c))))
+           (set! out (cons i out)))" => "#<void>",
+        "(if (< (length out) 6) (k #f))" => "#<void>",
+        "out" => "(2 1 0 2 1 0)"
+    ];
+}
+
 #[test]
 fn set() {
     evals!["(define (generator) (let ([x 0]) (lambda () (set! x (+ x 1)) x)))" => "#<void>",
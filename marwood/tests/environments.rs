@@ -0,0 +1,143 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::error::Error::{InvalidSyntax, VariableNotBound};
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::Vm;
+
+#[test]
+fn eval_with_environment() {
+    evals![
+        "(eval '(+ 1 2))" => "3",
+        "(eval '(+ 1 2) (interaction-environment))" => "3",
+        "(define x 10)" => "#<void>",
+        "(eval 'x (interaction-environment))" => "10",
+        "(eval '(define y 20) (interaction-environment))" => "#<void>",
+        "y" => "20",
+        "(environment? (interaction-environment))" => "#t",
+        "(environment? 'foo)" => "#f"
+    ];
+    fails![
+        "(eval 1 2)" => InvalidSyntax("bad argument to eval: 2 is not an environment".into())
+    ];
+}
+
+#[test]
+fn scheme_report_environment() {
+    evals![
+        "(define car cdr)" => "#<void>",
+        "(define x 10)" => "#<void>",
+        "(define env (scheme-report-environment 7))" => "#<void>",
+        "(eval '(car '(1 2)) env)" => "1",
+        "(eval '(define x 20) env)" => "#<void>",
+        "(eval 'x env)" => "20",
+        "x" => "10"
+    ];
+    evals![
+        "(eval '(let ((a 1)) (if (and a #t) 'yes 'no)) (null-environment 5))" => "yes"
+    ];
+    fails![
+        "(eval '(car '(1)) (null-environment 5))" => VariableNotBound("car".into()),
+        "(scheme-report-environment 6)" =>
+            InvalidSyntax("scheme-report-environment: unsupported version 6".into())
+    ];
+}
+
+#[test]
+fn isolated_environments() {
+    evals![
+        "(define restricted (copy-environment (interaction-environment) '(+ *)))" => "#<void>",
+        "(eval '(let* ((a 2) (b (+ a 1))) (* a b)) restricted)" => "6",
+        "(eval '(define y 5) restricted)" => "#<void>",
+        "(eval 'y restricted)" => "5",
+        "(eval '(define + *) restricted)" => "#<void>",
+        "(eval '(+ 5 2) restricted)" => "10",
+        "(+ 5 2)" => "7"
+    ];
+    evals![
+        "(define a (copy-environment (interaction-environment) '(list)))" => "#<void>",
+        "(eval '(define x 1) a)" => "#<void>",
+        "(define b (copy-environment a))" => "#<void>",
+        "(eval '(set! x 2) b)" => "#<void>",
+        "(list (eval 'x a) (eval 'x b))" => "(1 2)"
+    ];
+    fails![
+        "(eval '(car '(1)) (copy-environment (interaction-environment) '(+)))" =>
+            VariableNotBound("car".into()),
+        "(eval 'y (copy-environment (interaction-environment) '(+)))" =>
+            VariableNotBound("y".into()),
+        "(copy-environment (interaction-environment) '(not-bound))" =>
+            VariableNotBound("not-bound".into()),
+        "(copy-environment (interaction-environment) '(1))" =>
+            InvalidSyntax("bad argument to copy-environment: (1) is not a list of symbols".into())
+    ];
+}
+
+#[test]
+fn environments_from_rust() {
+    let mut vm = Vm::new();
+    vm.eval(&parse!("(define secret 42)")).unwrap();
+    let restricted = vm.make_environment(&["+", "list"]).unwrap();
+    assert_eq!(
+        vm.eval_in(&parse!("(list (+ 1 2))"), &restricted),
+        Ok(parse!("(3)"))
+    );
+    assert_eq!(
+        vm.eval_in(&parse!("secret"), &restricted),
+        Err(VariableNotBound("secret".into()))
+    );
+    vm.eval_in(&parse!("(define secret 0)"), &restricted)
+        .unwrap();
+    assert_eq!(vm.eval(&parse!("secret")), Ok(parse!("42")));
+    assert_eq!(
+        vm.eval_in(&parse!("(import (scheme file))"), &restricted),
+        Err(InvalidSyntax(
+            "out of context import: (import (scheme file))".into()
+        ))
+    );
+    assert_eq!(
+        vm.make_environment(&["not-bound"]),
+        Err(VariableNotBound("not-bound".into()))
+    );
+}
+
+#[test]
+fn dropped_environments() {
+    evals![
+        "(define e (scheme-report-environment 5))" => "#<void>",
+        "(eval '(define (a) 'a) e)" => "#<void>",
+        "(eval '(define (b) (list (a) (car '(b)))) e)" => "#<void>",
+        "(eval '(define-syntax twice (syntax-rules () ((_ x) (list x x)))) e)" => "#<void>",
+        "(define b (eval 'b e))" => "#<void>",
+        "(define c (eval '(lambda () (twice (a))) e))" => "#<void>",
+        "(set! e #f)" => "#<void>",
+        "(let loop ((i 0))
+           (if (< i 1000)
+               (begin (eval '(define x 1) (scheme-report-environment 5))
+                      (loop (+ i 1)))))" => "#<void>",
+        "(b)" => "(a b)",
+        "(c)" => "(a a)"
+    ];
+}
+
+#[test]
+fn derived_environments_cannot_import() {
+    evals![
+        "(define r (copy-environment (interaction-environment) '(+)))" => "#<void>",
+        "(eval '(+ 1 2) r)" => "3",
+        "(eval '(import (scheme base)) (interaction-environment))" => "#<void>"
+    ];
+    fails![
+        "(eval '(import (scheme file)) (copy-environment (interaction-environment) '(+)))" =>
+            InvalidSyntax("out of context import: (import (scheme file))".into()),
+        "(eval '(import (marwood internal)) (scheme-report-environment 5))" =>
+            InvalidSyntax("out of context import: (import (marwood internal))".into()),
+        "(eval '(import (scheme base)) (null-environment 5))" =>
+            InvalidSyntax("out of context import: (import (scheme base))".into()),
+        "(eval '(define-library (example x) (export)) (environment '(scheme base)))" =>
+            InvalidSyntax("out of context define-library: (define-library (example x) (export))".into()),
+        "(eval '(file-exists? \"x\") (copy-environment (interaction-environment) '(+)))" =>
+            VariableNotBound("file-exists?".into())
+    ];
+}
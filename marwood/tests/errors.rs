@@ -0,0 +1,57 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::error::Error::{ErrorSignal, InvalidSyntax};
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::Vm;
+
+#[test]
+fn error_objects() {
+    evals![
+        "(define e (guard (e (#t e)) (error \"something bad:\" 42 'foo \"bar\")))" => "#<void>",
+        "(error-object? e)" => "#t",
+        "(error-object-message e)" => "\"something bad:\"",
+        "(error-object-irritants e)" => "(42 foo \"bar\")",
+        "(error-object-kind e)" => "error",
+        "(file-error? e)" => "#f",
+        "(read-error? e)" => "#f",
+        "(error-object? 'foo)" => "#f",
+        "(file-error? 'foo)" => "#f",
+        "(read-error? \"foo\")" => "#f"
+    ];
+    evals![
+        "(define irritant (list 1 2))" => "#<void>",
+        "(eq? irritant (car (guard (e (#t (error-object-irritants e))) (error \"bad list\" irritant))))" => "#t",
+        "(guard (e ((error-object? e) (error-object-irritants e))) (error \"no irritants\"))" => "()",
+        "(guard (e ((string? e) 'string) ((error-object? e) (error-object-message e))) (error \"message\"))" => "\"message\"",
+        "(guard (e ((error-object? e) (list (error-object-message e) (error-object-irritants e)))) (raise (guard (e (#t e)) (error \"reraised\" 1))))" => "(\"reraised\" (1))"
+    ];
+    fails![
+        "(error \"something bad:\" 42 'foo)" => ErrorSignal(vec![Cell::new_string("something bad:"), Cell::from(42), Cell::new_symbol("foo")]),
+        "(error-object-message 'foo)" => InvalidSyntax("bad argument to error-object-message: foo is not an error object".into()),
+        "(error-object-irritants 10)" => InvalidSyntax("bad argument to error-object-irritants: 10 is not an error object".into())
+    ];
+}
+
+#[test]
+fn typed_conditions() {
+    evals![
+        "(define (kind-of thunk) (guard (e ((error-object? e) (error-object-kind e))) (thunk)))" => "#<void>",
+        "(kind-of (lambda () (error \"user error\")))" => "error",
+        "(kind-of (lambda () (car 5)))" => "type-error",
+        "(kind-of (lambda () (5 1 2)))" => "type-error",
+        "(kind-of (lambda () ((lambda (x) x))))" => "arity-error",
+        "(kind-of (lambda () (vector-ref (vector 1 2) 2)))" => "range-error",
+        "(kind-of (lambda () (string-ref \"abc\" 5)))" => "range-error",
+        "(kind-of (lambda () undefined-variable))" => "unbound-variable",
+        "(kind-of (lambda () (eval '(lambda (x)))))" => "syntax-error",
+        "(kind-of (lambda () (/ 1 0)))" => "runtime-error"
+    ];
+    evals![
+        "(guard (e ((error-object? e) (list (error-object-message e) (error-object-irritants e)))) (car 5))" => "(\"expected pair\" (5))",
+        "(guard (e ((error-object? e) (list (error-object-message e) (error-object-irritants e)))) (vector-ref (vector 1) 3))" => "(\"vector index out of range\" (3))",
+        "(guard (e ((error-object? e) (list (error-object-message e) (error-object-irritants e)))) unbound-thing)" => "(\"variable is not bound\" (unbound-thing))",
+        "(guard (e ((error-object? e) (list (error-object-message e) (error-object-irritants e)))) (car car))" => "(\"expected pair, but found #<procedure:car>\" ())"
+    ];
+}
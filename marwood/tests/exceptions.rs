@@ -0,0 +1,118 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::error::Error::{
+    ExpectedPairButFound, InvalidSyntax, InvalidVectorIndex, UncaughtException,
+};
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::Vm;
+
+#[test]
+fn raise() {
+    evals![
+        "(guard (e (#t (list 'caught e))) (raise 'boom))" => "(caught boom)",
+        "(guard (e ((symbol? e) (list 'sym e)) ((string? e) (list 'str e))) (raise \"oops\"))" => "(str \"oops\")",
+        "(guard (e (#t 'outer)) (guard (e ((string? e) 'inner)) (raise 'boom)))" => "outer",
+        "(guard (e (#t e)) (+ 1 (raise 10)))" => "10"
+    ];
+    fails![
+        "(raise 'boom)" => UncaughtException(parse!["boom"]),
+        "(raise '(1 2))" => UncaughtException(parse!["(1 2)"]),
+        "(guard (e ((string? e) 'str)) (raise 'boom))" => UncaughtException(parse!["boom"])
+    ];
+}
+
+#[test]
+fn raise_continuable() {
+    evals![
+        "(with-exception-handler (lambda (e) 10) (lambda () (+ 1 (raise-continuable 'c))))" => "11",
+        "(with-exception-handler
+            (lambda (e) (* e 2))
+            (lambda ()
+              (+ (raise-continuable 1) (raise-continuable 2))))" => "6",
+        "(with-exception-handler
+            (lambda (e) (+ e 1))
+            (lambda ()
+              (with-exception-handler
+                (lambda (e) (raise-continuable (* e 10)))
+                (lambda () (raise-continuable 1)))))" => "11",
+        "(guard (e ((string? e) 'str)) (list (guard (e ((symbol? e) 'sym)) (raise-continuable \"s\"))))" => "str"
+    ];
+    fails![
+        "(raise-continuable 'boom)" => UncaughtException(parse!["boom"])
+    ];
+}
+
+#[test]
+fn with_exception_handler() {
+    evals![
+        "(define trail '())" => "#<void>",
+        "(call/cc
+           (lambda (k)
+             (with-exception-handler
+               (lambda (e) (set! trail (cons e trail)) (k 'escaped))
+               (lambda () (raise 'boom)))))" => "escaped",
+        "trail" => "(boom)",
+        "(with-exception-handler (lambda (e) 0) (lambda () 'normal))" => "normal",
+        "(call-with-values (lambda () (with-exception-handler (lambda (e) 0) (lambda () (values 1 2)))) list)" => "(1 2)"
+    ];
+    evals![
+        "(define handled '())" => "#<void>",
+        "(guard (e (#t (list 'outer e)))
+           (with-exception-handler
+             (lambda (e) (set! handled (cons e handled)) 'ignored)
+             (lambda () (raise 'boom))))" => "(outer boom)",
+        "handled" => "(boom)"
+    ];
+    fails![
+        "(with-exception-handler (lambda (e) 'ignored) (lambda () (raise 'boom)))" => UncaughtException(parse!["boom"]),
+        "(with-exception-handler 1 (lambda () 1))" => InvalidSyntax("bad argument to with-exception-handler: 1 is not a procedure".into())
+    ];
+}
+
+#[test]
+fn guard() {
+    evals![
+        "(guard (e ((assq 'a e) => cdr) ((assq 'b e))) (raise (list (cons 'a 42))))" => "42",
+        "(guard (e ((assq 'a e) => cdr) ((assq 'b e))) (raise (list (cons 'b 23))))" => "(b . 23)",
+        "(guard (e ((string? e) 'str) (else 'other)) (raise 1))" => "other",
+        "(guard (e (#f 'none)) 'normal)" => "normal",
+        "(guard (e (#f 'none)) 1 2 3)" => "3",
+        "(call-with-values (lambda () (guard (e (#f 'none)) (values 1 2))) list)" => "(1 2)"
+    ];
+    evals![
+        "(define trail '())" => "#<void>",
+        "(guard (e (#t (set! trail (cons 'guard trail)) e))
+           (dynamic-wind
+             (lambda () (set! trail (cons 'before trail)))
+             (lambda () (raise 'boom))
+             (lambda () (set! trail (cons 'after trail)))))" => "boom",
+        "trail" => "(guard after before)"
+    ];
+    evals![
+        "(define (safe-div a b) (guard (e (#t 'div-error)) (if (= b 0) (raise 'zero) (/ a b))))" => "#<void>",
+        "(define (loop n acc) (if (= n 0) acc (loop (- n 1) (if (eq? (safe-div n 0) 'div-error) (+ acc 1) acc))))" => "#<void>",
+        "(loop 20000 0)" => "20000"
+    ];
+}
+
+#[test]
+fn runtime_errors_are_conditions() {
+    prints![
+        "(guard (e (#t e)) (vector-ref (vector 1 2) 5))" => "#<condition: vector index 5 out of range of 0..1>",
+        "(guard (e (#t e)) undefined-variable)" => "#<condition: undefined-variable is not bound>",
+        "(guard (e (#t e)) (error \"bad thing:\" 1 2))" => "#<condition: bad thing: 1 2>",
+        "(guard (e (#t e)) (car 5))" => "#<condition: expected pair, but found 5>"
+    ];
+    evals![
+        "(define v (vector 1 2 3))" => "#<void>",
+        "(guard (e (#t 'recovered)) (vector-ref v 10))" => "recovered",
+        "(vector-ref v 2)" => "3",
+        "(guard (e ((symbol? e) e)) (with-exception-handler (lambda (e) (raise 'replaced)) (lambda () (car '()))))" => "replaced"
+    ];
+    fails![
+        "(guard (e ((string? e) 'str)) (vector-ref (vector 1) 1))" => InvalidVectorIndex(1, 1),
+        "(with-exception-handler (lambda (e) 0) (lambda () (car 5)))" => ExpectedPairButFound(parse!["5"])
+    ];
+}
@@ -0,0 +1,178 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::error::Error::{InvalidSyntax, LibraryNotFound, VariableNotBound};
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::library::MemoryResolver;
+use marwood::vm::Vm;
+
+#[test]
+fn define_and_import_library() {
+    evals![
+        "(define-library (example stack)
+           (export make-stack push! (rename stack-top top))
+           (import (scheme base))
+           (begin
+             (define (make-stack) (list 'stack))
+             (define (push! stack x) (set-cdr! stack (cons x (cdr stack))))
+             (define (stack-top stack) (cadr stack))))" => "#<void>",
+        "(import (example stack))" => "#<void>",
+        "(define s (make-stack))" => "#<void>",
+        "(push! s 1)" => "#<void>",
+        "(push! s 2)" => "#<void>",
+        "(top s)" => "2"
+    ];
+    fails![
+        "(define-library (example bad) (export missing) (begin (define present 1)))" =>
+            InvalidSyntax("library (example bad) exports missing, which is not defined".into()),
+        "(define-library (example bad) (include-library-declarations \"bad.scm\"))" =>
+            InvalidSyntax("invalid library declaration (include-library-declarations \"bad.scm\")".into()),
+        "(define-library bad (export x))" => InvalidSyntax("invalid library name bad".into()),
+        "(import (example missing))" => LibraryNotFound("(example missing)".into())
+    ];
+}
+
+#[test]
+fn import_sets() {
+    evals![
+        "(define-library (example numbers)
+           (export one two three)
+           (import (scheme base))
+           (begin (define one 1) (define two 2) (define three 3)))" => "#<void>",
+        "(import (only (example numbers) one two))" => "#<void>",
+        "(list one two)" => "(1 2)",
+        "(import (prefix (except (example numbers) one) n:))" => "#<void>",
+        "(list n:two n:three)" => "(2 3)",
+        "(import (rename (only (example numbers) three) (three drei)))" => "#<void>",
+        "drei" => "3"
+    ];
+    fails![
+        "(import (only (scheme base) no-such-procedure))" =>
+            InvalidSyntax("no-such-procedure is not exported by (scheme base)".into()),
+        "(import (rename (scheme base) car))" =>
+            InvalidSyntax("invalid import set (rename (scheme base) car)".into()),
+        "(import (prefix (scheme base) 1))" =>
+            InvalidSyntax("expected identifier, but got 1".into())
+    ];
+}
+
+#[test]
+fn imported_bindings() {
+    let mut vm = Vm::new();
+    vm.eval(&parse!(
+        "(define-library (example counter)
+           (export count increment!)
+           (import (scheme base))
+           (begin
+             (define count 0)
+             (define (increment!) (set! count (+ count 1)))))"
+    ))
+    .unwrap();
+    vm.eval(&parse!("(import (example counter))")).unwrap();
+    vm.eval(&parse!("(increment!)")).unwrap();
+    assert_eq!(vm.eval(&parse!("count")), Ok(parse!("1")));
+    assert_eq!(
+        vm.eval(&parse!("(set! count 10)")),
+        Err(InvalidSyntax(
+            "cannot assign imported variable count".into()
+        ))
+    );
+
+    // A definition shadows the import
+    vm.eval(&parse!("(define count 10)")).unwrap();
+    vm.eval(&parse!("(set! count 11)")).unwrap();
+    vm.eval(&parse!("(increment!)")).unwrap();
+    assert_eq!(vm.eval(&parse!("count")), Ok(parse!("11")));
+}
+
+#[test]
+fn library_macros_use_library_bindings() {
+    evals![
+        "(define-library (example swap)
+           (export swap!)
+           (import (scheme base))
+           (begin
+             (define (swapped a b) (list b a))
+             (define-syntax swap!
+               (syntax-rules ()
+                 ((_ a b)
+                  (let ((tmp (swapped a b)))
+                    (set! a (car tmp))
+                    (set! b (cadr tmp))))))))" => "#<void>",
+        "(import (example swap))" => "#<void>",
+        "(define x 1)" => "#<void>",
+        "(define y 2)" => "#<void>",
+        "(swap! x y)" => "#<void>",
+        "(list x y)" => "(2 1)"
+    ];
+    fails![
+        "swapped" => VariableNotBound("swapped".into())
+    ];
+}
+
+#[test]
+fn standard_libraries() {
+    evals![
+        "(import (scheme base) (scheme write))" => "#<void>",
+        "(define env (environment '(scheme base)))" => "#<void>",
+        "(eval '(let loop ((i 0)) (if (< i 3) (loop (+ i 1)) i)) env)" => "3",
+        "(eval '(do ((i 0 (+ i 1))) ((= i 3) i)) env)" => "3",
+        "(eval '(force (delay 1)) (environment '(scheme base) '(scheme lazy)))" => "1",
+        "(eval '(car '(1 2)) (environment '(only (scheme base) car)))" => "1",
+        "(eval '(caddr '(1 2 3)) (environment '(scheme cxr)))" => "3",
+        "(eval '(cdaddr '(1 2 (3 4))) (environment '(scheme cxr)))" => "(4)",
+        "(cadddr '(1 2 3 4))" => "4"
+    ];
+    fails![
+        "(environment '(scheme time))" => LibraryNotFound("(scheme time)".into()),
+        "(eval '(display 1) (environment '(scheme base)))" => VariableNotBound("display".into()),
+        "map1" => VariableNotBound("map1".into()),
+        "do-aux" => VariableNotBound("do-aux".into()),
+        "(environment '(scheme base) 1)" => InvalidSyntax("invalid library name 1".into())
+    ];
+}
+
+#[test]
+fn library_resolvers() {
+    let mut vm = Vm::new();
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        &["example", "greeting"],
+        "(define-library (example greeting)
+           (export greeting)
+           (import (scheme base) (example name))
+           (begin (define greeting (string-append \"hello \" name))))",
+    );
+    resolver.insert(
+        &["example", "name"],
+        "(define-library (example name) (export name) (begin (define name \"world\")))",
+    );
+    resolver.insert(&["example", "empty"], "(define x 1)");
+    resolver.insert(
+        &["example", "cycle"],
+        "(define-library (example cycle) (import (example cycle)))",
+    );
+    vm.add_library_resolver(Box::new(resolver));
+
+    vm.eval(&parse!("(import (example greeting))")).unwrap();
+    assert_eq!(vm.eval(&parse!("greeting")), Ok(parse!("\"hello world\"")));
+    assert_eq!(
+        vm.eval(&parse!("(import (example empty))")),
+        Err(InvalidSyntax(
+            "the source of library (example empty) doesn't define it".into()
+        ))
+    );
+    assert_eq!(
+        vm.eval(&parse!("(import (example cycle))")),
+        Err(InvalidSyntax(
+            "library (example cycle) imports itself".into()
+        ))
+    );
+
+    vm.clear_library_resolvers();
+    assert_eq!(
+        vm.eval(&parse!("(import (example empty))")),
+        Err(LibraryNotFound("(example empty)".into()))
+    );
+}
@@ -103,3 +103,98 @@ fn assoc() {
            "(assv '(1 2) '((0 foo) ((1 2) bar) (2 baz)))" => "#f"
     ];
 }
+
+#[test]
+fn member() {
+    evals!["(member '(1) '(2 (1) 3))" => "((1) 3)",
+           "(member 4 '(1 2 3))" => "#f",
+           "(member 1 '())" => "#f",
+           "(member 2.0 '(1 2 3) =)" => "(2 3)",
+           "(member 2 '(1 2 3) (lambda (x y) (< x y)))" => "(3)",
+           "(member 'x '(1 2) eq?)" => "#f"
+    ];
+    fails!["(member 1 '(2 . 3))" => ExpectedPairButFound(cell![3]),
+           "(member 1 '(2 . 3) =)" => ExpectedPairButFound(cell![3]),
+           "(member 1 '(1) 5)" => InvalidSyntax("bad argument to member: 5 is not a procedure".into()),
+           "(member 1)" => InvalidNumArgs("member".into())
+    ];
+}
+
+#[test]
+fn assoc_with_predicate() {
+    evals!["(assoc 2.0 '((1 one) (2 two) (3 three)) =)" => "(2 two)",
+           "(assoc 5 '((1 one) (2 two)) =)" => "#f",
+           "(assoc \"b\" '((a 1) x (\"b\" 2)))" => "(\"b\" 2)",
+           "(assoc 2 '(x (1 one) y (2 two)) =)" => "(2 two)",
+           "(assoc 1 '((2 two) (3 three)) (lambda (x y) (< x y)))" => "(2 two)"
+    ];
+    fails!["(assoc 1 '((2 two) . 3) =)" => ExpectedPairButFound(cell![3]),
+           "(assoc 1 '((1 one)) 'foo)" => InvalidSyntax("bad argument to assoc: foo is not a procedure".into())
+    ];
+}
+
+#[test]
+fn list_copy() {
+    evals!["(list-copy '(1 2 3))" => "(1 2 3)",
+           "(list-copy '())" => "()",
+           "(list-copy '(1 2 . 3))" => "(1 2 . 3)",
+           "(list-copy 5)" => "5"
+    ];
+    evals!["(define a (list 1 2 3))" => "#<void>",
+           "(define b (list-copy a))" => "#<void>",
+           "(set-car! b 10)" => "#<void>",
+           "(list a b)" => "((1 2 3) (10 2 3))",
+           "(eq? a b)" => "#f"
+    ];
+}
+
+#[test]
+fn make_list() {
+    evals!["(make-list 0)" => "()",
+           "(make-list 2)" => "(0 0)",
+           "(make-list 3 'a)" => "(a a a)"
+    ];
+    fails!["(make-list -1)" => InvalidSyntax("-1 is not a valid size".into())];
+}
+
+#[test]
+fn list_set() {
+    evals!["(define l (list 1 2 3))" => "#<void>",
+           "(list-set! l 0 'one)" => "#<void>",
+           "(list-set! l 2 'three)" => "#<void>",
+           "l" => "(one 2 three)"
+    ];
+    fails!["(list-set! (list 1 2) 2 0)" => InvalidSyntax("2 is out of range for (1 2)".into()),
+           "(list-set! 5 0 0)" => ExpectedPairButFound(cell![5])
+    ];
+}
+
+#[test]
+fn sort() {
+    evals!["(sort '(3 1 2) <)" => "(1 2 3)",
+           "(sort '() <)" => "()",
+           "(sort '(1) <)" => "(1)",
+           "(sort #(5 3 9 1 1 0) <)" => "#(0 1 1 3 5 9)",
+           "(sort #() <)" => "#()",
+           "(sort (list \"pear\" \"apple\" \"fig\") string<?)" => "(\"apple\" \"fig\" \"pear\")"
+    ];
+
+    // The sort is stable, and doesn't modify the sequence
+    evals!["(define l '((b 1) (a 2) (b 0) (a 1)))" => "#<void>",
+           "(sort l (lambda (x y) (string<? (symbol->string (car x)) (symbol->string (car y)))))" =>
+                "((a 2) (a 1) (b 1) (b 0))",
+           "l" => "((b 1) (a 2) (b 0) (a 1))"
+    ];
+
+    evals!["(define (build n acc) (if (= n 0) acc (build (- n 1) (cons (modulo (* n 7919) 10007) acc))))" => "#<void>",
+           "(define (sorted? l) (or (null? l) (null? (cdr l)) (and (<= (car l) (cadr l)) (sorted? (cdr l)))))" => "#<void>",
+           "(define s (sort (build 20000 '()) <))" => "#<void>",
+           "(list (length s) (sorted? s))" => "(20000 #t)"
+    ];
+
+    fails!["(sort '(1 2 . 3) <)" => InvalidSyntax("(1 2 . 3) is an improper list".into()),
+           "(sort 5 <)" => InvalidSyntax("bad argument to sort: 5 is not a list or vector".into()),
+           "(sort '(1 2) 5)" => InvalidSyntax("bad argument to sort: 5 is not a procedure".into()),
+           "(sort '(1 2) car)" => InvalidNumArgs("car".into())
+    ];
+}
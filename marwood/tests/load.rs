@@ -0,0 +1,240 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::error::Error::{
+    ExpectedPairButFound, FileError, InvalidSyntax, LoadError, UncaughtException, VariableNotBound,
+};
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::Vm;
+
+/// Write File
+///
+/// Write text to the file at path in the VM's filesystem
+fn write_file(vm: &Vm, path: &str, text: &str) {
+    vm.filesystem().create_file(path).unwrap();
+    vm.filesystem().append_file(path, text).unwrap();
+}
+
+#[test]
+fn load_source() {
+    let mut vm = Vm::new();
+    assert_eq!(
+        vm.load_source("example.scm", "(define x 10) (define (f y) (* x y)) (f 2)"),
+        Ok(parse!("20"))
+    );
+    assert_eq!(vm.eval(&parse!("(f 3)")), Ok(parse!("30")));
+    assert_eq!(vm.load_source("empty.scm", ""), Ok(Cell::Void));
+    assert_eq!(
+        vm.load_source("unbound.scm", "(define z 1) (+ z w)"),
+        Err(LoadError(
+            "unbound.scm".into(),
+            Box::new(VariableNotBound("w".into()))
+        ))
+    );
+    assert_eq!(vm.eval(&parse!("z")), Ok(parse!("1")));
+    assert!(matches!(
+        vm.load_source("incomplete.scm", "(define x"),
+        Err(LoadError(name, _)) if name == "incomplete.scm"
+    ));
+}
+
+#[test]
+fn eval_file() {
+    let mut vm = Vm::new();
+    write_file(
+        &vm,
+        "macros.scm",
+        "(define-syntax twice (syntax-rules () ((_ e) (begin e e))))
+         (define count 0)
+         (twice (set! count (+ count 1)))
+         count",
+    );
+    assert_eq!(vm.eval_file("macros.scm"), Ok(parse!("2")));
+    assert_eq!(
+        vm.eval_file("missing.scm"),
+        Err(FileError("missing.scm".into(), "file not found".into()))
+    );
+}
+
+#[test]
+fn load() {
+    let mut vm = Vm::new();
+    write_file(
+        &vm,
+        "lib.scm",
+        "(define-syntax inc! (syntax-rules () ((_ v) (set! v (+ v 1)))))
+         (define counter 0)
+         (inc! counter)",
+    );
+    write_file(&vm, "bad.scm", "(define x (car '()))");
+    write_file(&vm, "incomplete.scm", "(define x");
+    assert_eq!(vm.eval(&parse!("(load \"lib.scm\")")), Ok(Cell::Void));
+    assert_eq!(vm.eval(&parse!("counter")), Ok(parse!("1")));
+    assert_eq!(vm.eval(&parse!("(inc! counter)")), Ok(Cell::Void));
+    assert_eq!(vm.eval(&parse!("counter")), Ok(parse!("2")));
+
+    vm.eval(&parse!("(define env (environment '(scheme base)))"))
+        .unwrap();
+    assert_eq!(vm.eval(&parse!("(load \"lib.scm\" env)")), Ok(Cell::Void));
+    assert_eq!(vm.eval(&parse!("(eval 'counter env)")), Ok(parse!("1")));
+    assert_eq!(vm.eval(&parse!("counter")), Ok(parse!("2")));
+
+    assert_eq!(
+        vm.eval(&parse!("(load \"missing.scm\")")),
+        Err(FileError("missing.scm".into(), "file not found".into()))
+    );
+    assert!(matches!(
+        vm.eval(&parse!("(load \"incomplete.scm\")")),
+        Err(LoadError(name, _)) if name == "incomplete.scm"
+    ));
+    assert_eq!(
+        vm.eval(&parse!(
+            "(guard (e ((read-error? e) (error-object-message e))) (load \"incomplete.scm\"))"
+        )),
+        Ok(parse!("\"incomplete.scm: incomplete\""))
+    );
+    assert_eq!(
+        vm.eval(&parse!(
+            "(guard (e ((file-error? e) 'caught)) (load \"missing.scm\"))"
+        )),
+        Ok(parse!("caught"))
+    );
+    assert_eq!(
+        vm.eval(&parse!("(load \"bad.scm\")")),
+        Err(LoadError(
+            "bad.scm".into(),
+            Box::new(ExpectedPairButFound(Cell::Nil))
+        ))
+    );
+}
+
+#[test]
+fn load_errors() {
+    let mut vm = Vm::new();
+    write_file(&vm, "car.scm", "(define y 1) (car 5)");
+    write_file(&vm, "syntax.scm", "(if)");
+    write_file(&vm, "raise.scm", "(raise 'oops)");
+    write_file(&vm, "nested.scm", "(load \"car.scm\")");
+    write_file(&vm, "error.scm", "(error \"boom\" 1)");
+
+    assert_eq!(
+        vm.eval(&parse!("(load \"car.scm\")")),
+        Err(LoadError(
+            "car.scm".into(),
+            Box::new(ExpectedPairButFound(parse!("5")))
+        ))
+    );
+    assert_eq!(vm.eval(&parse!("y")), Ok(parse!("1")));
+    assert!(matches!(
+        vm.eval(&parse!("(load \"syntax.scm\")")),
+        Err(LoadError(name, _)) if name == "syntax.scm"
+    ));
+    assert_eq!(
+        vm.eval(&parse!("(load \"raise.scm\")")),
+        Err(LoadError(
+            "raise.scm".into(),
+            Box::new(UncaughtException(parse!("oops")))
+        ))
+    );
+    assert!(matches!(
+        vm.eval(&parse!("(load \"nested.scm\")")),
+        Err(LoadError(name, _)) if name == "nested.scm"
+    ));
+
+    // A handler is applied to the error as it was raised
+    assert_eq!(
+        vm.eval(&parse!(
+            "(guard (e ((error-object? e) (error-object-message e))) (load \"error.scm\"))"
+        )),
+        Ok(parse!("\"boom\""))
+    );
+    assert_eq!(
+        vm.eval(&parse!("(guard (e ((symbol? e) e)) (load \"raise.scm\"))")),
+        Ok(parse!("oops"))
+    );
+}
+
+#[test]
+fn include() {
+    let mut vm = Vm::new();
+    write_file(&vm, "defs.scm", "(define a 1) (define b 2)");
+    write_file(&vm, "more.scm", "(define c (+ a b)) c");
+    write_file(&vm, "upper.scm", "(DEFINE (Square X) (* X X))");
+    write_file(&vm, "body.scm", "(define d 4) (* d 2)");
+
+    assert_eq!(
+        vm.eval(&parse!("(include \"defs.scm\" \"more.scm\")")),
+        Ok(parse!("3"))
+    );
+    assert_eq!(vm.eval(&parse!("(list a b c)")), Ok(parse!("(1 2 3)")));
+    assert_eq!(
+        vm.eval(&parse!("(include-ci \"upper.scm\")")),
+        Ok(Cell::Void)
+    );
+    assert_eq!(vm.eval(&parse!("(square 4)")), Ok(parse!("16")));
+    assert_eq!(
+        vm.eval(&parse!("(let () (include \"body.scm\"))")),
+        Ok(parse!("8"))
+    );
+    assert_eq!(
+        vm.eval(&parse!("(define (f) (include \"body.scm\"))")),
+        Ok(Cell::Void)
+    );
+    assert_eq!(vm.eval(&parse!("(f)")), Ok(parse!("8")));
+    assert_eq!(vm.eval(&parse!("d")), Err(VariableNotBound("d".into())));
+    write_file(&vm, "local.scm", "(define (g) (h)) (define (h) 'h)");
+    assert_eq!(
+        vm.eval(&parse!("(define (f) (include \"local.scm\") (g))")),
+        Ok(Cell::Void)
+    );
+    assert_eq!(vm.eval(&parse!("(f)")), Ok(parse!("h")));
+    assert_eq!(vm.eval(&parse!("g")), Err(VariableNotBound("g".into())));
+    assert_eq!(
+        vm.eval(&parse!("(include \"missing.scm\")")),
+        Err(FileError("missing.scm".into(), "file not found".into()))
+    );
+    assert_eq!(
+        vm.eval(&parse!("(include foo)")),
+        Err(InvalidSyntax("invalid include (include foo)".into()))
+    );
+}
+
+#[test]
+fn include_errors() {
+    let mut vm = Vm::new();
+    write_file(&vm, "defs.scm", "(define a 1)");
+    write_file(&vm, "unbound.scm", "(define b 2) (undefined-procedure)");
+
+    assert_eq!(
+        vm.eval(&parse!("(include \"defs.scm\" \"unbound.scm\")")),
+        Err(LoadError(
+            "unbound.scm".into(),
+            Box::new(VariableNotBound("undefined-procedure".into()))
+        ))
+    );
+    assert_eq!(vm.eval(&parse!("(list a b)")), Ok(parse!("(1 2)")));
+}
+
+#[test]
+fn include_in_library() {
+    let mut vm = Vm::new();
+    write_file(
+        &vm,
+        "stack.scm",
+        "(define (make-stack) (list 'stack))
+         (define (stack-empty? stack) (null? (cdr stack)))",
+    );
+    vm.eval(&parse!(
+        "(define-library (example stack)
+           (export make-stack stack-empty?)
+           (import (scheme base))
+           (include \"stack.scm\"))"
+    ))
+    .unwrap();
+    vm.eval(&parse!("(import (example stack))")).unwrap();
+    assert_eq!(
+        vm.eval(&parse!("(stack-empty? (make-stack))")),
+        Ok(parse!("#t"))
+    );
+}
@@ -0,0 +1,184 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::error::Error::{InvalidSyntax, VariableNotBound};
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::Vm;
+
+#[test]
+fn introduced_bindings_do_not_capture() {
+    evals![
+        "(define-syntax my-or
+           (syntax-rules ()
+             ((_) #f)
+             ((_ e) e)
+             ((_ e r ...) (let ((temp e)) (if temp temp (my-or r ...))))))" => "#<void>",
+        "(define temp 5)" => "#<void>",
+        "(my-or #f temp)" => "5",
+        "(let ((temp 7)) (my-or #f temp))" => "7",
+        "(let ((temp #f)) (my-or temp #f 3))" => "3"
+    ];
+    evals![
+        "(define-syntax swap!
+           (syntax-rules ()
+             ((_ a b) (let ((tmp a)) (set! a b) (set! b tmp)))))" => "#<void>",
+        "(define tmp 1)" => "#<void>",
+        "(define other 2)" => "#<void>",
+        "(swap! tmp other)" => "#<void>",
+        "(list tmp other)" => "(2 1)"
+    ];
+    evals![
+        "(define-syntax with-helper
+           (syntax-rules ()
+             ((_ e) (let () (define helper 10) (+ helper e)))))" => "#<void>",
+        "(let ((helper 1)) (with-helper helper))" => "11"
+    ];
+}
+
+#[test]
+fn prelude_bindings_do_not_capture() {
+    evals![
+        "(let ((var1 5)) (or #f var1))" => "5",
+        "(let ((atom-key 'b)) (case 'a ((a) atom-key) (else 'none)))" => "b",
+        "(let ((temp 'user)) (guard (e (#t temp)) (raise 'boom)))" => "user"
+    ];
+}
+
+#[test]
+fn free_identifiers_refer_to_definition_environment() {
+    evals![
+        "(define-syntax my-or
+           (syntax-rules ()
+             ((_) #f)
+             ((_ e) e)
+             ((_ e r ...) (let ((temp e)) (if temp temp (my-or r ...))))))" => "#<void>",
+        "(let ((temp list)) (my-or #f 2))" => "2",
+        "(define-syntax my-unless (syntax-rules () ((_ c e) (if (not c) e #f))))" => "#<void>",
+        "(let ((not (lambda (x) x))) (my-unless #f 'ran))" => "ran",
+        "(define-syntax my-list (syntax-rules () ((_ a ...) (list a ...))))" => "#<void>",
+        "(let ((list vector)) (my-list 1 2))" => "(1 2)",
+        "(define (f list) (my-list list 3))" => "#<void>",
+        "(f 'a)" => "(a 3)"
+    ];
+}
+
+#[test]
+fn introduced_literals_and_data() {
+    evals![
+        "(define-syntax my-if (syntax-rules () ((_ c a b) (cond (c a) (else b)))))" => "#<void>",
+        "(my-if #f 1 2)" => "2",
+        "(define-syntax quoted (syntax-rules () ((_) '(foo #(bar)))))" => "#<void>",
+        "(quoted)" => "(foo #(bar))",
+        "(define-syntax quasi (syntax-rules () ((_ x) `(foo ,x))))" => "#<void>",
+        "(quasi 1)" => "(foo 1)",
+        "(define-syntax define-zero (syntax-rules () ((_) (define zero 0))))" => "#<void>",
+        "(define-zero)" => "#<void>",
+        "zero" => "0"
+    ];
+}
+
+#[test]
+fn let_syntax() {
+    evals![
+        "(let-syntax ((double (syntax-rules () ((_ x) (* 2 x))))) (double 21))" => "42",
+        "(let-syntax ((foo (syntax-rules () ((_ x) (+ x 1))))
+                      (bar (syntax-rules () ((_ x) (* x 2)))))
+           (foo (bar 5)))" => "11",
+        "(let ((x 'outer))
+           (let-syntax ((m (syntax-rules () ((_) x))))
+             (let ((x 'inner))
+               (m))))" => "outer",
+        "(let ((x 'outer))
+           (let-syntax ((m (syntax-rules () ((_) (set! x 'assigned)))))
+             (let ((x 'inner))
+               (m)
+               (list x))))" => "(inner)",
+        "(define x 'global)" => "#<void>",
+        "(let-syntax ((m (syntax-rules () ((_) x))))
+           (let ((x 'inner))
+             (m)))" => "global",
+        "(let ((x 1))
+           (let-syntax ((m (syntax-rules () ((_ e) (let ((x 10)) (+ x e))))))
+             (m x)))" => "11",
+        "(let-syntax ((list (syntax-rules () ((_) 'macro))))
+           (let ((list (lambda () 'variable)))
+             (list)))" => "variable"
+    ];
+    evals![
+        "(define-syntax my-if (syntax-rules () ((_ c a b) (cond (c a) (else b)))))" => "#<void>",
+        "(let-syntax ((my-if (syntax-rules () ((_ c a b) (if c b a))))
+                      (choose (syntax-rules () ((_ c) (my-if c 'yes 'no)))))
+           (choose #t))" => "yes"
+    ];
+    fails![
+        "(begin (let-syntax ((double (syntax-rules () ((_ x) (* 2 x))))) (double 1)) (double 2))"
+            => VariableNotBound("double".into()),
+        "(let-syntax (double) 1)" => InvalidSyntax("expected syntax binding, but got double".into()),
+        "(let-syntax ((1 (syntax-rules () ((_) 1)))) 1)"
+            => InvalidSyntax("expected syntax binding, but got (1 (syntax-rules () ((_) 1)))".into())
+    ];
+}
+
+#[test]
+fn letrec_syntax() {
+    evals![
+        "(letrec-syntax ((my-or (syntax-rules ()
+                                  ((_) #f)
+                                  ((_ e) e)
+                                  ((_ e r ...) (let ((t e)) (if t t (my-or r ...)))))))
+           (let ((t 5))
+             (my-or #f t)))" => "5",
+        "(letrec-syntax ((my-and (syntax-rules ()
+                                   ((_) #t)
+                                   ((_ e) e)
+                                   ((_ e r ...) (if e (my-and r ...) #f)))))
+           (list (my-and 1 2 3) (my-and 1 #f 3)))" => "(3 #f)"
+    ];
+}
+
+#[test]
+fn internal_define_syntax() {
+    evals![
+        "(define (f x)
+           (define-syntax twice (syntax-rules () ((_ e) (begin e e))))
+           (define n 0)
+           (twice (set! n (+ n x)))
+           n)" => "#<void>",
+        "(f 5)" => "10",
+        "(define (g x) (define-syntax get-x (syntax-rules () ((_) x))) (get-x))" => "#<void>",
+        "(g 3)" => "3",
+        "(define (h x)
+           (define-syntax m (syntax-rules () ((_) x)))
+           (let ((x 'inner))
+             (list x (m))))" => "#<void>",
+        "(h 'outer)" => "(inner outer)",
+        "(define (k x)
+           (define-syntax m (syntax-rules () ((_) x)))
+           (let loop ((x 0))
+             (if (< x 2) (loop (+ x 1)) (list x (m)))))" => "#<void>",
+        "(k 'outer)" => "(2 outer)"
+    ];
+    evals![
+        "(let ()
+           (define-syntax inc! (syntax-rules () ((_ v) (set! v (+ v 1)))))
+           (define count 0)
+           (inc! count)
+           (inc! count)
+           count)" => "2",
+        "((lambda (x)
+            (define-syntax square (syntax-rules () ((_ e) (* e e))))
+            (square x)) 4)" => "16"
+    ];
+    evals![
+        "(define-syntax m (syntax-rules () ((_) 'global)))" => "#<void>",
+        "(let () (define-syntax m (syntax-rules () ((_) 'local))) (m))" => "local",
+        "(m)" => "global"
+    ];
+    fails![
+        "(begin (let () (define-syntax local (syntax-rules () ((_) 1))) (local)) (local))"
+            => VariableNotBound("local".into()),
+        "(let () (define x 1) x (define-syntax m (syntax-rules () ((_) 1))) (m))"
+            => InvalidSyntax("out of context define: (define-syntax m (syntax-rules () ((_) 1)))".into())
+    ];
+}
@@ -26,6 +26,40 @@ fn numerical_prefixes() {
     ]
 }
 
+#[test]
+fn numeric_literals() {
+    prints![
+        "+inf.0" => "+inf.0",
+        "-inf.0" => "-inf.0",
+        "+nan.0" => "+nan.0",
+        "-nan.0" => "+nan.0",
+        "'(+inf.0 -inf.0)" => "(+inf.0 -inf.0)",
+        "1+inf.0i" => "1.0+inf.0i",
+        "#e1.5e3" => "1500",
+        "#e1.2" => "6/5",
+        "#e-.5" => "-1/2",
+        "#i3/4" => "0.75",
+        "#x#e10" => "16",
+        "#e#x10" => "16",
+        "#X#I10" => "16.0",
+        "1.5E2" => "150.0",
+        "1." => "1.0",
+        "1e-8" => "1e-8",
+        "-1e11" => "-1e11",
+        "123.456" => "123.456",
+        "'-inf" => "-inf",
+        "'+inf.0x" => "+inf.0x"
+    ];
+    evals![
+        "(infinite? -inf.0)" => "#t",
+        "(nan? +nan.0)" => "#t",
+        "(integer? +inf.0)" => "#f",
+        "(rational? +inf.0)" => "#f",
+        "(real? +nan.0)" => "#t",
+        "(< -inf.0 0 +inf.0)" => "#t"
+    ];
+}
+
 #[test]
 fn number_string() {
     evals![
@@ -55,7 +89,14 @@ fn number_string() {
         "(number->string 42.42 2)" => "\"101010.0110101110000101000111101011100001010001111011\"",
         "(number->string -42.42 2)" => "\"-101010.0110101110000101000111101011100001010001111011\"",
         "(number->string 92233720368547758070000 2)" => "\"10011100001111111111111111111111111111111111111111111111111111101100011110000\"",
-        "(number->string 12/7 2)" => "\"1100/111\""
+        "(number->string 12/7 2)" => "\"1100/111\"",
+
+        "(number->string -42 16)" => "\"-2a\"",
+        "(number->string -12/7 16)" => "\"-c/7\"",
+        "(number->string 42.0 16)" => "\"2a.0\"",
+        "(number->string 1e20 16)" => "\"56bc75e2d63100000.0\"",
+        "(number->string +inf.0 16)" => "\"+inf.0\"",
+        "(number->string 1e-10)" => "\"1e-10\""
     ];
 
     evals![
@@ -63,7 +104,23 @@ fn number_string() {
         "(string->number \"42.42\")" => "42.42",
         "(string->number \"5/7\")" => "5/7",
         "(string->number \"ff\" 16)" => "255",
-        "(string->number \"-1101\" 2)" => "-13"
+        "(string->number \"-1101\" 2)" => "-13",
+        "(string->number \"#xff\")" => "255",
+        "(string->number \"#e1.25\")" => "5/4",
+        "(string->number \"#d10\" 16)" => "10",
+        "(string->number \"-inf.0\")" => "-inf.0",
+        "(string->number \"inf\")" => "#f",
+        "(string->number \"1/0\")" => "#f",
+        "(string->number \"#e#i1\")" => "#f",
+        "(string->number \"#e1e100000000\")" => "#f",
+        "(string->number \"#e1e-100000000\")" => "#f",
+        "(string->number \"#e1e20\")" => "100000000000000000000",
+        "(string->number (number->string 0.1))" => "0.1",
+        "(string->number (number->string -2/3 2) 2)" => "-2/3"
+    ];
+    fails![
+        "(number->string 42 3)" => InvalidSyntax("number->string: invalid radix 3".into()),
+        "(string->number \"42\" 37)" => InvalidSyntax("string->number: invalid radix 37".into())
     ];
 }
 
@@ -191,6 +248,138 @@ fn arithmetic() {
     ];
 }
 
+#[test]
+fn exact_rationals() {
+    prints![
+        "(/ 1 3000000000)" => "1/3000000000",
+        "(+ 1/3000000000 1/7000000000)" => "1/2100000000",
+        "(- 1/3000000000 1/3000000000)" => "0",
+        "(* 1/3000000000 3000000000)" => "1",
+        "(/ 1/3000000000 1/6000000000)" => "2",
+        "(numerator (/ 6 4000000000))" => "3",
+        "(denominator (/ 6 4000000000))" => "2000000000",
+        "(expt 5/7 30)" => "931322574615478515625/22539340290692258087863249",
+        "(expt 2/3 -40)" => "12157665459056928801/1099511627776",
+        "(floor 10000000001/3)" => "3333333333",
+        "(abs -1/3000000000)" => "1/3000000000",
+        "(+ 0.5 1/3000000000)" => "0.5000000003333334",
+        "(exact->inexact 1/3000000000)" => "3.333333333333333e-10"
+    ];
+
+    evals![
+        "(< 1/3000000000 1/2999999999)" => "#t",
+        "(= 1/3000000000 2/6000000000)" => "#t",
+        "(rational? 1/3000000000)" => "#t",
+        "(integer? 9000000000/3)" => "#t"
+    ];
+
+    prints![
+        "(define (sum-reciprocals n)
+           (let loop ((i 1) (sum 0))
+             (if (> i n)
+                 sum
+                 (loop (+ i 1) (+ sum (/ 1 i))))))" => "#<void>",
+        "(sum-reciprocals 30)" => "9304682830147/2329089562800"
+    ];
+}
+
+#[test]
+fn complex_numbers() {
+    prints![
+        "1+2i" => "1+2i",
+        "-2.5-i" => "-2.5-1.0i",
+        "+i" => "+i",
+        "-i" => "-i",
+        "+2/3i" => "+2/3i",
+        "1+0i" => "1",
+        "1.0+0.0i" => "1.0+0.0i",
+        "'(1+2i . -i)" => "(1+2i . -i)",
+        "(+ 1+2i 3-2i)" => "4",
+        "(- 1+2i 3+4i)" => "-2-2i",
+        "(* 1+2i 3+4i)" => "-5+10i",
+        "(* +i +i)" => "-1",
+        "(/ 1+2i 3+4i)" => "11/25+2/25i",
+        "(/ 1+2i 2)" => "1/2+i",
+        "(+ 0.5 1+2i)" => "1.5+2.0i",
+        "(- 1+i)" => "-1-i",
+        "(expt 1+i 2)" => "+2i",
+        "(exact->inexact 1/2+i)" => "0.5+1.0i",
+        "(inexact->exact 0.5+2.0i)" => "1/2+2i",
+        "(number->string 10+11i 16)" => "a+bi",
+        "(string->number \"1-i\")" => "1-i"
+    ];
+
+    evals![
+        "(= 1+2i 1+2i)" => "#t",
+        "(= 1+2i 1.0+2.0i)" => "#t",
+        "(= 1+2i 1+3i)" => "#f",
+        "(= 1.0+0.0i 1)" => "#t",
+        "(zero? +i)" => "#f",
+        "(< 1+i 2)" => "#f",
+        "(eqv? 1+2i 1+2i)" => "#t",
+        "(equal? '(1+2i) '(1+2i))" => "#t"
+    ];
+
+    fails!["(round 1+i)" => InvalidSyntax("1+i is not a valid real number".into())];
+    fails!["(max 1 +i)" => InvalidSyntax("+i is not a valid real number".into())];
+}
+
+#[test]
+fn complex_constructors_and_accessors() {
+    prints![
+        "(make-rectangular 3 4)" => "3+4i",
+        "(make-rectangular 3 0)" => "3",
+        "(make-rectangular 1.5 -2)" => "1.5-2.0i",
+        "(make-polar 2 0)" => "2",
+        "(real-part 3+4i)" => "3",
+        "(imag-part 3+4i)" => "4",
+        "(real-part 3)" => "3",
+        "(imag-part 3)" => "0",
+        "(magnitude 3+4i)" => "5",
+        "(magnitude -5)" => "5",
+        "(magnitude 1+i)" => "1.4142135623730951",
+        "(angle +i)" => "1.5707963267948966",
+        "(angle -1)" => "3.141592653589793",
+        "(angle 1)" => "0"
+    ];
+
+    evals![
+        "(real-part (make-polar 2 3.141592653589793))" => "-2.0",
+        "(magnitude (make-polar 2 1))" => "2.0",
+        "(angle (make-polar 2 1))" => "1.0"
+    ];
+
+    fails!["(make-rectangular +i 1)" => InvalidSyntax("+i is not a valid real number".into())];
+}
+
+#[test]
+fn complex_transcendental() {
+    prints![
+        "(sqrt -4)" => "+2i",
+        "(sqrt -4.0)" => "+2.0i",
+        "(sqrt -2)" => "+1.4142135623730951i",
+        "(sqrt -1/4)" => "+1/2i",
+        "(sqrt 1/4)" => "1/2",
+        "(sqrt +2i)" => "1.0+1.0i",
+        "(log -1)" => "+3.141592653589793i",
+        "(expt 2 -2)" => "1/4",
+        "(expt 2 2.0)" => "4.0",
+        "(expt 4 0.5)" => "2.0"
+    ];
+
+    evals![
+        "(real-part (exp +3.141592653589793i))" => "-1.0",
+        "(imag-part (expt -1 1/2))" => "1.0",
+        "(imag-part (asin 2))" => "-1.3169578969248166",
+        "(imag-part (acos 2))" => "1.3169578969248164",
+        "(real-part (sin +i))" => "0.0",
+        "(imag-part (sin +i))" => "1.1752011936438014",
+        "(real-part (cos +i))" => "1.5430806348152437"
+    ];
+
+    fails!["(expt 0 -1)" => InvalidSyntax("expt is undefined for 0 -1".into())];
+}
+
 #[test]
 fn num_compare() {
     evals![
@@ -241,3 +430,81 @@ fn num_unary() {
         "(even? 3)" => "#f"
     ];
 }
+
+#[test]
+fn integer_division() {
+    evals![
+        "(call-with-values (lambda () (floor/ 5 2)) list)" => "(2 1)",
+        "(call-with-values (lambda () (floor/ -5 2)) list)" => "(-3 1)",
+        "(call-with-values (lambda () (floor/ 5 -2)) list)" => "(-3 -1)",
+        "(call-with-values (lambda () (floor/ -5 -2)) list)" => "(2 -1)",
+        "(call-with-values (lambda () (truncate/ 5 2)) list)" => "(2 1)",
+        "(call-with-values (lambda () (truncate/ -5 2)) list)" => "(-2 -1)",
+        "(call-with-values (lambda () (truncate/ 5 -2)) list)" => "(-2 1)",
+        "(call-with-values (lambda () (truncate/ -5 -2)) list)" => "(2 -1)",
+        "(floor-quotient -7 2)" => "-4",
+        "(floor-remainder -7 2)" => "1",
+        "(truncate-quotient -7 2)" => "-3",
+        "(truncate-remainder -7 2)" => "-1",
+        "(floor-quotient -100000000000000000000 3)" => "-33333333333333333334",
+        "(floor-remainder -100000000000000000000 3)" => "2",
+        "(floor-quotient 7.0 2)" => "3.0",
+        "(floor-quotient -9223372036854775808 -1)" => "9223372036854775808",
+        "(floor-remainder -9223372036854775808 -1)" => "0",
+        "(truncate-quotient -9223372036854775808 -1)" => "9223372036854775808",
+        "(truncate-remainder -9223372036854775808 -1)" => "0",
+        "(call-with-values (lambda () (floor/ -9223372036854775808 -1)) list)" => "(9223372036854775808 0)",
+        "(call-with-values (lambda () (truncate/ -9223372036854775808 -1)) list)" => "(9223372036854775808 0)"
+    ];
+    fails![
+        "(floor/ 1 0)" => InvalidSyntax("floor/ is undefined for 0".into()),
+        "(truncate-quotient 1 0)" => InvalidSyntax("quotient is undefined for 0".into()),
+        "(floor-quotient 1/2 2)" => InvalidSyntax("1/2 is not a valid integer".into())
+    ];
+}
+
+#[test]
+fn numeric_library() {
+    evals![
+        "(call-with-values (lambda () (exact-integer-sqrt 4)) list)" => "(2 0)",
+        "(call-with-values (lambda () (exact-integer-sqrt 5)) list)" => "(2 1)",
+        "(call-with-values (lambda () (exact-integer-sqrt 100000000000000000000)) list)" => "(10000000000 0)",
+        "(gcd 32 -36)" => "4",
+        "(gcd)" => "0",
+        "(gcd 0 5)" => "5",
+        "(gcd 100000000000000000000 30)" => "10",
+        "(lcm 32 -36)" => "288",
+        "(lcm 32.0 -36)" => "288.0",
+        "(lcm)" => "1",
+        "(square 42)" => "1764",
+        "(square 2.0)" => "4.0",
+        "(square 1/3)" => "1/9",
+        "(exact-integer? 32)" => "#t",
+        "(exact-integer? 32.0)" => "#f",
+        "(exact-integer? 32/5)" => "#f",
+        "(exact-rational? 32/5)" => "#t",
+        "(exact-rational? 32.5)" => "#f",
+        "(exact-rational? 'a)" => "#f",
+        "(nan? 1)" => "#f",
+        "(infinite? 1.0)" => "#f",
+        "(finite? 1/3)" => "#t",
+        "(finite? 1+2i)" => "#t",
+        "(exact? 1/2)" => "#t",
+        "(inexact? 0.5)" => "#t"
+    ];
+    prints![
+        "(exact 2.5)" => "5/2",
+        "(inexact 1/4)" => "0.25",
+        "(rationalize 1/3 1/100)" => "1/3",
+        "(rationalize 3/10 1/10)" => "1/3",
+        "(rationalize .3 1/10)" => "0.3333333333333333",
+        "(lcm 32.0 -36)" => "288.0"
+    ];
+    fails![
+        "(exact-integer-sqrt -1)" => InvalidSyntax("exact-integer-sqrt is undefined for -1".into()),
+        "(gcd 1/2 3)" => InvalidSyntax("1/2 is not a valid integer".into()),
+        "(exact +inf.0)" => InvalidSyntax("exact is undefined for +inf.0".into()),
+        "(exact +nan.0)" => InvalidSyntax("exact is undefined for +nan.0".into()),
+        "(inexact->exact +inf.0)" => InvalidSyntax("exact is undefined for +inf.0".into())
+    ];
+}
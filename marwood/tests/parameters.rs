@@ -0,0 +1,68 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::Vm;
+
+#[test]
+fn make_parameter() {
+    evals![
+        "(define p (make-parameter 10))" => "#<void>",
+        "(p)" => "10",
+        "(procedure? p)" => "#t",
+        "(define q (make-parameter 10 (lambda (x) (* x 2))))" => "#<void>",
+        "(q)" => "20"
+    ];
+}
+
+#[test]
+fn parameterize() {
+    evals![
+        "(define p (make-parameter 1))" => "#<void>",
+        "(define q (make-parameter 2 (lambda (x) (* x 10))))" => "#<void>",
+        "(define (get) (list (p) (q)))" => "#<void>",
+        "(parameterize ((p 3)) (get))" => "(3 20)",
+        "(parameterize ((p 3) (q 4)) (get))" => "(3 40)",
+        "(parameterize ((p 3)) (parameterize ((p 5)) (p)))" => "5",
+        "(parameterize ((p 3) (q (p))) (get))" => "(3 10)",
+        "(parameterize () (get))" => "(1 20)",
+        "(get)" => "(1 20)"
+    ];
+}
+
+#[test]
+fn parameterize_restores_on_escape() {
+    evals![
+        "(define p (make-parameter 'outer))" => "#<void>",
+        "(call/cc (lambda (k) (parameterize ((p 'inner)) (k (p)))))" => "inner",
+        "(p)" => "outer",
+        "(guard (e (#t (p))) (parameterize ((p 'inner)) (raise 'oops)))" => "outer",
+        "(p)" => "outer"
+    ];
+    evals![
+        "(define p (make-parameter 'outer))" => "#<void>",
+        "(define k #f)" => "#<void>",
+        "(define seen '())" => "#<void>",
+        "(parameterize ((p 'inner))
+           (call/cc (lambda (c) (set! k c)))
+           (set! seen (cons (p) seen)))" => "#<void>",
+        "(p)" => "outer",
+        "(if (< (length seen) 2) (k #f))" => "#<void>",
+        "seen" => "(inner inner)",
+        "(p)" => "outer"
+    ];
+}
+
+#[test]
+fn current_port_parameters() {
+    evals![
+        "(define port (open-output-string))" => "#<void>",
+        "(parameterize ((current-output-port port)) (display \"hello\") (write 'world))" => "#<void>",
+        "(get-output-string port)" => "\"helloworld\"",
+        "(eq? port (current-output-port))" => "#f",
+        "(parameterize ((current-input-port (open-input-string \"abc\"))) (read-line))" => "\"abc\"",
+        "(guard (e ((error-object? e) (error-object-message e)))
+           (parameterize ((current-output-port 'foo)) 1))" => "\"not an output port\""
+    ];
+}
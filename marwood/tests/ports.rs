@@ -0,0 +1,236 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::error::Error::{FileError, InvalidSyntax};
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::{SystemInterface, Vm};
+
+#[test]
+fn output_string_ports() {
+    evals![
+        "(define port (open-output-string))" => "#<void>",
+        "(get-output-string port)" => "\"\"",
+        "(display \"hello\" port)" => "#<void>",
+        "(write-char #\\space port)" => "#<void>",
+        "(write \"world\" port)" => "#<void>",
+        "(newline port)" => "#<void>",
+        "(write-string \"!\" port)" => "#<void>",
+        "(get-output-string port)" => "\"hello \\\"world\\\"\\n!\"",
+        "(write #\\a port)" => "#<void>",
+        "(display '(1 \"two\" #\\3) port)" => "#<void>",
+        "(get-output-string port)" => "\"hello \\\"world\\\"\\n!#\\\\a(1 two 3)\""
+    ];
+    evals![
+        "(call-with-output-string (lambda (port) (display 42 port) (write 'x port)))" => "\"42x\"",
+        "(call-with-output-string (lambda (port) #t))" => "\"\""
+    ];
+}
+
+#[test]
+fn input_string_ports() {
+    evals![
+        "(define port (open-input-string \"ab\\nλd\"))" => "#<void>",
+        "(char-ready? port)" => "#t",
+        "(peek-char port)" => "#\\a",
+        "(read-char port)" => "#\\a",
+        "(read-line port)" => "\"b\"",
+        "(read-char port)" => "#\\λ",
+        "(read-string 5 port)" => "\"d\"",
+        "(eof-object? (read-char port))" => "#t",
+        "(eof-object? (peek-char port))" => "#t",
+        "(eof-object? (read-line port))" => "#t",
+        "(eof-object? (read-string 1 port))" => "#t",
+        "(eof-object? #\\a)" => "#f"
+    ];
+    evals![
+        "(define port (open-input-string \"one\\r\\ntwo\\n\\nthree\"))" => "#<void>",
+        "(read-line port)" => "\"one\"",
+        "(read-line port)" => "\"two\"",
+        "(read-line port)" => "\"\"",
+        "(read-string 2 port)" => "\"th\"",
+        "(read-string 0 port)" => "\"\"",
+        "(read-line port)" => "\"ree\""
+    ];
+    prints![
+        "(read-char (open-input-string \"\"))" => "#<eof>",
+        "(open-input-string \"\")" => "#<port>"
+    ];
+}
+
+#[test]
+fn read() {
+    evals![
+        "(define port (open-input-string \"(1 \\\"two\\\" #\\\\3) foo ; comment\\n #(1 2) '(a . b) 4.5\"))" => "#<void>",
+        "(read port)" => "(1 \"two\" #\\3)",
+        "(read port)" => "foo",
+        "(read port)" => "#(1 2)",
+        "(read port)" => "(quote (a . b))",
+        "(read-char port)" => "#\\space",
+        "(read port)" => "4.5",
+        "(eof-object? (read port))" => "#t",
+        "(eof-object? (read port))" => "#t"
+    ];
+    evals![
+        "(define port (open-input-string \"  ; only a comment\"))" => "#<void>",
+        "(eof-object? (read port))" => "#t",
+        "(eof-object? (eof-object))" => "#t",
+        "(eq? (eof-object) (read (open-input-string \"\")))" => "#t",
+        "(symbol? (read (open-input-string \"abc\")))" => "#t",
+        "(with-output-to-file \"data.scm\" (lambda () (write '(1 (2 \"3\")))))" => "#<void>",
+        "(call-with-input-file \"data.scm\" read)" => "(1 (2 \"3\"))"
+    ];
+    evals![
+        "(define port (open-input-string \"(1 2\"))" => "#<void>",
+        "(guard (e ((read-error? e) 'read-error)) (read port))" => "read-error",
+        "(eof-object? (read port))" => "#t",
+        "(guard (e ((read-error? e) 'read-error)) (read (open-input-string \")\")))" => "read-error",
+        "(guard (e ((read-error? e) 'read-error)) (read (open-input-string \"#\\\\bogus\")))" => "read-error",
+        "(guard (e ((read-error? e) 'read-error)) (read (open-input-string \"#e1e100000000\")))" => "read-error"
+    ];
+    evals![
+        "(read (open-input-string \"1 #z\"))" => "1",
+        "(define port (open-input-string \"(a b) \\\"unterminated\"))" => "#<void>",
+        "(read port)" => "(a b)",
+        "(guard (e ((read-error? e) 'read-error)) (read port))" => "read-error",
+        "(eof-object? (read port))" => "#t",
+        "(define port (open-input-string \"'x #z\"))" => "#<void>",
+        "(read port)" => "(quote x)",
+        "(guard (e ((read-error? e) 'read-error)) (read port))" => "read-error"
+    ];
+}
+
+#[test]
+fn port_predicates() {
+    evals![
+        "(port? (open-input-string \"\"))" => "#t",
+        "(port? (open-output-string))" => "#t",
+        "(port? \"\")" => "#f",
+        "(input-port? (open-input-string \"\"))" => "#t",
+        "(input-port? (open-output-string))" => "#f",
+        "(output-port? (open-output-string))" => "#t",
+        "(output-port? (open-input-string \"\"))" => "#f",
+        "(output-port? 'port)" => "#f",
+        "(textual-port? (open-output-string))" => "#t",
+        "(output-port? (current-output-port))" => "#t",
+        "(input-port? (current-input-port))" => "#t",
+        "(eq? (current-output-port) (current-output-port))" => "#t"
+    ];
+}
+
+#[test]
+fn port_errors() {
+    fails![
+        "(display 1 'port)" => InvalidSyntax("bad argument to display: port is not a port".into()),
+        "(display 1 (open-input-string \"\"))"
+            => InvalidSyntax("bad argument to display: port is not an output port".into()),
+        "(read-char (open-output-string))"
+            => InvalidSyntax("bad argument to read-char: port is not an input port".into()),
+        "(get-output-string (open-input-string \"\"))"
+            => InvalidSyntax("bad argument to get-output-string: port is not a string output port".into()),
+        "(open-input-string 'foo)"
+            => InvalidSyntax("bad argument to open-input-string: foo is not a string".into())
+    ];
+}
+
+#[test]
+fn file_ports() {
+    evals![
+        "(file-exists? \"foo.txt\")" => "#f",
+        "(define port (open-output-file \"foo.txt\"))" => "#<void>",
+        "(file-exists? \"foo.txt\")" => "#t",
+        "(output-port? port)" => "#t",
+        "(write \"hello\" port)" => "#<void>",
+        "(newline port)" => "#<void>",
+        "(display 'world port)" => "#<void>",
+        "(define port (open-input-file \"foo.txt\"))" => "#<void>",
+        "(input-port? port)" => "#t",
+        "(read-line port)" => "\"\\\"hello\\\"\"",
+        "(read-line port)" => "\"world\"",
+        "(eof-object? (read-char port))" => "#t",
+        "(delete-file \"foo.txt\")" => "#<void>",
+        "(file-exists? \"foo.txt\")" => "#f"
+    ];
+    evals![
+        "(call-with-output-file \"foo.txt\" (lambda (port) (display \"abc\" port)))" => "#<void>",
+        "(call-with-input-file \"foo.txt\" read-line)" => "\"abc\"",
+        "(with-output-to-file \"foo.txt\" (lambda () (display 1) (write-char #\\x) 'done))" => "done",
+        "(with-input-from-file \"foo.txt\" read-line)" => "\"1x\"",
+        "(output-port? (current-output-port))" => "#t",
+        "(eq? (current-output-port) (call-with-output-file \"foo.txt\" (lambda (port) (current-output-port))))" => "#t"
+    ];
+    evals![
+        "(define port (open-output-file \"foo.txt\"))" => "#<void>",
+        "(display \"abc\" port)" => "#<void>",
+        "(close-output-port port)" => "#<void>",
+        "(close-port port)" => "#<void>",
+        "(define in (open-input-file \"foo.txt\"))" => "#<void>",
+        "(read-char in)" => "#\\a",
+        "(close-input-port in)" => "#<void>",
+        "(eof-object? (read-char in))" => "#t",
+        "(call-with-values
+           (lambda () (call-with-output-file \"foo.txt\" (lambda (port) (write 'xyz port) (values 1 2))))
+           list)" => "(1 2)",
+        "(call-with-port (open-input-file \"foo.txt\") (lambda (port) (set! in port) (read port)))" => "xyz",
+        "(eof-object? (peek-char in))" => "#t"
+    ];
+    fails![
+        "(let ((port (open-output-file \"foo.txt\"))) (close-port port) (display 1 port))"
+            => InvalidSyntax("port is closed".into()),
+        "(let ((out #f))
+           (call-with-output-file \"foo.txt\" (lambda (port) (set! out port)))
+           (display 1 out))"
+            => InvalidSyntax("port is closed".into()),
+        "(close-input-port (open-output-string))"
+            => InvalidSyntax("bad argument to close-input-port: port is not an input port".into()),
+        "(close-output-port (open-input-string \"\"))"
+            => InvalidSyntax("bad argument to close-output-port: port is not an output port".into())
+    ];
+    evals![
+        "(define out (current-output-port))" => "#<void>",
+        "(guard (e (#t #f)) (with-output-to-file \"foo.txt\" (lambda () (raise 'oops))))" => "#f",
+        "(eq? out (current-output-port))" => "#t"
+    ];
+}
+
+#[test]
+fn file_errors() {
+    evals![
+        "(guard (e ((file-error? e) (list (error-object-message e) (error-object-irritants e))))
+           (open-input-file \"missing.txt\"))" => "(\"file not found\" (\"missing.txt\"))",
+        "(guard (e ((file-error? e) 'caught)) (delete-file \"missing.txt\"))" => "caught"
+    ];
+    fails![
+        "(open-input-file \"missing.txt\")" => FileError("missing.txt".into(), "file not found".into()),
+        "(open-input-file 'foo)" => InvalidSyntax("bad argument to open-input-file: foo is not a string".into())
+    ];
+}
+
+/// An interface that leaves the filesystem to the SystemInterface default,
+/// which denies all access
+#[derive(Debug)]
+struct DeniedInterface {}
+
+impl SystemInterface for DeniedInterface {
+    fn display(&self, _: &Cell) {}
+    fn write(&self, _: &Cell) {}
+    fn terminal_dimensions(&self) -> (usize, usize) {
+        (0, 0)
+    }
+    fn time_utc(&self) -> u64 {
+        0
+    }
+}
+
+#[test]
+fn denied_filesystem() {
+    let mut vm = Vm::new();
+    vm.set_system_interface(Box::new(DeniedInterface {}));
+    let denied = Err(FileError("foo.txt".into(), "file access denied".into()));
+    assert_eq!(vm.eval(&parse!("(open-output-file \"foo.txt\")")), denied);
+    assert_eq!(vm.eval(&parse!("(open-input-file \"foo.txt\")")), denied);
+    assert_eq!(
+        vm.eval(&parse!("(file-exists? \"foo.txt\")")),
+        Ok(Cell::Bool(false))
+    );
+}
@@ -81,16 +81,23 @@ fn unary_predicate() {
         "(real? 10/5)" => "#t",
         "(real? 10/3)" => "#t",
         "(real? 10.3)" => "#t",
+        "(real? 1+2i)" => "#f",
+        "(real? 1+0i)" => "#t",
+        "(real? 1.0+0.0i)" => "#f",
 
         "(complex? 10)" => "#t",
         "(complex? 10/5)" => "#t",
         "(complex? 10/3)" => "#t",
         "(complex? 10.3)" => "#t",
+        "(complex? 1+2i)" => "#t",
+        "(complex? +i)" => "#t",
 
         "(rational? 10)" => "#t",
         "(rational? 10/5)" => "#t",
         "(rational? 10/3)" => "#t",
-        "(rational? 10.3)" => "#f"
+        "(rational? 10.3)" => "#f",
+        "(rational? 1/2+i)" => "#f",
+        "(integer? 1+i)" => "#f"
     ];
 
     evals![
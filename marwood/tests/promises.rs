@@ -0,0 +1,98 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::error::Error::InvalidSyntax;
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::Vm;
+
+#[test]
+fn delay_and_force() {
+    evals![
+        "(define count 0)" => "#<void>",
+        "(define p (delay (begin (set! count (+ count 1)) (* 6 7))))" => "#<void>",
+        "count" => "0",
+        "(force p)" => "42",
+        "(force p)" => "42",
+        "count" => "1",
+        "(force (delay (+ 1 2)))" => "3",
+        "(let ((p (delay (+ 1 2)))) (list (force p) (force p)))" => "(3 3)",
+        "(promise? (force (delay (delay 1))))" => "#t",
+        "(force 7)" => "7"
+    ];
+    prints!["(delay 1)" => "#<promise>"];
+}
+
+#[test]
+fn make_promise() {
+    evals![
+        "(force (make-promise 5))" => "5",
+        "(promise? (make-promise 5))" => "#t",
+        "(define p (delay 1))" => "#<void>",
+        "(eq? p (make-promise p))" => "#t"
+    ];
+}
+
+#[test]
+fn promise_predicate() {
+    evals![
+        "(promise? (delay 1))" => "#t",
+        "(promise? (delay-force (delay 1)))" => "#t",
+        "(promise? '((#t . 1)))" => "#f",
+        "(promise? (lambda () 1))" => "#f",
+        "(promise? 1)" => "#f"
+    ];
+    evals!["(force '((#t . 1)))" => "((#t . 1))"];
+    fails!["(eval '(promise-value '((#t . 1))) (environment '(marwood internal)))"
+        => InvalidSyntax("bad argument to promise-value: ((#t . 1)) is not a promise".into())];
+}
+
+#[test]
+fn reentrant_force() {
+    evals![
+        "(define x 5)" => "#<void>",
+        "(define count 0)" => "#<void>",
+        "(define p
+           (delay (begin (set! count (+ count 1))
+                         (if (> count x)
+                             count
+                             (force p)))))" => "#<void>",
+        "(force p)" => "6",
+        "(begin (set! x 10) (force p))" => "6"
+    ];
+}
+
+#[test]
+fn streams() {
+    evals![
+        "(define integers
+           (letrec ((next (lambda (n) (delay (cons n (next (+ n 1)))))))
+             (next 0)))" => "#<void>",
+        "(define (head stream) (car (force stream)))" => "#<void>",
+        "(define (tail stream) (cdr (force stream)))" => "#<void>",
+        "(head (tail (tail integers)))" => "2",
+        "(define (stream-filter p? s)
+           (delay-force
+             (if (null? (force s))
+                 (delay '())
+                 (let ((h (car (force s)))
+                       (t (cdr (force s))))
+                   (if (p? h)
+                       (delay (cons h (stream-filter p? t)))
+                       (stream-filter p? t))))))" => "#<void>",
+        "(head (tail (tail (stream-filter odd? integers))))" => "5",
+        "(head (stream-filter (lambda (n) (= n 5000)) integers))" => "5000"
+    ];
+}
+
+#[test]
+fn delay_force_runs_in_constant_space() {
+    evals![
+        "(define (loop n)
+           (delay-force
+             (if (= n 0)
+                 (delay 'done)
+                 (loop (- n 1)))))" => "#<void>",
+        "(force (loop 100000))" => "done"
+    ];
+}
@@ -0,0 +1,106 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::error::Error::{InvalidArgs, InvalidNumArgs, InvalidSyntax, InvalidUsePrimitive};
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::Vm;
+
+#[test]
+fn define_record_type() {
+    evals![
+        "(define-record-type <point> (make-point x y) point? (x point-x set-point-x!) (y point-y))" => "#<void>",
+        "(define p (make-point 1 2))" => "#<void>",
+        "(point? p)" => "#t",
+        "(point? 10)" => "#f",
+        "(point? (vector 1 2))" => "#f",
+        "(list (point-x p) (point-y p))" => "(1 2)",
+        "(set-point-x! p 10)" => "#<void>",
+        "(point-x p)" => "10"
+    ];
+    prints![
+        "(let () (define-record-type <point> (make-point x y) point? (x point-x) (y point-y)) (make-point 1 2))" => "#<record point>",
+        "(let () (define-record-type <point> (make-point x y) point? (x point-x) (y point-y)) <point>)" => "#<record-type point>"
+    ];
+    fails![
+        "(let () (define-record-type point (make-point x) point? (x point-x)) (point-x 10))"
+            => InvalidArgs("point-x".into(), "point".into(), "10".into()),
+        "(let () (define-record-type point (make-point x) point? (x point-x set-point-x!)) (set-point-x! '(1 2) 3))"
+            => InvalidArgs("set-point-x!".into(), "point".into(), "(1 2)".into()),
+        "(let () (define-record-type point (make-point x y) point? (x point-x) (y point-y)) (make-point 1))"
+            => InvalidNumArgs("(λ (x y))".into())
+    ];
+}
+
+#[test]
+fn record_constructor_fields() {
+    evals![
+        "(define-record-type node (make-node value) node? (value node-value) (next node-next set-node-next!))" => "#<void>",
+        "(define n (make-node 1))" => "#<void>",
+        "(node-next n)" => "#f",
+        "(set-node-next! n (make-node 2))" => "#<void>",
+        "(node-value (node-next n))" => "2",
+        "(define-record-type pare (kons y x) pare? (x kar) (y kdr))" => "#<void>",
+        "(list (kar (kons 1 2)) (kdr (kons 1 2)))" => "(2 1)"
+    ];
+}
+
+#[test]
+fn record_types_are_distinct() {
+    evals![
+        "(define-record-type a (make-a x) a? (x a-x))" => "#<void>",
+        "(define-record-type b (make-b x) b? (x b-x))" => "#<void>",
+        "(a? (make-b 1))" => "#f",
+        "(b? (make-b 1))" => "#t"
+    ];
+    fails![
+        "(let ()
+           (define-record-type a (make-a x) a? (x a-x))
+           (define-record-type b (make-b x) b? (x b-x))
+           (a-x (make-b 1)))" => InvalidArgs("a-x".into(), "a".into(), "#<record b>".into())
+    ];
+}
+
+#[test]
+fn record_equality() {
+    evals![
+        "(define-record-type point (make-point x y) point? (x point-x) (y point-y))" => "#<void>",
+        "(define-record-type other (make-other x y) other? (x other-x) (y other-y))" => "#<void>",
+        "(define p (make-point 1 '(2)))" => "#<void>",
+        "(eq? p p)" => "#t",
+        "(eqv? p p)" => "#t",
+        "(eqv? p (make-point 1 '(2)))" => "#f",
+        "(equal? p (make-point 1 '(2)))" => "#t",
+        "(equal? p (make-point 1 '(3)))" => "#f",
+        "(equal? p (make-other 1 '(2)))" => "#f",
+        "(equal? (list p) (list (make-point 1 '(2))))" => "#t"
+    ];
+}
+
+#[test]
+fn internal_define_record_type() {
+    evals![
+        "(define (make-counter)
+           (define-record-type counter (new-counter n) counter? (n counter-n set-counter-n!))
+           (define c (new-counter 0))
+           (lambda ()
+             (set-counter-n! c (+ (counter-n c) 1))
+             (counter-n c)))" => "#<void>",
+        "(define counter (make-counter))" => "#<void>",
+        "(counter)" => "1",
+        "(counter)" => "2"
+    ];
+}
+
+#[test]
+fn define_record_type_syntax() {
+    fails![
+        "(define-record-type point (make-point x))" => InvalidNumArgs("define-record-type".into()),
+        "(define-record-type 1 (make-point x) point? (x point-x))" => InvalidSyntax("expected variable, but got 1".into()),
+        "(define-record-type point make-point point? (x point-x))" => InvalidSyntax("expected constructor, but got make-point".into()),
+        "(define-record-type point (make-point x) point? x)" => InvalidSyntax("expected field spec, but got x".into()),
+        "(define-record-type point (make-point z) point? (x point-x))" => InvalidSyntax("z is not a field of record type point".into()),
+        "(define-record-type point (make-point x) if (x point-x))" => InvalidSyntax("expected variable, but got if".into()),
+        "(define define-record-type 10)" => InvalidUsePrimitive("define-record-type".into())
+    ];
+}
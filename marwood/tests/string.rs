@@ -5,7 +5,7 @@ use marwood::lex;
 use marwood::parse;
 use marwood::vm::Vm;
 
-use marwood::error::Error::{InvalidStringIndex, InvalidSyntax};
+use marwood::error::Error::{InvalidStringIndex, InvalidSyntax, InvalidVectorIndex};
 
 #[test]
 fn eval_string_char_literals() {
@@ -236,4 +236,45 @@ fn symbol_procedures() {
 #[test]
 fn vector_conversion() {
     evals!["(vector->string (string->vector \"foo\"))" => "\"foo\""];
+    evals!["(string->vector \"abcd\" 1)" => "#(#\\b #\\c #\\d)",
+           "(string->vector \"abcd\" 1 3)" => "#(#\\b #\\c)",
+           "(vector->string #(#\\a #\\b #\\c) 1)" => "\"bc\"",
+           "(vector->string #(#\\a #\\b #\\c) 0 2)" => "\"ab\""
+    ];
+    fails!["(string->vector \"abc\" 4)" => InvalidStringIndex(4, 3),
+           "(vector->string #(#\\a) 0 2)" => InvalidVectorIndex(2, 1)
+    ];
+}
+
+#[test]
+fn string_copy_mut() {
+    evals!["(define s (make-string 5 #\\-))" => "#<void>",
+           "(string-copy! s 1 \"abcd\" 1 3)" => "#<void>",
+           "s" => "\"-bc--\"",
+           "(string-copy! s 3 \"o🐶\")" => "#<void>",
+           "s" => "\"-bco🐶\""
+    ];
+    evals!["(define s (string-copy \"abcdef\"))" => "#<void>",
+           "(string-copy! s 2 s 0 3)" => "#<void>",
+           "s" => "\"ababcf\""
+    ];
+    fails!["(string-copy! (make-string 2) 0 \"abc\")" =>
+            InvalidSyntax("string-copy!: to string is too small".into())];
+}
+
+#[test]
+fn string_map() {
+    evals!["(string-map char-upcase \"abc\")" => "\"ABC\"",
+           "(string-map (lambda (a b) (if (char<? a b) a b)) \"adc\" \"bbbz\")" => "\"abb\"",
+           "(string-map char-upcase \"\")" => "\"\""
+    ];
+    evals!["(define n 0)" => "#<void>",
+           "(string-for-each (lambda (c) (set! n (+ n 1))) \"o🐶o\")" => "#<void>",
+           "n" => "3"
+    ];
+    fails!["(string-map (lambda (c) 1) \"ab\")" =>
+            InvalidSyntax("string-map expected char but found 1".into()),
+           "(string-for-each \"ab\" \"ab\")" =>
+            InvalidSyntax("bad argument to string-for-each: \"ab\" is not a procedure".into())
+    ];
 }
@@ -0,0 +1,148 @@
+#[macro_use]
+mod common;
+use marwood::cell::Cell;
+use marwood::error::Error::{InvalidNumArgs, InvalidSyntax};
+use marwood::lex;
+use marwood::parse;
+use marwood::vm::Vm;
+
+#[test]
+fn values() {
+    evals![
+        "(values 1)" => "1",
+        "(+ (values 1) 2)" => "3",
+        "(call-with-values (lambda () (values)) list)" => "()",
+        "(call-with-values (lambda () (values 1 2 3)) list)" => "(1 2 3)",
+        "(call-with-values (lambda () 10) list)" => "(10)",
+        "(call-with-values (lambda () (values 4 5)) (lambda (a b) b))" => "5",
+        "(call-with-values * -)" => "-1"
+    ];
+    prints![
+        "(values 1 2 3)" => "1 2 3",
+        "(values 1 \"two\" '(3))" => "1 two (3)",
+        "(values)" => ""
+    ];
+}
+
+#[test]
+fn values_cross_calls() {
+    evals![
+        "(define (div-mod n d) (values (quotient n d) (remainder n d)))" => "#<void>",
+        "(call-with-values (lambda () (div-mod 17 5)) list)" => "(3 2)",
+        "(define (tail-div-mod n d) (div-mod n d))" => "#<void>",
+        "(call-with-values (lambda () (tail-div-mod 17 5)) cons)" => "(3 . 2)",
+        "(define (nested-div-mod n d) (let ((result (div-mod n d))) result))" => "#<void>",
+        "(call-with-values (lambda () (nested-div-mod 9 2)) list)" => "(4 1)",
+        "(define (consumer a b) (* a b))" => "#<void>",
+        "(define (product) (call-with-values (lambda () (values 6 7)) consumer))" => "#<void>",
+        "(+ 1 (product))" => "43"
+    ];
+    evals![
+        "(define (loop n acc) (if (= n 0) acc (loop (- n 1) (call-with-values (lambda () (values acc 1)) +))))" => "#<void>",
+        "(loop 20000 0)" => "20000"
+    ];
+}
+
+#[test]
+fn values_cross_continuations() {
+    evals![
+        "(call-with-values (lambda () (call/cc (lambda (k) (k 1 2)))) list)" => "(1 2)",
+        "(call-with-values (lambda () (call/cc (lambda (k) (k)))) list)" => "()",
+        "(call-with-values (lambda () (call/cc (lambda (k) (+ 1 (k 3 4))))) list)" => "(3 4)",
+        "(call/cc (lambda (k) (k 5)))" => "5"
+    ];
+    evals![
+        "(define k #f)" => "#<void>",
+        "(define result '())" => "#<void>",
+        "(set! result (call-with-values (lambda () (call/cc (lambda (c) (set! k c) (values 1 2)))) list))" => "#<void>",
+        "result" => "(1 2)",
+        "(k 3 4 5)" => "#<void>",
+        "result" => "(3 4 5)"
+    ];
+    evals![
+        "(define trail '())" => "#<void>",
+        "(call-with-values
+            (lambda ()
+              (call/cc (lambda (k)
+                (dynamic-wind
+                  (lambda () (set! trail (cons 'before trail)))
+                  (lambda () (k 'a 'b))
+                  (lambda () (set! trail (cons 'after trail)))))))
+            list)" => "(a b)",
+        "trail" => "(after before)",
+        "(call-with-values
+            (lambda () (dynamic-wind (lambda () #f) (lambda () (values 1 2)) (lambda () #f)))
+            list)" => "(1 2)"
+    ];
+}
+
+#[test]
+fn receive() {
+    evals![
+        "(receive (a b) (values 1 2) (+ a b))" => "3",
+        "(receive (a . rest) (values 1 2 3) (list a rest))" => "(1 (2 3))",
+        "(receive all (values 1 2 3) all)" => "(1 2 3)",
+        "(receive () (values) 'none)" => "none"
+    ];
+}
+
+#[test]
+fn let_values() {
+    evals![
+        "(let-values (((a b) (values 1 2)) ((c) (values 3))) (list a b c))" => "(1 2 3)",
+        "(let-values (((a . rest) (values 1 2 3)) (all (values 4 5))) (list a rest all))" => "(1 (2 3) (4 5))",
+        "(let-values () 10)" => "10"
+    ];
+    evals![
+        "(define (div-mod n d) (values (quotient n d) (remainder n d)))" => "#<void>",
+        "(let ((a 'outer) (b 'outer))
+           (let-values (((a b) (div-mod 7 2)) ((c d) (values a b)))
+             (list a b c d)))" => "(3 1 outer outer)",
+        "(let ((a 'outer) (b 'outer))
+           (let*-values (((a b) (div-mod 7 2)) ((c d) (values a b)))
+             (list a b c d)))" => "(3 1 3 1)",
+        "(let*-values (((a) (values 1)) (rest (values a 2))) rest)" => "(1 2)",
+        "(let*-values () 10)" => "10"
+    ];
+}
+
+#[test]
+fn define_values() {
+    evals![
+        "(define-values (a b) (values 1 2))" => "#<void>",
+        "(list a b)" => "(1 2)",
+        "(define-values (c . d) (values 3 4 5))" => "#<void>",
+        "(list c d)" => "(3 (4 5))",
+        "(define-values e (values 6 7))" => "#<void>",
+        "e" => "(6 7)",
+        "(define-values () (values))" => "#<void>",
+        "(define-values (f) 8)" => "#<void>",
+        "f" => "8"
+    ];
+    evals![
+        "(define (f)
+           (define-values (q r) (values (quotient 17 5) (remainder 17 5)))
+           (define s (+ q r))
+           (list q r s))" => "#<void>",
+        "(f)" => "(3 2 5)",
+        "(define (g x)
+           (define-values (a . rest) (values x (* x 2) (* x 3)))
+           (lambda () (cons a rest)))" => "#<void>",
+        "((g 2))" => "(2 4 6)"
+    ];
+    fails![
+        "(define-values (a b) (values 1 2 3))" => InvalidNumArgs("(λ (a b))".into()),
+        "(define-values (x) (values))" => InvalidNumArgs("(λ (x))".into()),
+        "(define-values (a 1) (values 1 2))" => InvalidSyntax("expected variable, but got 1".into()),
+        "(define-values (a b))" => InvalidNumArgs("define-values".into())
+    ];
+}
+
+#[test]
+fn call_with_values_errors() {
+    fails![
+        "(call-with-values 1 list)" => InvalidSyntax("bad argument to call-with-values: 1 is not a procedure".into()),
+        "(call-with-values (lambda () 1))" => InvalidNumArgs("call-with-values".into()),
+        "(call-with-values (lambda (x) x) list)" => InvalidNumArgs("(λ (x))".into())
+    ];
+}
@@ -100,3 +100,49 @@ fn vector_copy_mut() {
         "a" => "#(4 5 6)"
     ];
 }
+
+#[test]
+fn vector_ranges() {
+    evals!["(vector->list #(1 2 3 4) 1)" => "(2 3 4)",
+           "(vector->list #(1 2 3 4) 1 3)" => "(2 3)",
+           "(vector->list #(1 2 3 4) 2 2)" => "()"
+    ];
+    evals!["(define v (vector 1 2 3 4))" => "#<void>",
+           "(vector-fill! v 'x 2)" => "#<void>",
+           "v" => "#(1 2 x x)",
+           "(vector-fill! v 'y 0 1)" => "#<void>",
+           "v" => "#(y 2 x x)"
+    ];
+    fails!["(vector->list #(1 2) 3)" => InvalidVectorIndex(3, 2),
+           "(vector->list #(1 2) 0 3)" => InvalidVectorIndex(3, 2),
+           "(vector-fill! (vector 1 2) 0 2 1)" => InvalidVectorIndex(2, 2)
+    ];
+}
+
+#[test]
+fn vector_append() {
+    evals!["(vector-append)" => "#()",
+           "(vector-append #(1 2))" => "#(1 2)",
+           "(vector-append #(1 2) #() #(3 (4)))" => "#(1 2 3 (4))"
+    ];
+    fails!["(vector-append #(1) '(2))" =>
+            InvalidSyntax("(2) is not a vector".into())];
+}
+
+#[test]
+fn vector_map() {
+    evals!["(vector-map (lambda (x) (* x x)) #(1 2 3))" => "#(1 4 9)",
+           "(vector-map + #(1 2 3) #(10 20))" => "#(11 22)",
+           "(vector-map car #((a) (b)))" => "#(a b)",
+           "(vector-map (lambda (x) x) #())" => "#()"
+    ];
+    evals!["(define acc '())" => "#<void>",
+           "(vector-for-each (lambda (x y) (set! acc (cons (+ x y) acc))) #(1 2 3) #(10 20 30))" => "#<void>",
+           "acc" => "(33 22 11)"
+    ];
+    evals!["(vector-length (vector-map (lambda (x) (list x x)) (make-vector 20000 1)))" => "20000"];
+    fails!["(vector-map 1 #(1))" =>
+            InvalidSyntax("bad argument to vector-map: 1 is not a procedure".into()),
+           "(vector-for-each car #(1))" => ExpectedPairButFound(cell![1])
+    ];
+}